@@ -3,13 +3,16 @@ use rusqlite::Connection;
 use std::path::Path;
 use std::sync::Mutex;
 
+use crate::error::TlError;
 use crate::paths;
 use crate::translation::TranslationRequest;
 
 /// Manages translation caching using a `SQLite` database.
 ///
 /// The cache stores translations keyed by source text, target language,
-/// model, endpoint, and prompt hash to avoid redundant API calls.
+/// model, endpoint, and prompt hash to avoid redundant API calls. It also
+/// holds a translation memory of preferred translations, such as
+/// corrections made by hand, which are looked up separately.
 ///
 /// The connection is held in a `Mutex` for thread safety and reused across
 /// all cache operations to avoid the overhead of opening a new connection
@@ -19,15 +22,18 @@ use crate::translation::TranslationRequest;
 ///
 /// ```no_run
 /// use tl_cli::cache::CacheManager;
-/// use tl_cli::translation::TranslationRequest;
+/// use tl_cli::translation::{PromptOptions, TranslationRequest};
 ///
 /// let cache = CacheManager::new().unwrap();
 /// let request = TranslationRequest {
 ///     source_text: "Hello".to_string(),
 ///     target_language: "ja".to_string(),
+///     source_language: None,
+///     source_language_detected: false,
 ///     model: "gpt-4".to_string(),
 ///     endpoint: "https://api.openai.com".to_string(),
 ///     style: None,
+///     prompt: PromptOptions::default(),
 /// };
 ///
 /// // Check cache
@@ -48,7 +54,10 @@ impl CacheManager {
         let cache_dir = paths::cache_dir()?;
 
         std::fs::create_dir_all(&cache_dir).with_context(|| {
-            format!("Failed to create cache directory: {}", cache_dir.display())
+            TlError::Io(format!(
+                "Failed to create cache directory: {}",
+                cache_dir.display()
+            ))
         })?;
 
         let db_path = cache_dir.join("translations.db");
@@ -58,9 +67,13 @@ impl CacheManager {
     /// Creates a new cache manager with a specific database path.
     ///
     /// This is useful for testing with a custom database location.
-    fn with_path(db_path: &Path) -> Result<Self> {
-        let conn = Connection::open(db_path)
-            .with_context(|| format!("Failed to open cache database: {}", db_path.display()))?;
+    pub fn with_path(db_path: &Path) -> Result<Self> {
+        let conn = Connection::open(db_path).with_context(|| {
+            TlError::Io(format!(
+                "Failed to open cache database: {}",
+                db_path.display()
+            ))
+        })?;
 
         let manager = Self {
             conn: Mutex::new(conn),
@@ -75,7 +88,7 @@ impl CacheManager {
         let conn = self
             .conn
             .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire database lock: {e}"))?;
+            .map_err(|e| TlError::Io(format!("Failed to acquire database lock: {e}")))?;
 
         conn.execute(
             "CREATE TABLE IF NOT EXISTS translations (
@@ -92,13 +105,41 @@ impl CacheManager {
             )",
             [],
         )
-        .context("Failed to create translations table")?;
+        .with_context(|| TlError::Io("Failed to create translations table".to_string()))?;
 
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_cache_key ON translations(cache_key)",
             [],
         )
-        .context("Failed to create index")?;
+        .with_context(|| TlError::Io("Failed to create index".to_string()))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS completions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                cache_key TEXT UNIQUE NOT NULL,
+                content TEXT NOT NULL,
+                model TEXT NOT NULL,
+                endpoint TEXT NOT NULL,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                accessed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )
+        .with_context(|| TlError::Io("Failed to create completions table".to_string()))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS translation_memory (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                memory_key TEXT UNIQUE NOT NULL,
+                source_text TEXT NOT NULL,
+                translated_text TEXT NOT NULL,
+                target_language TEXT NOT NULL,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                accessed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )
+        .with_context(|| TlError::Io("Failed to create translation memory table".to_string()))?;
 
         drop(conn);
         Ok(())
@@ -113,11 +154,12 @@ impl CacheManager {
         let conn = self
             .conn
             .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire database lock: {e}"))?;
+            .map_err(|e| TlError::Io(format!("Failed to acquire database lock: {e}")))?;
 
         let result: Option<String> = {
-            let mut stmt =
-                conn.prepare("SELECT translated_text FROM translations WHERE cache_key = ?1")?;
+            let mut stmt = conn
+                .prepare("SELECT translated_text FROM translations WHERE cache_key = ?1")
+                .with_context(|| TlError::Io("Failed to query cache".to_string()))?;
             stmt.query_row([&cache_key], |row| row.get(0)).ok()
         };
 
@@ -125,7 +167,8 @@ impl CacheManager {
             conn.execute(
                 "UPDATE translations SET accessed_at = CURRENT_TIMESTAMP WHERE cache_key = ?1",
                 [&cache_key],
-            )?;
+            )
+            .with_context(|| TlError::Io("Failed to update cache access time".to_string()))?;
         }
 
         drop(conn);
@@ -137,11 +180,11 @@ impl CacheManager {
     /// If a translation with the same cache key already exists, it is replaced.
     pub fn put(&self, request: &TranslationRequest, translated_text: &str) -> Result<()> {
         let cache_key = request.cache_key();
-        let prompt_hash = TranslationRequest::prompt_hash();
+        let prompt_hash = request.prompt_hash();
         let conn = self
             .conn
             .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to acquire database lock: {e}"))?;
+            .map_err(|e| TlError::Io(format!("Failed to acquire database lock: {e}")))?;
 
         conn.execute(
             "INSERT OR REPLACE INTO translations
@@ -157,7 +200,125 @@ impl CacheManager {
                 &prompt_hash,
             ],
         )
-        .context("Failed to insert translation into cache")?;
+        .with_context(|| TlError::Io("Failed to insert translation into cache".to_string()))?;
+
+        drop(conn);
+        Ok(())
+    }
+
+    /// Retrieves the preferred translation for a request, if any.
+    ///
+    /// Preferred translations apply to any model and endpoint, but only
+    /// with the same style and prompt (see
+    /// [`TranslationRequest::memory_key`]). Updates the `accessed_at`
+    /// timestamp on a hit.
+    pub fn get_preferred(&self, request: &TranslationRequest) -> Result<Option<String>> {
+        let memory_key = request.memory_key();
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| TlError::Io(format!("Failed to acquire database lock: {e}")))?;
+
+        let result: Option<String> = {
+            let mut stmt = conn
+                .prepare("SELECT translated_text FROM translation_memory WHERE memory_key = ?1")
+                .with_context(|| TlError::Io("Failed to query translation memory".to_string()))?;
+            stmt.query_row([&memory_key], |row| row.get(0)).ok()
+        };
+
+        if result.is_some() {
+            conn.execute(
+                "UPDATE translation_memory SET accessed_at = CURRENT_TIMESTAMP WHERE memory_key = ?1",
+                [&memory_key],
+            )
+            .with_context(|| TlError::Io("Failed to update cache access time".to_string()))?;
+        }
+
+        drop(conn);
+        Ok(result)
+    }
+
+    /// Stores a preferred translation, such as a correction made by hand.
+    ///
+    /// It replaces any earlier preferred translation with the same memory
+    /// key, and is returned by [`Self::get_preferred`]. Cached machine
+    /// translations are not affected.
+    pub fn put_preferred(&self, request: &TranslationRequest, translated_text: &str) -> Result<()> {
+        let memory_key = request.memory_key();
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| TlError::Io(format!("Failed to acquire database lock: {e}")))?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO translation_memory
+             (memory_key, source_text, translated_text, target_language)
+             VALUES (?1, ?2, ?3, ?4)",
+            [
+                &memory_key,
+                &request.source_text,
+                translated_text,
+                &request.target_language,
+            ],
+        )
+        .with_context(|| {
+            TlError::Io("Failed to insert translation into translation memory".to_string())
+        })?;
+
+        drop(conn);
+        Ok(())
+    }
+
+    /// Retrieves a cached chat completion by key.
+    ///
+    /// Used by `tl proxy` for arbitrary chat requests, which are keyed by
+    /// the caller rather than by a `TranslationRequest`.
+    pub fn get_completion(&self, cache_key: &str) -> Result<Option<String>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| TlError::Io(format!("Failed to acquire database lock: {e}")))?;
+
+        let result: Option<String> = {
+            let mut stmt = conn
+                .prepare("SELECT content FROM completions WHERE cache_key = ?1")
+                .with_context(|| TlError::Io("Failed to query cache".to_string()))?;
+            stmt.query_row([cache_key], |row| row.get(0)).ok()
+        };
+
+        if result.is_some() {
+            conn.execute(
+                "UPDATE completions SET accessed_at = CURRENT_TIMESTAMP WHERE cache_key = ?1",
+                [cache_key],
+            )
+            .with_context(|| TlError::Io("Failed to update cache access time".to_string()))?;
+        }
+
+        drop(conn);
+        Ok(result)
+    }
+
+    /// Stores the assistant content of a chat completion.
+    ///
+    /// If a completion with the same cache key already exists, it is replaced.
+    pub fn put_completion(
+        &self,
+        cache_key: &str,
+        model: &str,
+        endpoint: &str,
+        content: &str,
+    ) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| TlError::Io(format!("Failed to acquire database lock: {e}")))?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO completions (cache_key, content, model, endpoint)
+             VALUES (?1, ?2, ?3, ?4)",
+            [cache_key, content, model, endpoint],
+        )
+        .with_context(|| TlError::Io("Failed to insert completion into cache".to_string()))?;
 
         drop(conn);
         Ok(())
@@ -168,6 +329,8 @@ impl CacheManager {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::error::find_tl_error;
+    use crate::translation::PromptOptions;
     use tempfile::TempDir;
 
     fn create_test_manager(temp_dir: &TempDir) -> CacheManager {
@@ -179,12 +342,27 @@ mod tests {
         TranslationRequest {
             source_text: "Hello, World!".to_string(),
             target_language: "ja".to_string(),
+            source_language: None,
+            source_language_detected: false,
             model: "gpt-oss:20b".to_string(),
             endpoint: "http://localhost:11434".to_string(),
             style: None,
+            prompt: PromptOptions::default(),
         }
     }
 
+    #[test]
+    fn test_open_failure_is_io_error() {
+        let temp_dir = TempDir::new().unwrap();
+        // A path inside a missing directory cannot be opened as a database
+        let db_path = temp_dir.path().join("missing").join("translations.db");
+
+        let Err(err) = CacheManager::with_path(&db_path) else {
+            panic!("expected an error");
+        };
+        assert!(matches!(find_tl_error(&err), Some(TlError::Io(_))));
+    }
+
     #[test]
     fn test_cache_miss() {
         let temp_dir = TempDir::new().unwrap();
@@ -215,17 +393,23 @@ mod tests {
         let request1 = TranslationRequest {
             source_text: "Hello".to_string(),
             target_language: "ja".to_string(),
+            source_language: None,
+            source_language_detected: false,
             model: "model1".to_string(),
             endpoint: "http://localhost:11434".to_string(),
             style: None,
+            prompt: PromptOptions::default(),
         };
 
         let request2 = TranslationRequest {
             source_text: "Hello".to_string(),
             target_language: "en".to_string(),
+            source_language: None,
+            source_language_detected: false,
             model: "model1".to_string(),
             endpoint: "http://localhost:11434".to_string(),
             style: None,
+            prompt: PromptOptions::default(),
         };
 
         manager.put(&request1, "Translation 1").unwrap();
@@ -241,6 +425,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_preferred_translation_is_kept_apart_from_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_test_manager(&temp_dir);
+        let request = create_test_request();
+
+        manager.put(&request, "こんにちは、世界！").unwrap();
+        manager.put_preferred(&request, "こんにちは世界").unwrap();
+        // Plain cache lookups (compare, chat, serve) never see it
+        assert_eq!(
+            manager.get(&request).unwrap(),
+            Some("こんにちは、世界！".to_string())
+        );
+        assert_eq!(
+            manager.get_preferred(&request).unwrap(),
+            Some("こんにちは世界".to_string())
+        );
+    }
+
+    #[test]
+    fn test_preferred_translation_is_scoped_to_style_and_language() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_test_manager(&temp_dir);
+        manager
+            .put_preferred(&create_test_request(), "こんにちは世界")
+            .unwrap();
+
+        let other_model = TranslationRequest {
+            model: "other".to_string(),
+            ..create_test_request()
+        };
+        assert_eq!(
+            manager.get_preferred(&other_model).unwrap(),
+            Some("こんにちは世界".to_string())
+        );
+
+        let other_style = TranslationRequest {
+            style: Some("Be casual.".to_string()),
+            ..create_test_request()
+        };
+        assert!(manager.get_preferred(&other_style).unwrap().is_none());
+
+        let other_language = TranslationRequest {
+            target_language: "ko".to_string(),
+            ..create_test_request()
+        };
+        assert!(manager.get_preferred(&other_language).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_completion_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_test_manager(&temp_dir);
+
+        assert!(manager.get_completion("key").unwrap().is_none());
+
+        manager
+            .put_completion("key", "model1", "http://localhost:11434", "Hi there")
+            .unwrap();
+        assert_eq!(
+            manager.get_completion("key").unwrap(),
+            Some("Hi there".to_string())
+        );
+
+        // Completions do not collide with translations
+        assert!(manager.get(&create_test_request()).unwrap().is_none());
+    }
+
     #[test]
     fn test_cache_key_includes_endpoint() {
         let temp_dir = TempDir::new().unwrap();
@@ -249,17 +501,23 @@ mod tests {
         let request1 = TranslationRequest {
             source_text: "Hello".to_string(),
             target_language: "ja".to_string(),
+            source_language: None,
+            source_language_detected: false,
             model: "model1".to_string(),
             endpoint: "http://localhost:11434".to_string(),
             style: None,
+            prompt: PromptOptions::default(),
         };
 
         let request2 = TranslationRequest {
             source_text: "Hello".to_string(),
             target_language: "ja".to_string(),
+            source_language: None,
+            source_language_detected: false,
             model: "model1".to_string(),
             endpoint: "http://production:11434".to_string(),
             style: None,
+            prompt: PromptOptions::default(),
         };
 
         manager.put(&request1, "Local Translation").unwrap();
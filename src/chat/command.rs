@@ -1,28 +1,107 @@
 use inquire::autocompletion::{Autocomplete, Replacement};
 
+use super::input::search;
+
 // Available slash commands: (command, description)
 const SLASH_COMMANDS: &[(&str, &str)] = &[
+    ("/clear", "Forget earlier messages (context)"),
+    (
+        "/compare",
+        "Translate the last message with other models (e.g. gpt-4o,llama3.2)",
+    ),
     ("/config", "Show current configuration"),
+    ("/edit", "Write multi-line text in $EDITOR"),
     ("/help", "Show available commands"),
+    ("/paste", "Paste multi-line text (end with a line '.')"),
     ("/quit", "Exit chat mode"),
-    ("/set", "Set option (style, to, model)"),
+    ("/save", "Save the transcript (.json or Markdown)"),
+    (
+        "/set",
+        "Set option (style, to, from, pair, provider, model, context, cache)",
+    ),
+];
+
+/// Keys accepted by `/set`.
+pub const SET_KEYS: &[&str] = &[
+    "style", "to", "from", "pair", "provider", "model", "context", "cache",
 ];
 
-/// Slash command autocompleter
+/// Maximum number of input history matches suggested at once.
+const MAX_HISTORY_SUGGESTIONS: usize = 50;
+
+/// Slash command and input history autocompleter
+///
+/// Input starting with `/` is completed to a command; other input
+/// searches earlier lines (most recent first).
 #[derive(Clone, Default)]
-pub struct SlashCommandCompleter;
+pub struct SlashCommandCompleter {
+    history: Vec<String>,
+    /// Values suggested after `/set <key> `.
+    values: Vec<(&'static str, Vec<String>)>,
+}
+
+impl SlashCommandCompleter {
+    /// Creates a completer that also suggests earlier input lines.
+    pub fn with_history(history: &[String]) -> Self {
+        Self {
+            history: history.to_vec(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Suggests `values` for `/set <key>`.
+    #[must_use]
+    pub fn with_values(mut self, key: &'static str, values: Vec<String>) -> Self {
+        self.values.push((key, values));
+        self
+    }
+
+    /// Completes the key or value of a `/set` command.
+    fn set_suggestions(&self, args: &str) -> Vec<String> {
+        let args = args.trim_start();
+        let Some((key, partial)) = args.split_once(' ') else {
+            return SET_KEYS
+                .iter()
+                .filter(|key| key.starts_with(args))
+                .map(|key| format!("/set {key}"))
+                .collect();
+        };
+
+        let partial = partial.trim_start().to_lowercase();
+        self.values
+            .iter()
+            .filter(|(k, _)| *k == key)
+            .flat_map(|(_, values)| values)
+            .filter(|value| value.to_lowercase().starts_with(&partial))
+            .map(|value| format!("/set {key} {value}"))
+            .collect()
+    }
+}
 
 impl Autocomplete for SlashCommandCompleter {
     fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, inquire::CustomUserError> {
-        if !input.starts_with('/') {
+        if input.is_empty() {
             return Ok(vec![]);
         }
 
-        let suggestions: Vec<String> = SLASH_COMMANDS
-            .iter()
-            .filter(|(cmd, _)| cmd.starts_with(input))
-            .map(|(cmd, desc)| format!("{cmd}  {desc}"))
+        let mut suggestions: Vec<String> = match input.strip_prefix("/set ") {
+            Some(args) => self.set_suggestions(args),
+            None if input.starts_with('/') => SLASH_COMMANDS
+                .iter()
+                .filter(|(cmd, _)| cmd.starts_with(input))
+                .map(|(cmd, desc)| format!("{cmd}  {desc}"))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let history: Vec<String> = search(&self.history, input)
+            .into_iter()
+            .filter(|line| line.starts_with('/') == input.starts_with('/'))
+            .filter(|line| !suggestions.iter().any(|s| s == line))
+            .take(MAX_HISTORY_SUGGESTIONS)
+            .map(str::to_string)
             .collect();
+        suggestions.extend(history);
 
         Ok(suggestions)
     }
@@ -32,8 +111,13 @@ impl Autocomplete for SlashCommandCompleter {
         _input: &str,
         highlighted_suggestion: Option<String>,
     ) -> Result<Replacement, inquire::CustomUserError> {
-        let replacement =
-            highlighted_suggestion.map(|s| s.split_whitespace().next().unwrap_or("").to_string());
+        // Command suggestions carry a description; history lines are used as is
+        let replacement = highlighted_suggestion.map(|s| {
+            SLASH_COMMANDS
+                .iter()
+                .find(|(cmd, desc)| s == format!("{cmd}  {desc}"))
+                .map_or(s, |(cmd, _)| (*cmd).to_string())
+        });
         Ok(replacement)
     }
 }
@@ -41,9 +125,14 @@ impl Autocomplete for SlashCommandCompleter {
 /// Slash command types
 #[derive(Debug, Clone)]
 pub enum SlashCommand {
+    Clear,
+    Compare(Option<String>),
     Config,
+    Edit,
     Help,
+    Paste,
     Quit,
+    Save(Option<String>),
     Set { key: String, value: Option<String> },
     Unknown(String),
 }
@@ -63,6 +152,12 @@ pub fn parse_input(input: &str) -> Input {
         return Input::Empty;
     }
 
+    // A highlighted suggestion is submitted with its description
+    let input = SLASH_COMMANDS
+        .iter()
+        .find(|(cmd, desc)| input == format!("{cmd}  {desc}"))
+        .map_or(input, |(cmd, _)| cmd);
+
     input
         .strip_prefix('/')
         .map_or_else(|| Input::Text(input.to_string()), parse_slash_command)
@@ -72,9 +167,24 @@ fn parse_slash_command(cmd: &str) -> Input {
     let parts: Vec<&str> = cmd.split_whitespace().collect();
 
     match parts.first().copied() {
+        Some("clear") => Input::Command(SlashCommand::Clear),
+        Some("compare") => Input::Command(SlashCommand::Compare(
+            parts
+                .get(1..)
+                .map(|models| models.join(""))
+                .filter(|m| !m.is_empty()),
+        )),
         Some("config") => Input::Command(SlashCommand::Config),
+        Some("edit") => Input::Command(SlashCommand::Edit),
         Some("help") => Input::Command(SlashCommand::Help),
+        Some("paste") => Input::Command(SlashCommand::Paste),
         Some("quit" | "exit" | "q") => Input::Command(SlashCommand::Quit),
+        Some("save") => {
+            let file = cmd.trim_start()["save".len()..].trim();
+            Input::Command(SlashCommand::Save(
+                (!file.is_empty()).then(|| file.to_string()),
+            ))
+        }
         Some("set") => {
             let key = parts.get(1).map(|s| (*s).to_string()).unwrap_or_default();
             let value = parts.get(2).map(|s| (*s).to_string());
@@ -111,6 +221,14 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_clear_command() {
+        assert!(matches!(
+            parse_input("/clear"),
+            Input::Command(SlashCommand::Clear)
+        ));
+    }
+
     #[test]
     fn test_parse_help_command() {
         assert!(matches!(
@@ -143,6 +261,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_save_command() {
+        match parse_input("/save my notes.md") {
+            Input::Command(SlashCommand::Save(file)) => {
+                assert_eq!(file.as_deref(), Some("my notes.md"));
+            }
+            _ => panic!("Expected Input::Command(SlashCommand::Save)"),
+        }
+        assert!(matches!(
+            parse_input("/save"),
+            Input::Command(SlashCommand::Save(None))
+        ));
+        // Submitting the highlighted suggestion does not pass its description
+        assert!(matches!(
+            parse_input("/save  Save the transcript (.json or Markdown)"),
+            Input::Command(SlashCommand::Save(None))
+        ));
+    }
+
+    #[test]
+    fn test_parse_compare_command() {
+        match parse_input("/compare gpt-4o, llama3.2") {
+            Input::Command(SlashCommand::Compare(models)) => {
+                assert_eq!(models.as_deref(), Some("gpt-4o,llama3.2"));
+            }
+            _ => panic!("Expected Input::Command(SlashCommand::Compare)"),
+        }
+        assert!(matches!(
+            parse_input("/compare"),
+            Input::Command(SlashCommand::Compare(None))
+        ));
+    }
+
+    #[test]
+    fn test_parse_multiline_commands() {
+        assert!(matches!(
+            parse_input("/paste"),
+            Input::Command(SlashCommand::Paste)
+        ));
+        assert!(matches!(
+            parse_input("/edit"),
+            Input::Command(SlashCommand::Edit)
+        ));
+    }
+
     // /set command tests
 
     #[test]
@@ -215,26 +378,32 @@ mod tests {
 
     #[test]
     fn test_completer_no_suggestions_for_regular_text() {
-        let mut completer = SlashCommandCompleter;
+        let mut completer = SlashCommandCompleter::default();
         let suggestions = completer.get_suggestions("hello").unwrap();
         assert!(suggestions.is_empty());
     }
 
     #[test]
     fn test_completer_suggestions_for_slash() {
-        let mut completer = SlashCommandCompleter;
+        let mut completer = SlashCommandCompleter::default();
         let suggestions = completer.get_suggestions("/").unwrap();
-        assert_eq!(suggestions.len(), 4); // /config, /help, /quit, /set
+        assert_eq!(suggestions.len(), 9); // /clear, /compare, /config, /edit, /help, /paste, /quit, /save, /set
     }
 
     #[test]
     fn test_completer_suggestions_filter_by_prefix() {
-        let mut completer = SlashCommandCompleter;
+        let mut completer = SlashCommandCompleter::default();
 
         let suggestions = completer.get_suggestions("/c").unwrap();
+        assert_eq!(suggestions.len(), 3);
+
+        let suggestions = completer.get_suggestions("/con").unwrap();
         assert_eq!(suggestions.len(), 1);
         assert!(suggestions[0].starts_with("/config"));
 
+        let suggestions = completer.get_suggestions("/s").unwrap();
+        assert_eq!(suggestions.len(), 2);
+
         let suggestions = completer.get_suggestions("/q").unwrap();
         assert_eq!(suggestions.len(), 1);
         assert!(suggestions[0].starts_with("/quit"));
@@ -242,15 +411,69 @@ mod tests {
 
     #[test]
     fn test_completer_completion() {
-        let mut completer = SlashCommandCompleter;
+        let mut completer = SlashCommandCompleter::default();
         let suggestion = "/config  Show current configuration".to_string();
         let completion = completer.get_completion("/c", Some(suggestion)).unwrap();
         assert_eq!(completion, Some("/config".to_string()));
     }
 
+    #[test]
+    fn test_completer_suggests_history() {
+        let history: Vec<String> = ["Good morning", "/set to fr", "Good night"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let mut completer = SlashCommandCompleter::with_history(&history);
+
+        assert!(completer.get_suggestions("").unwrap().is_empty());
+        assert_eq!(
+            completer.get_suggestions("good").unwrap(),
+            ["Good night", "Good morning"]
+        );
+        let suggestions = completer.get_suggestions("/set").unwrap();
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[1], "/set to fr");
+
+        // History lines are completed in full
+        let completion = completer
+            .get_completion("/set", Some("/set to fr".to_string()))
+            .unwrap();
+        assert_eq!(completion, Some("/set to fr".to_string()));
+    }
+
+    #[test]
+    fn test_completer_suggests_set_keys_and_values() {
+        let mut completer = SlashCommandCompleter::default()
+            .with_values("style", vec!["casual".to_string(), "formal".to_string()])
+            .with_values("cache", vec!["on".to_string(), "off".to_string()]);
+
+        assert_eq!(
+            completer.get_suggestions("/set st").unwrap(),
+            ["/set style"]
+        );
+        assert_eq!(
+            completer.get_suggestions("/set ").unwrap().len(),
+            SET_KEYS.len()
+        );
+        assert_eq!(
+            completer.get_suggestions("/set style ").unwrap(),
+            ["/set style casual", "/set style formal"]
+        );
+        assert_eq!(
+            completer.get_suggestions("/set style F").unwrap(),
+            ["/set style formal"]
+        );
+        assert!(completer.get_suggestions("/set to j").unwrap().is_empty());
+
+        let completion = completer
+            .get_completion("/set cache o", Some("/set cache off".to_string()))
+            .unwrap();
+        assert_eq!(completion, Some("/set cache off".to_string()));
+    }
+
     #[test]
     fn test_completer_completion_none() {
-        let mut completer = SlashCommandCompleter;
+        let mut completer = SlashCommandCompleter::default();
         let completion = completer.get_completion("/x", None).unwrap();
         assert!(completion.is_none());
     }
@@ -0,0 +1,274 @@
+//! Persistent chat history and transcripts.
+//!
+//! Each chat session is saved as a JSON transcript under
+//! `$XDG_CACHE_HOME/tl/chat/` after every translation, so that
+//! `tl chat --resume` can pick up the last session's settings and context.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::TlError;
+use crate::fs::atomic_write;
+use crate::paths;
+
+/// Number of session transcripts kept in the history directory.
+const MAX_SESSIONS: usize = 20;
+
+/// Chat settings at the time a transcript was last saved.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSettings {
+    pub provider: String,
+    pub model: String,
+    pub to: String,
+    pub from: Option<String>,
+    pub pair: Option<String>,
+    pub style: Option<String>,
+    pub context: usize,
+}
+
+/// One translated message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    /// Unix time of the translation, in seconds.
+    pub timestamp: u64,
+    pub source_language: Option<String>,
+    pub target_language: String,
+    pub model: String,
+    pub style: Option<String>,
+    pub source: String,
+    pub translation: String,
+}
+
+/// A chat session: its current settings and every translated message.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transcript {
+    /// Unix time the session started, in seconds.
+    pub started_at: u64,
+    pub settings: SessionSettings,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    /// Creates an empty transcript starting now.
+    pub fn new(settings: SessionSettings) -> Self {
+        Self {
+            started_at: now(),
+            settings,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Loads the most recent saved session, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history directory or the transcript
+    /// cannot be read.
+    pub fn load_latest() -> Result<Option<(PathBuf, Self)>> {
+        let Some(path) = session_files(&history_dir()?)?.pop() else {
+            return Ok(None);
+        };
+        let contents = fs::read_to_string(&path).with_context(|| {
+            TlError::Io(format!("Failed to read chat history: {}", path.display()))
+        })?;
+        let transcript = serde_json::from_str(&contents)
+            .with_context(|| TlError::Input(format!("Invalid chat history: {}", path.display())))?;
+        Ok(Some((path, transcript)))
+    }
+
+    /// Writes the transcript to `path` as JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize chat history")?;
+        atomic_write(&path.to_string_lossy(), &json)
+            .with_context(|| TlError::Io(format!("Failed to write {}", path.display())))
+    }
+
+    /// Exports the transcript to `file`: JSON for `.json`, Markdown otherwise.
+    pub fn export(&self, file: &str) -> Result<()> {
+        let is_json = Path::new(file)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            return self.save(Path::new(file));
+        }
+        atomic_write(file, &self.to_markdown())
+            .with_context(|| TlError::Io(format!("Failed to write {file}")))
+    }
+
+    /// Renders the transcript as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let settings = &self.settings;
+        let mut out = String::from("# tl chat transcript\n\n");
+        let _ = writeln!(out, "- Provider: {}", settings.provider);
+        let _ = writeln!(out, "- Model: {}", settings.model);
+        if let Some(ref pair) = settings.pair {
+            let _ = writeln!(out, "- Pair: {pair}");
+        } else {
+            let _ = writeln!(out, "- To: {}", settings.to);
+        }
+        if let Some(ref style) = settings.style {
+            let _ = writeln!(out, "- Style: {style}");
+        }
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            let source = entry.source_language.as_deref().unwrap_or("auto");
+            let _ = write!(
+                out,
+                "\n## {}. {source} → {}\n\n",
+                index + 1,
+                entry.target_language
+            );
+            for line in entry.source.lines() {
+                let _ = writeln!(out, "> {line}");
+            }
+            let _ = write!(out, "\n{}\n", entry.translation.trim_end());
+        }
+        out
+    }
+}
+
+/// Returns the directory holding saved chat sessions.
+pub fn history_dir() -> Result<PathBuf> {
+    Ok(paths::cache_dir()?.join("chat"))
+}
+
+/// Returns a path for a new session and removes the oldest sessions
+/// beyond the retention limit.
+pub fn new_session_path() -> Result<PathBuf> {
+    let dir = history_dir()?;
+    fs::create_dir_all(&dir).with_context(|| {
+        TlError::Io(format!(
+            "Failed to create chat history directory: {}",
+            dir.display()
+        ))
+    })?;
+
+    let sessions = session_files(&dir)?;
+    let excess = (sessions.len() + 1).saturating_sub(MAX_SESSIONS);
+    for old in &sessions[..excess] {
+        let _ = fs::remove_file(old);
+    }
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    Ok(dir.join(format!("session-{millis:016}.json")))
+}
+
+/// Returns the saved session files, oldest first.
+fn session_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(anyhow::Error::new(e).context(TlError::Io(format!(
+                "Failed to read chat history directory: {}",
+                dir.display()
+            ))));
+        }
+    };
+    // Names embed a zero-padded timestamp, so they sort chronologically
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let is_session = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| stem.starts_with("session-"));
+            is_session && path.extension().is_some_and(|ext| ext == "json")
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the current Unix time in seconds.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn transcript() -> Transcript {
+        let mut transcript = Transcript::new(SessionSettings {
+            provider: "ollama".to_string(),
+            model: "gemma3:12b".to_string(),
+            to: "ja".to_string(),
+            style: Some("casual".to_string()),
+            ..SessionSettings::default()
+        });
+        transcript.entries.push(TranscriptEntry {
+            timestamp: 0,
+            source_language: Some("en".to_string()),
+            target_language: "ja".to_string(),
+            model: "gemma3:12b".to_string(),
+            style: Some("casual".to_string()),
+            source: "Hello\nworld".to_string(),
+            translation: "こんにちは\n世界\n".to_string(),
+        });
+        transcript
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let markdown = transcript().to_markdown();
+        assert!(markdown.starts_with("# tl chat transcript\n"));
+        assert!(markdown.contains("- Style: casual\n"));
+        assert!(markdown.contains("## 1. en → ja\n\n> Hello\n> world\n\nこんにちは\n世界\n"));
+    }
+
+    #[test]
+    fn test_export_by_extension() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_path = temp_dir.path().join("chat.json");
+        let md_path = temp_dir.path().join("chat.md");
+
+        transcript().export(json_path.to_str().unwrap()).unwrap();
+        transcript().export(md_path.to_str().unwrap()).unwrap();
+
+        let json = fs::read_to_string(&json_path).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Transcript>(&json).unwrap(),
+            transcript_with_started_at(&json)
+        );
+        assert!(fs::read_to_string(&md_path).unwrap().contains("> Hello"));
+    }
+
+    fn transcript_with_started_at(json: &str) -> Transcript {
+        let saved: Transcript = serde_json::from_str(json).unwrap();
+        Transcript {
+            started_at: saved.started_at,
+            ..transcript()
+        }
+    }
+
+    #[test]
+    fn test_session_files_sorted_and_filtered() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["session-2.json", "session-1.json", "other.json"] {
+            fs::write(temp_dir.path().join(name), "{}").unwrap();
+        }
+        let files = session_files(temp_dir.path()).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["session-1.json", "session-2.json"]);
+        assert!(
+            session_files(&temp_dir.path().join("missing"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
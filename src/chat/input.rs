@@ -0,0 +1,178 @@
+//! Chat input: persistent input history and multi-line entry.
+
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::TlError;
+use crate::fs::atomic_write;
+use crate::paths;
+
+/// Maximum number of lines kept in the input history file.
+const MAX_ENTRIES: usize = 1000;
+
+/// Line that ends `/paste` input.
+pub const PASTE_TERMINATOR: &str = ".";
+
+/// Lines typed at the chat prompt, oldest first.
+///
+/// Saved to `$XDG_CACHE_HOME/tl/chat_input_history` so they are
+/// available again in later sessions.
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl InputHistory {
+    /// Loads the saved input history and keeps appending to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file exists but cannot be read
+    /// or trimmed.
+    pub fn load() -> Result<Self> {
+        let path = paths::cache_dir()?.join("chat_input_history");
+        let mut entries: Vec<String> = match fs::read_to_string(&path) {
+            Ok(contents) => contents.lines().map(str::to_string).collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(anyhow::Error::new(e).context(TlError::Io(format!(
+                    "Failed to read input history: {}",
+                    path.display()
+                ))));
+            }
+        };
+
+        if entries.len() > MAX_ENTRIES {
+            entries.drain(..entries.len() - MAX_ENTRIES);
+            let mut contents = entries.join("\n");
+            contents.push('\n');
+            atomic_write(&path.to_string_lossy(), &contents)
+                .with_context(|| TlError::Io(format!("Failed to write {}", path.display())))?;
+        }
+
+        Ok(Self {
+            entries,
+            path: Some(path),
+        })
+    }
+
+    /// Records a line typed at the prompt.
+    ///
+    /// Multi-line text and repeats of the previous line are not recorded.
+    pub fn add(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || line.contains('\n') || self.entries.last().is_some_and(|l| l == line)
+        {
+            return;
+        }
+        self.entries.push(line.to_string());
+
+        if let Some(ref path) = self.path
+            && let Err(e) = append_line(path, line)
+        {
+            crate::warn!("Failed to save input history: {e:#}");
+            // Don't warn again for every line
+            self.path = None;
+        }
+    }
+
+    /// Returns the recorded lines, oldest first.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+}
+
+fn append_line(path: &Path, line: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+/// Returns earlier lines containing `query` (case-insensitive), most
+/// recent first and without duplicates.
+pub fn search<'a>(entries: &'a [String], query: &str) -> Vec<&'a str> {
+    let lowercase = query.to_lowercase();
+    let mut matches: Vec<&str> = Vec::new();
+    for entry in entries.iter().rev() {
+        if entry != query
+            && entry.to_lowercase().contains(&lowercase)
+            && !matches.contains(&entry.as_str())
+        {
+            matches.push(entry);
+        }
+    }
+    matches
+}
+
+/// Reads lines until a line with only `.` or end of input.
+///
+/// # Errors
+///
+/// Returns an error if standard input cannot be read.
+pub fn read_paste(reader: impl BufRead) -> Result<String> {
+    let mut lines = Vec::new();
+    for line in reader.lines() {
+        let line = line.context("Failed to read pasted text")?;
+        if line.trim_end() == PASTE_TERMINATOR {
+            break;
+        }
+        lines.push(line);
+    }
+    Ok(lines.join("\n").trim().to_string())
+}
+
+/// Opens `$EDITOR` for text to translate.
+///
+/// # Errors
+///
+/// Returns an error if the editor cannot be started.
+pub fn read_editor() -> Result<String> {
+    let text = inquire::Editor::new("Text to translate (opens editor):")
+        .with_help_message("Save and close the editor to translate")
+        .prompt()?;
+    Ok(text.trim().to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_skips_empty_multiline_and_repeats() {
+        let mut history = InputHistory::default();
+        history.add("Hello");
+        history.add("Hello");
+        history.add("  ");
+        history.add("one\ntwo");
+        history.add("/set to fr");
+        assert_eq!(history.entries(), ["Hello", "/set to fr"]);
+    }
+
+    #[test]
+    fn test_search_most_recent_first() {
+        let entries: Vec<String> = ["Good morning", "Hello", "good night", "Good morning"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(search(&entries, "good"), ["Good morning", "good night"]);
+        assert_eq!(search(&entries, "Hello"), Vec::<&str>::new());
+        assert_eq!(search(&entries, "hel"), ["Hello"]);
+    }
+
+    #[test]
+    fn test_read_paste_stops_at_terminator() {
+        let input = "Dear team,\n\nThanks!\n.\nnot read\n";
+        assert_eq!(
+            read_paste(input.as_bytes()).unwrap(),
+            "Dear team,\n\nThanks!"
+        );
+        assert_eq!(read_paste(&b"a\nb"[..]).unwrap(), "a\nb");
+    }
+}
@@ -4,6 +4,10 @@
 
 /// Slash command parsing and autocomplete.
 pub mod command;
+/// Saved chat sessions and transcript export.
+pub mod history;
+/// Input history and multi-line input.
+pub mod input;
 mod session;
 mod ui;
 
@@ -2,16 +2,25 @@ use anyhow::Result;
 use futures_util::StreamExt;
 use inquire::Text;
 use inquire::ui::{Attributes, Color, RenderConfig, StyleSheet, Styled};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::io::{self, Write};
+use std::path::PathBuf;
 
-use super::command::{Input, SlashCommand, SlashCommandCompleter, parse_input};
+use super::command::{Input, SET_KEYS, SlashCommand, SlashCommandCompleter, parse_input};
+use super::history::{self, SessionSettings, Transcript, TranscriptEntry};
+use super::input::{self, InputHistory};
 use super::ui;
-use crate::config::{CustomStyle, ResolvedConfig};
+use crate::cache::CacheManager;
+use crate::compare::{self, Candidate};
+use crate::config::{ConfigFile, CustomStyle, ResolveOptions, ResolvedConfig, resolve_config};
 use crate::output;
 use crate::style;
-use crate::translation::{TranslationClient, TranslationRequest};
-use crate::ui::{Spinner, Style};
+use crate::translation::{
+    AUTO_DETECT, FewShotExample, LanguagePair, SUPPORTED_LANGUAGES, TranslationClient,
+    TranslationRequest, canonicalize_language, is_detected, resolve_source_language, same_language,
+    validate_template,
+};
+use crate::ui::{MultiSpinner, Spinner, Style};
 
 /// Configuration for a chat session.
 ///
@@ -22,6 +31,16 @@ pub struct SessionConfig {
     pub resolved: ResolvedConfig,
     /// Available custom styles (cached from config file).
     pub custom_styles: HashMap<String, CustomStyle>,
+    /// Fixed source language (`None` detects it for each message).
+    pub source_language: Option<String>,
+    /// Language aliases (cached from config file).
+    pub aliases: HashMap<String, String>,
+    /// Number of earlier exchanges sent as context (0 disables it).
+    pub context_size: usize,
+    /// Whether translations are looked up in and stored to the cache.
+    pub use_cache: bool,
+    /// The loaded config file (providers for `/set provider`).
+    pub config_file: ConfigFile,
 }
 
 impl SessionConfig {
@@ -31,16 +50,42 @@ impl SessionConfig {
         Self {
             resolved,
             custom_styles,
+            source_language: None,
+            aliases: HashMap::new(),
+            context_size: 0,
+            use_cache: true,
+            config_file: ConfigFile::default(),
         }
     }
 }
 
+/// Maximum number of earlier exchanges kept for context.
+const MAX_CONTEXT: usize = 20;
+
+/// A translated message, kept as context for later ones.
+struct Exchange {
+    target_language: String,
+    pair: FewShotExample,
+}
+
 /// An interactive chat session for translation.
 ///
 /// Provides a REPL-style interface for translating text interactively.
 pub struct ChatSession {
     config: SessionConfig,
     client: TranslationClient,
+    /// Recent exchanges, oldest first (at most `MAX_CONTEXT`).
+    history: VecDeque<Exchange>,
+    /// Every translated message, for `/save` and `tl chat --resume`.
+    transcript: Transcript,
+    /// Where the transcript is saved (`None` when history is disabled).
+    transcript_path: Option<PathBuf>,
+    /// Lines typed at the prompt, suggested while typing.
+    input_history: InputHistory,
+    /// Translation cache shared with one-shot translations.
+    cache: Option<CacheManager>,
+    /// The request of the last translated message, for `/compare`.
+    last_request: Option<TranslationRequest>,
 }
 
 impl ChatSession {
@@ -50,7 +95,75 @@ impl ChatSession {
             config.resolved.endpoint.clone(),
             config.resolved.api_key.clone(),
         );
-        Self { config, client }
+        let transcript = Transcript::new(SessionSettings::default());
+        Self {
+            config,
+            client,
+            history: VecDeque::new(),
+            transcript,
+            transcript_path: None,
+            input_history: InputHistory::default(),
+            cache: None,
+            last_request: None,
+        }
+    }
+
+    /// Uses `cache` for translations while the cache is turned on.
+    pub fn set_cache(&mut self, cache: CacheManager) {
+        self.cache = Some(cache);
+    }
+
+    /// Uses `history` for suggestions and records new input lines in it.
+    pub fn set_input_history(&mut self, history: InputHistory) {
+        self.input_history = history;
+    }
+
+    /// Saves the transcript to `path` after every translation.
+    pub fn record_to(&mut self, path: PathBuf) {
+        self.transcript_path = Some(path);
+    }
+
+    /// Continues a saved session: its messages are kept in the
+    /// transcript and the most recent ones become context again.
+    pub fn resume(&mut self, transcript: Transcript) {
+        let skip = transcript.entries.len().saturating_sub(MAX_CONTEXT);
+        for entry in &transcript.entries[skip..] {
+            self.history.push_back(Exchange {
+                target_language: entry.target_language.clone(),
+                pair: FewShotExample {
+                    source: entry.source.clone(),
+                    target: entry.translation.clone(),
+                },
+            });
+        }
+        self.transcript = transcript;
+    }
+
+    /// Returns the current settings as stored in the transcript.
+    fn settings(&self) -> SessionSettings {
+        let resolved = &self.config.resolved;
+        SessionSettings {
+            provider: resolved.provider_name.clone(),
+            model: resolved.model.clone(),
+            to: resolved.target_language.clone(),
+            from: self.config.source_language.clone(),
+            pair: resolved.pair.as_ref().map(ToString::to_string),
+            style: resolved.style_name.clone(),
+            context: self.config.context_size,
+        }
+    }
+
+    /// Writes the transcript to the history directory.
+    ///
+    /// Failures are reported but do not end the session.
+    fn persist(&mut self) {
+        let Some(ref path) = self.transcript_path else {
+            return;
+        };
+        self.transcript.settings = self.settings();
+        if let Err(e) = self.transcript.save(path) {
+            crate::warn!("Failed to save chat history: {e:#}");
+        }
     }
 
     pub async fn run(&mut self) -> Result<()> {
@@ -71,13 +184,29 @@ impl ChatSession {
         loop {
             let input = Text::new("")
                 .with_render_config(render_config)
-                .with_autocomplete(SlashCommandCompleter)
-                .with_help_message("Type text to translate, /help for commands, Ctrl+C to quit")
+                .with_autocomplete(self.completer())
+                .with_help_message(
+                    "Type text to translate, ↓ for earlier input, /help for commands, Ctrl+C to quit",
+                )
                 .prompt();
 
+            if let Ok(ref line) = input {
+                self.input_history.add(line);
+            }
             match input {
                 Ok(line) => match parse_input(&line) {
                     Input::Empty => {}
+                    Input::Command(SlashCommand::Paste) => {
+                        ui::print_paste_hint();
+                        let text = input::read_paste(io::stdin().lock());
+                        self.translate_multiline(text).await?;
+                    }
+                    Input::Command(SlashCommand::Edit) => {
+                        self.translate_multiline(input::read_editor()).await?;
+                    }
+                    Input::Command(SlashCommand::Compare(models)) => {
+                        self.compare(models.as_deref()).await;
+                    }
                     Input::Command(cmd) => {
                         if !self.handle_command(cmd) {
                             break;
@@ -98,12 +227,21 @@ impl ChatSession {
             }
         }
 
+        // Keep settings changed after the last message for --resume
+        if !self.transcript.entries.is_empty() {
+            self.persist();
+        }
         ui::print_goodbye();
         Ok(())
     }
 
     fn handle_command(&mut self, cmd: SlashCommand) -> bool {
         match cmd {
+            SlashCommand::Clear => {
+                self.history.clear();
+                println!("{} Conversation context cleared", Style::success("✓"));
+                true
+            }
             SlashCommand::Config => {
                 ui::print_config(&self.config);
                 true
@@ -112,7 +250,13 @@ impl ChatSession {
                 ui::print_help();
                 true
             }
+            // Asynchronous or read further input, so they are handled in `run`
+            SlashCommand::Compare(_) | SlashCommand::Paste | SlashCommand::Edit => true,
             SlashCommand::Quit => false,
+            SlashCommand::Save(file) => {
+                self.save_transcript(file.as_deref());
+                true
+            }
             SlashCommand::Set { key, value } => {
                 self.handle_set(&key, value.as_deref());
                 true
@@ -124,18 +268,40 @@ impl ChatSession {
         }
     }
 
+    fn save_transcript(&mut self, file: Option<&str>) {
+        let Some(file) = file else {
+            ui::print_error("Usage: /save <file> (.json for JSON, Markdown otherwise)");
+            return;
+        };
+        self.transcript.settings = self.settings();
+        match self.transcript.export(file) {
+            Ok(()) => println!(
+                "{} Saved {} messages to {}",
+                Style::success("✓"),
+                self.transcript.entries.len(),
+                Style::value(file)
+            ),
+            Err(e) => ui::print_error(&format!("{e:#}")),
+        }
+    }
+
     fn handle_set(&mut self, key: &str, value: Option<&str>) {
         match key {
             "style" => self.set_style(value),
             "to" => self.set_to(value),
+            "from" => self.set_from(value),
+            "pair" => self.set_pair(value),
+            "provider" => self.set_provider(value),
             "model" => self.set_model(value),
+            "context" => self.set_context(value),
+            "cache" => self.set_cache_enabled(value),
             "" => {
                 println!("Usage: /set <key> <value>");
-                println!("Keys: style, to, model");
+                println!("Keys: {}", SET_KEYS.join(", "));
             }
             _ => {
                 ui::print_error(&format!("Unknown setting: {key}"));
-                println!("Available: style, to, model");
+                println!("Available: {}", SET_KEYS.join(", "));
             }
         }
     }
@@ -143,8 +309,7 @@ impl ChatSession {
     fn set_style(&mut self, value: Option<&str>) {
         let Some(key) = value else {
             // Clear style
-            self.config.resolved.style_name = None;
-            self.config.resolved.style_prompt = None;
+            self.config.resolved.apply_style(None);
             println!("{} Style cleared", Style::success("✓"));
             return;
         };
@@ -158,12 +323,18 @@ impl ChatSession {
             }
         };
 
-        self.config.resolved.style_name = Some(key.to_string());
-        self.config.resolved.style_prompt = Some(resolved.prompt().to_string());
+        if let Some(template) = resolved.template()
+            && let Err(e) = validate_template(template, &format!("style '{key}'"))
+        {
+            ui::print_error(&e.to_string());
+            return;
+        }
+
+        self.config.resolved.apply_style(Some(&resolved));
         println!(
             "{} Style set to {}\n",
             Style::success("✓"),
-            Style::value(key)
+            Style::value(resolved.key())
         );
     }
 
@@ -173,22 +344,102 @@ impl ChatSession {
                 ui::print_error("Usage: /set to <language>");
             }
             Some(lang) => {
-                self.config.resolved.target_language = lang.to_string();
+                let lang = match canonicalize_language(lang, &self.config.aliases) {
+                    Ok(lang) => lang,
+                    Err(e) => {
+                        ui::print_error(&e.to_string());
+                        return;
+                    }
+                };
                 println!(
                     "{} Target language set to {}",
                     Style::success("✓"),
-                    Style::value(lang)
+                    Style::value(&lang)
+                );
+                // A fixed target replaces any language pair
+                self.config.resolved.target_language = lang;
+                self.config.resolved.pair = None;
+            }
+        }
+    }
+
+    fn set_from(&mut self, value: Option<&str>) {
+        match value {
+            None | Some(AUTO_DETECT) => {
+                self.config.source_language = None;
+                println!(
+                    "{} Source language set to {}",
+                    Style::success("✓"),
+                    Style::value("auto-detect")
+                );
+            }
+            Some(lang) => {
+                let lang = match canonicalize_language(lang, &self.config.aliases) {
+                    Ok(lang) => lang,
+                    Err(e) => {
+                        ui::print_error(&e.to_string());
+                        return;
+                    }
+                };
+                println!(
+                    "{} Source language set to {}",
+                    Style::success("✓"),
+                    Style::value(&lang)
                 );
+                self.config.source_language = Some(lang);
             }
         }
     }
 
+    fn set_pair(&mut self, value: Option<&str>) {
+        let Some(value) = value else {
+            self.config.resolved.pair = None;
+            println!(
+                "{} Language pair cleared (target: {})",
+                Style::success("✓"),
+                Style::value(&self.config.resolved.target_language)
+            );
+            return;
+        };
+
+        match LanguagePair::parse(value, &self.config.aliases) {
+            Ok(pair) => {
+                println!(
+                    "{} Language pair set to {}",
+                    Style::success("✓"),
+                    Style::value(pair.to_string())
+                );
+                self.config
+                    .resolved
+                    .target_language
+                    .clone_from(&pair.primary);
+                self.config.resolved.pair = Some(pair);
+            }
+            Err(e) => ui::print_error(&e.to_string()),
+        }
+    }
+
     fn set_model(&mut self, value: Option<&str>) {
         match value {
             None => {
                 ui::print_error("Usage: /set model <name>");
             }
             Some(model) => {
+                let models = self
+                    .config
+                    .config_file
+                    .providers
+                    .get(&self.config.resolved.provider_name)
+                    .map(|provider| provider.models.as_slice())
+                    .unwrap_or_default();
+                if !models.is_empty() && !models.iter().any(|m| m == model) {
+                    ui::print_error(&format!(
+                        "Model '{model}' is not configured for '{}'\n\nConfigured models: {}",
+                        self.config.resolved.provider_name,
+                        models.join(", ")
+                    ));
+                    return;
+                }
                 self.config.resolved.model = model.to_string();
                 println!(
                     "{} Model set to {}",
@@ -199,15 +450,338 @@ impl ChatSession {
         }
     }
 
-    async fn translate_and_print(&self, text: &str) -> Result<()> {
+    fn set_provider(&mut self, value: Option<&str>) {
+        let Some(name) = value else {
+            ui::print_error("Usage: /set provider <name>");
+            return;
+        };
+
+        // Keep the model if the new provider has it, otherwise use its first one
+        let resolved = &self.config.resolved;
+        let model = match self.config.config_file.providers.get(name) {
+            Some(provider)
+                if !provider.models.is_empty() && !provider.models.contains(&resolved.model) =>
+            {
+                provider.models[0].clone()
+            }
+            _ => resolved.model.clone(),
+        };
+        let options = ResolveOptions {
+            provider: Some(name.to_string()),
+            model: Some(model),
+            ..self.resolve_options()
+        };
+
+        match resolve_config(&options, &self.config.config_file) {
+            Ok(resolved) => {
+                self.client =
+                    TranslationClient::new(resolved.endpoint.clone(), resolved.api_key.clone());
+                println!(
+                    "{} Provider set to {} (model: {})",
+                    Style::success("✓"),
+                    Style::value(&resolved.provider_name),
+                    Style::value(&resolved.model)
+                );
+                self.config.resolved = resolved;
+            }
+            Err(e) => ui::print_error(&e.to_string()),
+        }
+    }
+
+    /// Returns options that resolve to the current target, pair, and style.
+    fn resolve_options(&self) -> ResolveOptions {
+        let resolved = &self.config.resolved;
+        ResolveOptions {
+            to: resolved
+                .pair
+                .is_none()
+                .then(|| resolved.target_language.clone()),
+            provider: None,
+            model: None,
+            style: resolved.style_name.clone(),
+            pair: resolved.pair.as_ref().map(ToString::to_string),
+        }
+    }
+
+    /// Translates the last message again with the current model and each
+    /// of `models`, and prints the results side by side.
+    ///
+    /// The results are not added to the transcript or context.
+    async fn compare(&self, models: Option<&str>) {
+        let Some(ref request) = self.last_request else {
+            ui::print_error("Translate a message first, then /compare it with other models");
+            return;
+        };
+        let models = compare::split_list(models);
+        if models.is_empty() {
+            ui::print_error("Usage: /compare <model>[,<model>...]");
+            return;
+        }
+
+        let resolved = &self.config.resolved;
+        let mut candidates = vec![Candidate {
+            provider: Some(resolved.provider_name.clone()),
+            model: Some(resolved.model.clone()),
+        }];
+        for model in &models {
+            let candidate = compare::route_model(
+                model,
+                Some(&resolved.provider_name),
+                &self.config.config_file,
+            );
+            if !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+        if candidates.len() < 2 {
+            ui::print_error("Name a model other than the current one");
+            return;
+        }
+        let contenders = match compare::resolve_candidates(
+            &candidates,
+            &self.resolve_options(),
+            &self.config.config_file,
+        ) {
+            Ok(contenders) => contenders,
+            Err(e) => {
+                ui::print_error(&e.to_string());
+                return;
+            }
+        };
+
+        let labels: Vec<String> = contenders
+            .iter()
+            .map(|resolved| compare::label(&resolved.provider_name, &resolved.model))
+            .collect();
+        let progress = (!output::is_quiet()).then(|| MultiSpinner::new(&labels, "waiting..."));
+        let cache = self.cache.as_ref().filter(|_| self.config.use_cache);
+        let entries = compare::run(request, &contenders, cache, progress.as_ref()).await;
+        drop(progress);
+
+        print!(
+            "{}",
+            compare::side_by_side(&entries, compare::terminal_width())
+        );
+        println!();
+    }
+
+    /// Builds the autocompleter with the current input history and the
+    /// values each `/set` key accepts.
+    fn completer(&self) -> SlashCommandCompleter {
+        let config = &self.config;
+        let mut styles: Vec<String> = style::PRESETS.iter().map(|p| p.key.to_string()).collect();
+        styles.extend(
+            style::sorted_custom_keys(&config.custom_styles)
+                .into_iter()
+                .cloned(),
+        );
+
+        let mut languages: Vec<String> = config.aliases.keys().cloned().collect();
+        languages.sort();
+        languages.extend(
+            SUPPORTED_LANGUAGES
+                .iter()
+                .map(|(code, _)| (*code).to_string()),
+        );
+        let mut sources = vec![AUTO_DETECT.to_string()];
+        sources.extend(languages.iter().cloned());
+
+        let mut providers: Vec<String> = config.config_file.providers.keys().cloned().collect();
+        providers.sort();
+        let models = config
+            .config_file
+            .providers
+            .get(&config.resolved.provider_name)
+            .map(|provider| provider.models.clone())
+            .unwrap_or_default();
+
+        SlashCommandCompleter::with_history(self.input_history.entries())
+            .with_values("style", styles)
+            .with_values("to", languages)
+            .with_values("from", sources)
+            .with_values("provider", providers)
+            .with_values("model", models)
+            .with_values("cache", vec!["on".to_string(), "off".to_string()])
+    }
+
+    fn set_context(&mut self, value: Option<&str>) {
+        let Some(value) = value else {
+            ui::print_error("Usage: /set context <n> (0 disables context)");
+            return;
+        };
+        let size = match value.parse::<usize>() {
+            Ok(size) if size <= MAX_CONTEXT => size,
+            _ => {
+                ui::print_error(&format!(
+                    "Invalid context size: '{value}' (expected 0 to {MAX_CONTEXT})"
+                ));
+                return;
+            }
+        };
+
+        self.config.context_size = size;
+        if size == 0 {
+            println!("{} Context disabled", Style::success("✓"));
+        } else {
+            println!(
+                "{} Context set to the last {} messages",
+                Style::success("✓"),
+                Style::value(size.to_string())
+            );
+        }
+    }
+
+    fn set_cache_enabled(&mut self, value: Option<&str>) {
+        let enabled = match value {
+            Some("on") => true,
+            Some("off") => false,
+            _ => {
+                ui::print_error("Usage: /set cache on|off");
+                return;
+            }
+        };
+        if enabled && self.cache.is_none() {
+            ui::print_error("The cache is not available in this session");
+            return;
+        }
+
+        self.config.use_cache = enabled;
+        println!(
+            "{} Cache turned {}",
+            Style::success("✓"),
+            Style::value(if enabled { "on" } else { "off" })
+        );
+    }
+
+    /// Returns the cached translation for `request`, if the cache is on.
+    fn cached_translation(&self, request: &TranslationRequest) -> Option<String> {
+        if !self.config.use_cache {
+            return None;
+        }
+        self.cache
+            .as_ref()?
+            .get(request)
+            .inspect_err(|e| {
+                crate::warn!("Failed to read cache: {e:#}");
+            })
+            .ok()
+            .flatten()
+    }
+
+    fn store_in_cache(&self, request: &TranslationRequest, translation: &str) {
+        if !self.config.use_cache {
+            return;
+        }
+        if let Some(ref cache) = self.cache
+            && let Err(e) = cache.put(request, translation)
+        {
+            crate::warn!("Failed to write cache: {e:#}");
+        }
+    }
+
+    /// Returns the most recent exchanges into `target`, oldest first.
+    ///
+    /// Only exchanges with the same target language are used, so a
+    /// language pair switching direction does not mix languages.
+    fn context_for(&self, target: &str) -> Vec<FewShotExample> {
+        let mut context: Vec<FewShotExample> = self
+            .history
+            .iter()
+            .rev()
+            .filter(|exchange| exchange.target_language == target)
+            .take(self.config.context_size)
+            .map(|exchange| exchange.pair.clone())
+            .collect();
+        context.reverse();
+        context
+    }
+
+    fn remember(&mut self, request: TranslationRequest, translation: String) {
+        self.transcript.entries.push(TranscriptEntry {
+            timestamp: history::now(),
+            source_language: request.source_language.clone(),
+            target_language: request.target_language.clone(),
+            model: request.model.clone(),
+            style: self.config.resolved.style_name.clone(),
+            source: request.source_text.clone(),
+            translation: translation.clone(),
+        });
+        if self.history.len() == MAX_CONTEXT {
+            self.history.pop_front();
+        }
+        self.history.push_back(Exchange {
+            target_language: request.target_language,
+            pair: FewShotExample {
+                source: request.source_text,
+                target: translation,
+            },
+        });
+    }
+
+    /// Translates text from `/paste` or `/edit`.
+    async fn translate_multiline(&mut self, text: Result<String>) -> Result<()> {
+        match text {
+            Ok(text) if text.is_empty() => {
+                println!("{}", Style::secondary("Nothing to translate"));
+                println!();
+                Ok(())
+            }
+            Ok(text) => self.translate_and_print(&text).await,
+            Err(e) => {
+                ui::print_error(&format!("{e:#}"));
+                Ok(())
+            }
+        }
+    }
+
+    async fn translate_and_print(&mut self, text: &str) -> Result<()> {
+        let source_language = resolve_source_language(self.config.source_language.as_deref(), text);
+        let target_language = self
+            .config
+            .resolved
+            .target_language_for(source_language.as_deref());
+
+        // Only a language set with `/set from` can skip the translation
+        let source_language_detected = is_detected(self.config.source_language.as_deref());
+        let skipped = !source_language_detected
+            && source_language
+                .as_deref()
+                .is_some_and(|source| same_language(source, &target_language));
+        if let Some(ref source) = source_language {
+            ui::print_languages(source, &target_language, skipped);
+        }
+        if skipped {
+            println!("{text}");
+            println!();
+            return Ok(());
+        }
+
+        let mut prompt = self
+            .config
+            .resolved
+            .prompt_options_for(source_language.as_deref(), &target_language);
+        prompt.context = self.context_for(&target_language);
         let request = TranslationRequest {
             source_text: text.to_string(),
-            target_language: self.config.resolved.target_language.clone(),
+            target_language,
+            source_language,
+            source_language_detected,
             model: self.config.resolved.model.clone(),
             endpoint: self.config.resolved.endpoint.clone(),
             style: self.config.resolved.style_prompt.clone(),
+            prompt,
         };
 
+        self.last_request = Some(request.clone());
+        if let Some(translation) = self.cached_translation(&request) {
+            println!("{translation}");
+            ui::print_cached();
+            println!();
+            self.remember(request, translation);
+            self.persist();
+            return Ok(());
+        }
+
         // Only show spinner in non-quiet mode
         let spinner = if output::is_quiet() {
             None
@@ -217,6 +791,7 @@ impl ChatSession {
 
         let mut stream = self.client.translate_stream(&request).await?;
         let mut first_chunk = true;
+        let mut translation = String::new();
 
         while let Some(chunk_result) = stream.next().await {
             let chunk = chunk_result?;
@@ -230,6 +805,7 @@ impl ChatSession {
 
             print!("{chunk}");
             io::stdout().flush()?;
+            translation.push_str(&chunk);
         }
 
         if first_chunk && let Some(ref s) = spinner {
@@ -238,13 +814,201 @@ impl ChatSession {
 
         println!();
         println!();
+        if !translation.is_empty() {
+            self.store_in_cache(&request, &translation);
+            self.remember(request, translation);
+            self.persist();
+        }
         Ok(())
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::config::ProviderConfig;
+    use crate::translation::PromptOptions;
+
+    fn test_resolved() -> ResolvedConfig {
+        ResolvedConfig {
+            provider_name: "ollama".to_string(),
+            endpoint: "http://localhost:11434".to_string(),
+            model: "gemma3:12b".to_string(),
+            api_key: None,
+            target_language: "ja".to_string(),
+            style_name: None,
+            style_prompt: None,
+            pair: None,
+            native_names: false,
+            language_prompts: HashMap::new(),
+            prompt_template: None,
+            style_template: None,
+            style_examples: Vec::new(),
+        }
+    }
+
+    fn exchange(text: &str, target: &str) -> TranslationRequest {
+        TranslationRequest {
+            source_text: text.to_string(),
+            target_language: target.to_string(),
+            source_language: None,
+            source_language_detected: false,
+            model: "gemma3:12b".to_string(),
+            endpoint: "http://localhost:11434".to_string(),
+            style: None,
+            prompt: PromptOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_context_for_uses_recent_exchanges_with_same_target() {
+        let mut session = ChatSession::new(SessionConfig::new(test_resolved(), HashMap::new()));
+        session.remember(exchange("one", "ja"), "一".to_string());
+        session.remember(exchange("uno", "es"), "uno".to_string());
+        session.remember(exchange("two", "ja"), "二".to_string());
+        session.remember(exchange("three", "ja"), "三".to_string());
+
+        assert!(session.context_for("ja").is_empty());
+
+        session.config.context_size = 2;
+        let sources: Vec<_> = session
+            .context_for("ja")
+            .into_iter()
+            .map(|pair| pair.source)
+            .collect();
+        assert_eq!(sources, ["two", "three"]);
+        assert_eq!(session.context_for("es").len(), 1);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut session = ChatSession::new(SessionConfig::new(test_resolved(), HashMap::new()));
+        for i in 0..=MAX_CONTEXT {
+            session.remember(exchange(&i.to_string(), "ja"), i.to_string());
+        }
+        assert_eq!(session.history.len(), MAX_CONTEXT);
+        assert_eq!(session.history[0].pair.source, "1");
+    }
+
+    #[test]
+    fn test_remember_records_transcript() {
+        let mut session = ChatSession::new(SessionConfig::new(test_resolved(), HashMap::new()));
+        session.remember(exchange("one", "ja"), "一".to_string());
+
+        let entries = &session.transcript.entries;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].source, "one");
+        assert_eq!(entries[0].translation, "一");
+        assert_eq!(session.settings().to, "ja");
+    }
+
+    #[test]
+    fn test_resume_restores_recent_context() {
+        let mut saved = ChatSession::new(SessionConfig::new(test_resolved(), HashMap::new()));
+        for i in 0..=MAX_CONTEXT {
+            saved.remember(exchange(&i.to_string(), "ja"), i.to_string());
+        }
+
+        let mut session = ChatSession::new(SessionConfig::new(test_resolved(), HashMap::new()));
+        session.config.context_size = 1;
+        session.resume(saved.transcript);
+
+        assert_eq!(session.transcript.entries.len(), MAX_CONTEXT + 1);
+        assert_eq!(session.history.len(), MAX_CONTEXT);
+        assert_eq!(session.context_for("ja")[0].source, MAX_CONTEXT.to_string());
+    }
+
+    #[test]
+    fn test_cached_translation_respects_cache_setting() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cache = CacheManager::with_path(&temp_dir.path().join("cache.db")).unwrap();
+        let mut session = ChatSession::new(SessionConfig::new(test_resolved(), HashMap::new()));
+        assert!(session.cached_translation(&exchange("one", "ja")).is_none());
+
+        session.set_cache(cache);
+        session.store_in_cache(&exchange("one", "ja"), "一");
+        assert_eq!(
+            session
+                .cached_translation(&exchange("one", "ja"))
+                .as_deref(),
+            Some("一")
+        );
+
+        session.set_cache_enabled(Some("off"));
+        assert!(!session.config.use_cache);
+        assert!(session.cached_translation(&exchange("one", "ja")).is_none());
+        session.store_in_cache(&exchange("two", "ja"), "二");
+
+        session.set_cache_enabled(Some("on"));
+        assert!(session.cached_translation(&exchange("two", "ja")).is_none());
+    }
+
+    fn provider(endpoint: &str, models: &[&str], api_key_env: Option<&str>) -> ProviderConfig {
+        ProviderConfig {
+            endpoint: endpoint.to_string(),
+            api_key: None,
+            api_key_env: api_key_env.map(str::to_string),
+            models: models.iter().map(ToString::to_string).collect(),
+            prompt_template: None,
+        }
+    }
+
+    fn session_with_providers() -> ChatSession {
+        let mut config = SessionConfig::new(test_resolved(), HashMap::new());
+        let providers = &mut config.config_file.providers;
+        providers.insert(
+            "ollama".to_string(),
+            provider("http://localhost:11434", &["gemma3:12b", "llama3.2"], None),
+        );
+        providers.insert(
+            "remote".to_string(),
+            provider("https://example.com/v1", &["gpt-4o-mini"], None),
+        );
+        providers.insert(
+            "locked".to_string(),
+            provider(
+                "https://example.com/v1",
+                &[],
+                Some("TL_TEST_CHAT_MISSING_API_KEY"),
+            ),
+        );
+        ChatSession::new(config)
+    }
+
+    #[test]
+    fn test_set_provider_switches_endpoint_and_model() {
+        let mut session = session_with_providers();
+        session.config.resolved.style_name = Some("casual".to_string());
+
+        session.set_provider(Some("remote"));
+        let resolved = &session.config.resolved;
+        assert_eq!(resolved.provider_name, "remote");
+        assert_eq!(resolved.endpoint, "https://example.com/v1");
+        assert_eq!(resolved.model, "gpt-4o-mini");
+        assert_eq!(resolved.target_language, "ja");
+        assert_eq!(resolved.style_name.as_deref(), Some("casual"));
+
+        // Unknown providers and missing API keys leave the settings alone
+        session.set_provider(Some("missing"));
+        session.set_provider(Some("locked"));
+        assert_eq!(session.config.resolved.provider_name, "remote");
+    }
+
+    #[test]
+    fn test_set_validates_model_and_language() {
+        let mut session = session_with_providers();
+
+        session.set_model(Some("gpt-4o"));
+        assert_eq!(session.config.resolved.model, "gemma3:12b");
+        session.set_model(Some("llama3.2"));
+        assert_eq!(session.config.resolved.model, "llama3.2");
+
+        session.set_to(Some("not-a-language"));
+        assert_eq!(session.config.resolved.target_language, "ja");
+        session.set_to(Some("EN"));
+        assert_eq!(session.config.resolved.target_language, "en");
+    }
 
     #[test]
     fn test_session_config_new() {
@@ -254,6 +1018,9 @@ mod tests {
             CustomStyle {
                 description: "My description".to_string(),
                 prompt: "My custom prompt".to_string(),
+                prompt_template: None,
+                extends: None,
+                examples: Vec::new(),
             },
         );
 
@@ -265,6 +1032,12 @@ mod tests {
             target_language: "ja".to_string(),
             style_name: Some("casual".to_string()),
             style_prompt: Some("Use a casual tone.".to_string()),
+            pair: None,
+            native_names: false,
+            language_prompts: HashMap::new(),
+            prompt_template: None,
+            style_template: None,
+            style_examples: Vec::new(),
         };
 
         let config = SessionConfig::new(resolved, custom_styles);
@@ -1,5 +1,6 @@
 //! Chat mode UI components.
 
+use crate::output;
 use crate::ui::Style;
 
 use super::session::SessionConfig;
@@ -36,6 +37,21 @@ pub fn print_config(config: &SessionConfig) {
         Style::label("to"),
         Style::value(&config.resolved.target_language)
     );
+    println!(
+        "  {}       {}",
+        Style::label("from"),
+        config
+            .source_language
+            .as_deref()
+            .map_or_else(|| Style::secondary("(auto)"), Style::value)
+    );
+    if let Some(ref pair) = config.resolved.pair {
+        println!(
+            "  {}       {}",
+            Style::label("pair"),
+            Style::value(pair.to_string())
+        );
+    }
     println!(
         "  {}      {}",
         Style::label("style"),
@@ -45,6 +61,24 @@ pub fn print_config(config: &SessionConfig) {
             .as_deref()
             .map_or_else(|| Style::secondary("(none)"), Style::value)
     );
+    println!(
+        "  {}    {}",
+        Style::label("context"),
+        if config.context_size == 0 {
+            Style::secondary("(off)")
+        } else {
+            Style::value(format!("last {} messages", config.context_size))
+        }
+    );
+    println!(
+        "  {}      {}",
+        Style::label("cache"),
+        if config.use_cache {
+            Style::value("on")
+        } else {
+            Style::secondary("(off)")
+        }
+    );
     println!(
         "  {}   {}",
         Style::label("endpoint"),
@@ -56,50 +90,128 @@ pub fn print_config(config: &SessionConfig) {
 pub fn print_help() {
     println!("{}", Style::header("Available commands"));
     println!(
-        "  {}  {}",
+        "  {}   {}",
         Style::command("/config"),
         Style::secondary("Show current configuration")
     );
+    println!(
+        "  {}     {}",
+        Style::command("/edit"),
+        Style::secondary("Write multi-line text in $EDITOR")
+    );
     println!(
         "  {}    {}",
+        Style::command("/clear"),
+        Style::secondary("Forget earlier messages (context)")
+    );
+    println!(
+        "  {}  {}",
+        Style::command("/compare"),
+        Style::secondary("Translate the last message with other models (comma-separated)")
+    );
+    println!(
+        "  {}     {}",
         Style::command("/help"),
         Style::secondary("Show this help")
     );
     println!(
         "  {}    {}",
+        Style::command("/paste"),
+        Style::secondary("Paste multi-line text (end with a line '.')")
+    );
+    println!(
+        "  {}     {}",
         Style::command("/quit"),
         Style::secondary("Exit chat mode")
     );
     println!(
         "  {}     {}",
+        Style::command("/save"),
+        Style::secondary("Save the transcript (/save chat.md or chat.json)")
+    );
+    println!(
+        "  {}      {}",
         Style::command("/set"),
-        Style::secondary("Set option (style, to, model)")
+        Style::secondary("Set option (style, to, from, pair, provider, model, context, cache)")
     );
     println!();
     println!("{}", Style::header("Set examples"));
     println!(
-        "  {}  {}",
+        "  {}     {}",
         Style::command("/set style casual"),
         Style::secondary("Use casual translation style")
     );
     println!(
-        "  {}         {}",
+        "  {}            {}",
         Style::command("/set to ja"),
         Style::secondary("Set target language to Japanese")
     );
+    println!(
+        "  {}          {}",
+        Style::command("/set from en"),
+        Style::secondary("Fix source language (auto to detect)")
+    );
+    println!(
+        "  {}       {}",
+        Style::command("/set pair ja,en"),
+        Style::secondary("Translate ja → en, anything else → ja")
+    );
     println!(
         "  {}  {}",
+        Style::command("/set provider ollama"),
+        Style::secondary("Switch to another configured provider")
+    );
+    println!(
+        "  {}     {}",
         Style::command("/set model gpt-4o"),
         Style::secondary("Switch to a different model")
     );
     println!(
-        "  {}      {}",
+        "  {}        {}",
+        Style::command("/set context 3"),
+        Style::secondary("Send the last 3 messages as context")
+    );
+    println!(
+        "  {}        {}",
+        Style::command("/set cache off"),
+        Style::secondary("Always call the API (on to use the cache again)")
+    );
+    println!(
+        "  {}            {}",
         Style::command("/set style"),
         Style::secondary("Clear style (no style)")
     );
     println!();
 }
 
+/// Marks a translation served from the cache.
+pub fn print_cached() {
+    println!("{}", Style::secondary("(cached)"));
+}
+
+/// Explains how to end `/paste` input.
+pub fn print_paste_hint() {
+    println!(
+        "{}",
+        Style::secondary("Paste text, then enter a line with only '.' (or press Ctrl+D)")
+    );
+}
+
+/// Prints the language pair of a message (suppressed in quiet mode).
+///
+/// `skipped` notes that the message is already in the target language.
+pub fn print_languages(source: &str, target: &str, skipped: bool) {
+    if output::is_quiet() {
+        return;
+    }
+    let note = if skipped {
+        " (already in target language)"
+    } else {
+        ""
+    };
+    println!("{}", Style::secondary(format!("{source} → {target}{note}")));
+}
+
 pub fn print_error(message: &str) {
     eprintln!("{} {message}", Style::error("Error:"));
     eprintln!();
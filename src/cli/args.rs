@@ -2,6 +2,8 @@
 
 use clap::{Parser, Subcommand};
 
+use crate::output::{OutputFormat, StreamFormat};
+
 /// Command-line arguments for the `tl` CLI.
 #[derive(Parser, Debug)]
 #[command(name = "tl")]
@@ -12,10 +14,18 @@ pub struct Args {
     /// File to translate (reads from stdin if not provided)
     pub file: Option<String>,
 
-    /// Target language code (ISO 639-1, e.g., ja, en, zh)
+    /// Target language code (ISO 639-1, e.g., ja, en, zh); comma-separate for several
     #[arg(short = 't', long = "to")]
     pub to: Option<String>,
 
+    /// Source language code (detected from the input if omitted or "auto")
+    #[arg(long)]
+    pub from: Option<String>,
+
+    /// Language pair to swap between (e.g., ja,en: ja → en, anything else → ja)
+    #[arg(long, conflicts_with = "to")]
+    pub pair: Option<String>,
+
     /// Provider name (e.g., ollama, openrouter)
     #[arg(short = 'p', long)]
     pub provider: Option<String>,
@@ -24,7 +34,7 @@ pub struct Args {
     #[arg(short = 'm', long)]
     pub model: Option<String>,
 
-    /// Translation style (e.g., casual, formal, literal, natural)
+    /// Translation style (e.g., casual, formal, literal, natural; combine with commas)
     #[arg(short = 's', long)]
     pub style: Option<String>,
 
@@ -36,6 +46,30 @@ pub struct Args {
     #[arg(short = 'w', long)]
     pub write: bool,
 
+    /// Translate the result back and report how similar it is to the input
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Fail if the back-translation similarity is below this score (0.0-1.0)
+    #[arg(long, value_name = "SCORE", requires = "verify")]
+    pub verify_threshold: Option<f64>,
+
+    /// Translate again once if placeholders or markup ({name}, %s, <b>) were changed
+    #[arg(long)]
+    pub fix_placeholders: bool,
+
+    /// Fail if the translation changed placeholders or markup (the file is not written)
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Write <name>.<lang>.<ext> next to the file, translating only paragraphs changed since the last run
+    #[arg(long)]
+    pub incremental: bool,
+
+    /// Streaming format (ndjson emits start/delta/done/error events, one per line)
+    #[arg(long, value_enum, default_value_t = StreamFormat::Text)]
+    pub stream_format: StreamFormat,
+
     /// Suppress non-essential output (only show translation and errors)
     #[arg(short = 'q', long, global = true)]
     pub quiet: bool,
@@ -44,6 +78,10 @@ pub struct Args {
     #[arg(long, global = true)]
     pub no_color: bool,
 
+    /// Output format (json prints a single object; errors go to stderr as JSON)
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -69,6 +107,14 @@ pub enum Command {
         #[arg(short = 't', long = "to")]
         to: Option<String>,
 
+        /// Source language code (detected per message if omitted or "auto")
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Language pair to swap between (e.g., ja,en: ja → en, anything else → ja)
+        #[arg(long, conflicts_with = "to")]
+        pair: Option<String>,
+
         /// Provider name (e.g., ollama, openrouter)
         #[arg(short = 'p', long)]
         provider: Option<String>,
@@ -77,12 +123,121 @@ pub enum Command {
         #[arg(short = 'm', long)]
         model: Option<String>,
 
-        /// Translation style (e.g., casual, formal, literal, natural)
+        /// Translation style (e.g., casual, formal, literal, natural; combine with commas)
+        #[arg(short = 's', long)]
+        style: Option<String>,
+
+        /// Continue the last chat session (its settings and context)
+        #[arg(long)]
+        resume: bool,
+
+        /// Disable cache (toggle with /set cache)
+        #[arg(short = 'n', long)]
+        no_cache: bool,
+    },
+    /// Translate with several models or providers and compare the results
+    Compare {
+        /// File to translate (reads from stdin if not provided)
+        file: Option<String>,
+
+        /// Target language code (ISO 639-1, e.g., ja, en, zh)
+        #[arg(short = 't', long = "to")]
+        to: Option<String>,
+
+        /// Source language code (detected from the input if omitted or "auto")
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Provider names, comma-separated (e.g., ollama,openrouter)
+        #[arg(short = 'p', long)]
+        provider: Option<String>,
+
+        /// Model names, comma-separated (e.g., gpt-4o,llama3.2)
+        #[arg(short = 'm', long)]
+        model: Option<String>,
+
+        /// Translation style (e.g., casual, formal, literal, natural; combine with commas)
+        #[arg(short = 's', long)]
+        style: Option<String>,
+
+        /// Disable cache
+        #[arg(short = 'n', long)]
+        no_cache: bool,
+
+        /// Show each result as a word diff against the first one
+        #[arg(long)]
+        diff: bool,
+    },
+    /// Ask a judge model to score a translation against its source
+    Review {
+        /// Source file
+        source: String,
+
+        /// Translated file
+        translation: String,
+
+        /// Language of the translation (ISO 639-1, e.g., ja, en, zh)
+        #[arg(short = 't', long = "to")]
+        to: Option<String>,
+
+        /// Source language code (detected from the source if omitted or "auto")
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Judge provider name (default: the configured review provider)
+        #[arg(short = 'p', long)]
+        provider: Option<String>,
+
+        /// Judge model name (default: the configured review model)
+        #[arg(short = 'm', long)]
+        model: Option<String>,
+
+        /// Style the translation should follow (checked by the judge)
         #[arg(short = 's', long)]
         style: Option<String>,
+
+        /// Fail if any score is below this value (1-5)
+        #[arg(long, value_name = "SCORE")]
+        min_score: Option<f64>,
     },
     /// Configure default settings
     Configure,
+    /// Run a local HTTP API for translation
+    Serve {
+        /// Address to bind to
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+
+        /// Maximum number of translations in flight (others get 503)
+        #[arg(long, default_value_t = 4)]
+        max_concurrency: usize,
+
+        /// Disable cache
+        #[arg(short = 'n', long)]
+        no_cache: bool,
+    },
+    /// Run an OpenAI-compatible proxy that caches chat completions
+    Proxy {
+        /// Address to bind to
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Port to listen on
+        #[arg(long, default_value_t = 8081)]
+        port: u16,
+
+        /// Provider to forward to (default: the provider listing the requested model)
+        #[arg(short = 'p', long)]
+        provider: Option<String>,
+
+        /// Disable cache
+        #[arg(short = 'n', long)]
+        no_cache: bool,
+    },
 }
 
 /// Subcommands for provider management.
@@ -122,4 +277,18 @@ pub enum StylesCommand {
         /// Style name to remove
         name: String,
     },
+    /// Print custom styles as a TOML style pack (all if no names given)
+    Export {
+        /// Style names to export (styles they extend are included)
+        names: Vec<String>,
+    },
+    /// Add custom styles from a TOML style pack
+    Import {
+        /// Path to the style pack
+        file: String,
+
+        /// Replace existing styles with the same name
+        #[arg(long)]
+        overwrite: bool,
+    },
 }
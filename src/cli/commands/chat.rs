@@ -3,38 +3,194 @@
 use anyhow::Result;
 
 use super::load_config;
+use crate::cache::CacheManager;
+use crate::chat::history::{self, SessionSettings, Transcript};
+use crate::chat::input::InputHistory;
 use crate::chat::{ChatSession, SessionConfig};
 use crate::config::{ResolveOptions, resolve_config};
+use crate::error::TlError;
+use crate::translation::canonicalize_source_language;
 
 /// Options for the chat command.
 pub struct ChatOptions {
     /// Target language code.
     pub to: Option<String>,
+    /// Source language code (`None` or `auto` detects it per message).
+    pub from: Option<String>,
+    /// Language pair (e.g. `ja,en`) for choosing the target per message.
+    pub pair: Option<String>,
     /// Provider name.
     pub provider: Option<String>,
     /// Model name.
     pub model: Option<String>,
     /// Translation style.
     pub style: Option<String>,
+    /// Continue the last saved session.
+    pub resume: bool,
+    /// Whether to bypass the cache.
+    pub no_cache: bool,
 }
 
 /// Runs the interactive chat mode.
 ///
 /// Starts a REPL-style session for translating text interactively.
-pub async fn run_chat(options: ChatOptions) -> Result<()> {
+pub async fn run_chat(mut options: ChatOptions) -> Result<()> {
     let (_manager, config_file) = load_config()?;
+    let record = config_file.tl.chat_history.unwrap_or(true);
+
+    let resumed = if options.resume {
+        let Some((path, transcript)) = Transcript::load_latest()? else {
+            return Err(TlError::Usage("No chat session to resume".to_string()).into());
+        };
+        apply_saved_settings(&mut options, &transcript.settings);
+        Some((path, transcript))
+    } else {
+        None
+    };
 
     let resolve_options = ResolveOptions {
         to: options.to,
         provider: options.provider,
         model: options.model,
         style: options.style,
+        pair: options.pair,
     };
 
     let resolved = resolve_config(&resolve_options, &config_file)?;
+    let from = canonicalize_source_language(options.from.as_deref(), &config_file.aliases)?;
 
-    let session_config = SessionConfig::new(resolved, config_file.styles.clone());
+    let mut session_config = SessionConfig::new(resolved, config_file.styles.clone());
+    session_config.source_language = from;
+    session_config.aliases = config_file.aliases.clone();
+    session_config.use_cache = !options.no_cache;
+    session_config.config_file = config_file;
+
+    if let Some((_, ref transcript)) = resumed {
+        session_config.context_size = transcript.settings.context;
+    }
 
     let mut session = ChatSession::new(session_config);
+    let path = if let Some((path, transcript)) = resumed {
+        crate::info!(
+            "Resuming chat session ({} messages)",
+            transcript.entries.len()
+        );
+        session.resume(transcript);
+        Some(path)
+    } else if record {
+        history::new_session_path()
+            .inspect_err(|e| {
+                crate::warn!("Chat history disabled: {e:#}");
+            })
+            .ok()
+    } else {
+        None
+    };
+    if let Some(path) = path
+        && record
+    {
+        session.record_to(path);
+    }
+    if record {
+        match InputHistory::load() {
+            Ok(input_history) => session.set_input_history(input_history),
+            Err(e) => {
+                crate::warn!("Input history disabled: {e:#}");
+            }
+        }
+    }
+    // Opened even with --no-cache, so `/set cache on` works
+    match CacheManager::new() {
+        Ok(cache) => session.set_cache(cache),
+        Err(e) => {
+            crate::warn!("Cache disabled: {e:#}");
+        }
+    }
     session.run().await
 }
+
+/// Fills options not given on the command line from a saved session.
+fn apply_saved_settings(options: &mut ChatOptions, saved: &SessionSettings) {
+    // A target given on the command line replaces the saved pair as well
+    if options.to.is_none() && options.pair.is_none() {
+        if saved.pair.is_some() {
+            options.pair.clone_from(&saved.pair);
+        } else {
+            options.to = Some(saved.to.clone());
+        }
+    }
+    // The saved model belongs to the saved provider
+    let same_provider = options
+        .provider
+        .as_ref()
+        .is_none_or(|provider| *provider == saved.provider);
+    if same_provider {
+        options
+            .provider
+            .get_or_insert_with(|| saved.provider.clone());
+        options.model.get_or_insert_with(|| saved.model.clone());
+    }
+    if options.style.is_none() {
+        options.style.clone_from(&saved.style);
+    }
+    if options.from.is_none() {
+        options.from.clone_from(&saved.from);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> ChatOptions {
+        ChatOptions {
+            to: None,
+            from: None,
+            pair: None,
+            provider: None,
+            model: None,
+            style: None,
+            resume: true,
+            no_cache: false,
+        }
+    }
+
+    fn saved() -> SessionSettings {
+        SessionSettings {
+            provider: "ollama".to_string(),
+            model: "gemma3:12b".to_string(),
+            to: "ja".to_string(),
+            from: Some("en".to_string()),
+            pair: Some("ja,en".to_string()),
+            style: Some("casual".to_string()),
+            context: 3,
+        }
+    }
+
+    #[test]
+    fn test_apply_saved_settings_fills_missing_options() {
+        let mut options = options();
+        apply_saved_settings(&mut options, &saved());
+
+        assert_eq!(options.pair.as_deref(), Some("ja,en"));
+        assert!(options.to.is_none());
+        assert_eq!(options.provider.as_deref(), Some("ollama"));
+        assert_eq!(options.model.as_deref(), Some("gemma3:12b"));
+        assert_eq!(options.style.as_deref(), Some("casual"));
+        assert_eq!(options.from.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn test_apply_saved_settings_keeps_command_line_options() {
+        let mut options = options();
+        options.to = Some("fr".to_string());
+        options.provider = Some("openrouter".to_string());
+        apply_saved_settings(&mut options, &saved());
+
+        assert_eq!(options.to.as_deref(), Some("fr"));
+        assert!(options.pair.is_none());
+        assert_eq!(options.provider.as_deref(), Some("openrouter"));
+        // The saved model is not used with another provider
+        assert!(options.model.is_none());
+    }
+}
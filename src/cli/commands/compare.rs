@@ -0,0 +1,191 @@
+//! Compare command handler.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::load_config;
+use crate::cache::CacheManager;
+use crate::compare::{self, Entry};
+use crate::config::ResolveOptions;
+use crate::error::TlError;
+use crate::input::InputReader;
+use crate::output;
+use crate::translation::{
+    TranslationRequest, Usage, canonicalize_source_language, is_detected, resolve_source_language,
+};
+use crate::ui::{MultiSpinner, Style};
+
+/// Options for the compare command.
+pub struct CompareOptions {
+    /// File to translate (reads from stdin if `None`).
+    pub file: Option<String>,
+    /// Target language code.
+    pub to: Option<String>,
+    /// Source language code (`None` or `auto` detects it).
+    pub from: Option<String>,
+    /// Provider names, comma-separated.
+    pub provider: Option<String>,
+    /// Model names, comma-separated.
+    pub model: Option<String>,
+    /// Translation style.
+    pub style: Option<String>,
+    /// Whether to bypass the cache.
+    pub no_cache: bool,
+    /// Show results as a diff against the first one.
+    pub diff: bool,
+}
+
+/// Result of a comparison, printed with `--output json`.
+#[derive(Debug, Serialize)]
+struct CompareReport<'a> {
+    source_language: Option<&'a str>,
+    target_language: &'a str,
+    style: Option<&'a str>,
+    results: Vec<ResultReport<'a>>,
+}
+
+/// One provider/model result in [`CompareReport`].
+#[derive(Debug, Serialize)]
+struct ResultReport<'a> {
+    provider: &'a str,
+    model: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    translation: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cached: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<Usage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Translates the input with every requested model or provider and
+/// prints the results side by side (or as a diff).
+///
+/// Failures are shown in place of their translation, and make the
+/// command fail after every result has been printed.
+pub async fn run_compare(options: &CompareOptions) -> Result<()> {
+    let (_manager, config_file) = load_config()?;
+
+    let models = compare::split_list(options.model.as_deref());
+    let providers = compare::split_list(options.provider.as_deref());
+    let candidates = compare::select_candidates(
+        &models,
+        &providers,
+        config_file.tl.provider.as_deref(),
+        &config_file,
+    )?;
+    let base_options = ResolveOptions {
+        to: options.to.clone(),
+        style: options.style.clone(),
+        ..ResolveOptions::default()
+    };
+    let contenders = compare::resolve_candidates(&candidates, &base_options, &config_file)?;
+    let from = canonicalize_source_language(options.from.as_deref(), &config_file.aliases)?;
+
+    let source_text = InputReader::read(options.file.as_deref())?;
+    if source_text.is_empty() {
+        return Err(TlError::Input("Input is empty".to_string()).into());
+    }
+
+    // Every contender shares the target, style, and prompt of the first
+    let first = &contenders[0];
+    let source_language = resolve_source_language(from.as_deref(), &source_text);
+    let target = first.target_language_for(source_language.as_deref());
+    let request = TranslationRequest {
+        prompt: first.prompt_options_for(source_language.as_deref(), &target),
+        source_language,
+        source_language_detected: is_detected(from.as_deref()),
+        source_text,
+        target_language: target.clone(),
+        model: first.model.clone(),
+        endpoint: first.endpoint.clone(),
+        style: first.style_prompt.clone(),
+    };
+
+    let cache_manager = if options.no_cache {
+        None
+    } else {
+        Some(CacheManager::new()?)
+    };
+    let labels: Vec<String> = contenders
+        .iter()
+        .map(|resolved| compare::label(&resolved.provider_name, &resolved.model))
+        .collect();
+    let progress = (!output::is_json() && !output::is_quiet())
+        .then(|| MultiSpinner::new(&labels, "waiting..."));
+
+    let entries = compare::run(
+        &request,
+        &contenders,
+        cache_manager.as_ref(),
+        progress.as_ref(),
+    )
+    .await;
+    drop(progress);
+
+    if output::is_json() {
+        output::print_json(&report(&request, first.style_name.as_deref(), &entries))?;
+    } else if options.diff {
+        print!("{}", compare::diff_view(&entries));
+    } else {
+        print!(
+            "{}",
+            compare::side_by_side(&entries, compare::terminal_width())
+        );
+    }
+
+    // The first failure keeps its exit code; the message summarizes them all
+    let total = entries.len();
+    let mut failed = 0;
+    let mut first_error = None;
+    for entry in entries {
+        let label = entry.label();
+        if let Err(err) = entry.outcome {
+            crate::warn!("{} {label} failed: {err}", Style::warning("Warning:"));
+            failed += 1;
+            first_error.get_or_insert(err);
+        }
+    }
+    first_error.map_or(Ok(()), |err| {
+        Err(err.context(format!("{failed} of {total} translations failed")))
+    })
+}
+
+fn report<'a>(
+    request: &'a TranslationRequest,
+    style: Option<&'a str>,
+    entries: &'a [Entry],
+) -> CompareReport<'a> {
+    CompareReport {
+        source_language: request.source_language.as_deref(),
+        target_language: &request.target_language,
+        style,
+        results: entries
+            .iter()
+            .map(|entry| {
+                let mut result = ResultReport {
+                    provider: &entry.provider,
+                    model: &entry.model,
+                    translation: None,
+                    cached: None,
+                    duration_ms: None,
+                    usage: None,
+                    error: None,
+                };
+                match entry.outcome {
+                    Ok(ref outcome) => {
+                        result.translation = Some(&outcome.translation);
+                        result.cached = Some(outcome.cached);
+                        result.duration_ms = Some(outcome.duration_ms);
+                        result.usage = outcome.usage;
+                    }
+                    Err(ref err) => result.error = Some(format!("{err:#}")),
+                }
+                result
+            })
+            .collect(),
+    }
+}
@@ -1,10 +1,11 @@
 //! Configure command handler for editing default settings.
 
-use anyhow::{Result, bail};
+use anyhow::Result;
 use inquire::{Select, Text};
 
 use super::load_config;
 use crate::config::{ConfigFile, TlConfig};
+use crate::error::TlError;
 use crate::style::{PRESETS, sorted_custom_keys};
 use crate::translation::SUPPORTED_LANGUAGES;
 use crate::ui::{Style, handle_prompt_cancellation};
@@ -21,10 +22,12 @@ fn run_configure_inner() -> Result<()> {
 
     // Check if at least one provider is configured
     if config.providers.is_empty() {
-        bail!(
+        return Err(TlError::Config(
             "No providers configured.\n\n\
              Run 'tl providers add' to add a provider first."
-        );
+                .to_string(),
+        )
+        .into());
     }
 
     // Display current defaults
@@ -61,6 +64,12 @@ fn run_configure_inner() -> Result<()> {
         model: Some(model),
         to: Some(to),
         style,
+        pair: config.tl.pair.take(),
+        native_names: config.tl.native_names,
+        prompt_template: config.tl.prompt_template.take(),
+        chat_history: config.tl.chat_history,
+        review_provider: config.tl.review_provider.take(),
+        review_model: config.tl.review_model.take(),
     };
 
     // Save config
@@ -141,7 +150,7 @@ fn select_model(available_models: &[String], default: Option<&str>) -> Result<St
         let model = prompt.prompt()?;
 
         if model.trim().is_empty() {
-            bail!("Model name cannot be empty");
+            return Err(TlError::Usage("Model name cannot be empty".to_string()).into());
         }
 
         Ok(model.trim().to_string())
@@ -7,15 +7,27 @@ use crate::config::{ConfigFile, ConfigManager};
 /// Chat mode command handler.
 pub mod chat;
 
+/// Compare command handler.
+pub mod compare;
+
 /// Configure command handler.
 pub mod configure;
 
 /// Provider management command handler.
 pub mod providers;
 
+/// OpenAI-compatible proxy command handler.
+pub mod proxy;
+
+/// HTTP API server command handler.
+pub mod serve;
+
 /// Style management command handler.
 pub mod styles;
 
+/// Review command handler.
+pub mod review;
+
 /// Translation command handler.
 pub mod translate;
 
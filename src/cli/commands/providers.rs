@@ -1,10 +1,12 @@
 //! Provider management command handler.
 
-use anyhow::{Result, bail};
+use anyhow::Result;
 use inquire::{Confirm, Select, Text};
 
 use super::load_config;
 use crate::config::ProviderConfig;
+use crate::error::TlError;
+use crate::output;
 use crate::ui::{Style, handle_prompt_cancellation};
 
 /// Reserved names that cannot be used as provider names.
@@ -14,6 +16,25 @@ const RESERVED_NAMES: &[&str] = &["add", "edit", "remove", "list"];
 pub fn list_providers() -> Result<()> {
     let (_manager, config) = load_config()?;
 
+    if output::is_json() {
+        let mut names: Vec<_> = config.providers.keys().collect();
+        names.sort();
+        let providers: Vec<_> = names
+            .into_iter()
+            .map(|name| {
+                let provider = &config.providers[name];
+                serde_json::json!({
+                    "name": name,
+                    "endpoint": provider.endpoint,
+                    "models": provider.models,
+                    "requires_api_key": provider.requires_api_key(),
+                    "default": config.tl.provider.as_deref() == Some(name.as_str()),
+                })
+            })
+            .collect();
+        return output::print_json(&serde_json::json!({ "providers": providers }));
+    }
+
     if config.providers.is_empty() {
         println!("{}", Style::warning("No providers configured."));
         println!(
@@ -80,6 +101,7 @@ fn add_provider_inner() -> Result<()> {
         api_key,
         api_key_env,
         models,
+        prompt_template: None,
     };
 
     // Add to config
@@ -109,7 +131,7 @@ fn edit_provider_inner(name: &str) -> Result<()> {
 
     // Check if provider exists
     let Some(provider) = config.providers.get(name) else {
-        bail!("Provider '{name}' not found");
+        return Err(TlError::Config(format!("Provider '{name}' not found")).into());
     };
 
     println!(
@@ -134,6 +156,7 @@ fn edit_provider_inner(name: &str) -> Result<()> {
         api_key,
         api_key_env,
         models,
+        prompt_template: provider.prompt_template.clone(),
     };
 
     config.providers.insert(name.to_string(), provider_config);
@@ -161,15 +184,16 @@ fn remove_provider_inner(name: &str) -> Result<()> {
 
     // Check if provider exists
     if !config.providers.contains_key(name) {
-        bail!("Provider '{name}' not found");
+        return Err(TlError::Config(format!("Provider '{name}' not found")).into());
     }
 
     // Check if this is the default provider
     if config.tl.provider.as_deref() == Some(name) {
-        bail!(
+        return Err(TlError::Config(format!(
             "Cannot remove '{name}' because it is the default provider.\n\n\
              Run 'tl configure' to change the default provider first."
-        );
+        ))
+        .into());
     }
 
     // Check if this is the last provider
@@ -216,15 +240,18 @@ fn input_provider_name(existing_names: &[String]) -> Result<String> {
     let name = name.trim().to_string();
 
     if name.is_empty() {
-        bail!("Provider name cannot be empty");
+        return Err(TlError::Usage("Provider name cannot be empty".to_string()).into());
     }
 
     if RESERVED_NAMES.contains(&name.as_str()) {
-        bail!("Provider name '{name}' is reserved. Choose a different name.");
+        return Err(TlError::Usage(format!(
+            "Provider name '{name}' is reserved. Choose a different name."
+        ))
+        .into());
     }
 
     if existing_names.contains(&name) {
-        bail!("Provider '{name}' already exists");
+        return Err(TlError::Usage(format!("Provider '{name}' already exists")).into());
     }
 
     Ok(name)
@@ -241,12 +268,14 @@ fn input_endpoint(default: Option<&str>) -> Result<String> {
     let endpoint = endpoint.trim().to_string();
 
     if endpoint.is_empty() {
-        bail!("Endpoint URL cannot be empty");
+        return Err(TlError::Usage("Endpoint URL cannot be empty".to_string()).into());
     }
 
     // Basic URL validation
     if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
-        bail!("Endpoint must start with http:// or https://");
+        return Err(
+            TlError::Usage("Endpoint must start with http:// or https://".to_string()).into(),
+        );
     }
 
     Ok(endpoint)
@@ -288,7 +317,10 @@ fn input_api_key_method(
             let env_var = env_var.trim().to_string();
 
             if env_var.is_empty() {
-                bail!("Environment variable name cannot be empty");
+                return Err(TlError::Usage(
+                    "Environment variable name cannot be empty".to_string(),
+                )
+                .into());
             }
 
             Ok((None, Some(env_var)))
@@ -305,7 +337,7 @@ fn input_api_key_method(
             let api_key = api_key.trim().to_string();
 
             if api_key.is_empty() {
-                bail!("API key cannot be empty");
+                return Err(TlError::Usage("API key cannot be empty".to_string()).into());
             }
 
             Ok((Some(api_key), None))
@@ -0,0 +1,57 @@
+//! Proxy command handler for the OpenAI-compatible caching proxy.
+
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+use super::load_config;
+use crate::cache::CacheManager;
+use crate::error::TlError;
+use crate::server::{self, proxy};
+use crate::ui::Style;
+
+/// Options for the proxy command.
+pub struct ProxyOptions {
+    /// Address to bind to.
+    pub host: String,
+    /// Port to listen on (0 picks a free port).
+    pub port: u16,
+    /// Provider to forward every request to.
+    pub provider: Option<String>,
+    /// Whether to bypass the cache.
+    pub no_cache: bool,
+}
+
+/// Runs the proxy until interrupted.
+pub async fn run_proxy(options: ProxyOptions) -> Result<()> {
+    let (_manager, config_file) = load_config()?;
+
+    if let Some(ref name) = options.provider
+        && !config_file.providers.contains_key(name)
+    {
+        return Err(TlError::Config(format!("Provider '{name}' not found")).into());
+    }
+
+    let cache = if options.no_cache {
+        None
+    } else {
+        Some(CacheManager::new()?)
+    };
+
+    let addr = format!("{}:{}", options.host, options.port);
+    let listener = TcpListener::bind(&addr)
+        .await
+        .with_context(|| TlError::Io(format!("Failed to bind to {addr}")))?;
+    let local_addr = listener
+        .local_addr()
+        .with_context(|| TlError::Io("Failed to read listening address".to_string()))?;
+
+    crate::info!(
+        "{} http://{local_addr}/v1 {}",
+        Style::success("Proxying OpenAI-compatible API on"),
+        Style::hint("(Ctrl+C to stop)")
+    );
+
+    let state = proxy::ProxyState::new(config_file, options.provider, cache);
+    server::serve(listener, proxy::router(Arc::new(state))).await
+}
@@ -0,0 +1,200 @@
+//! Review command handler.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::load_config;
+use crate::config::{ConfigFile, ResolveOptions, resolve_config};
+use crate::error::TlError;
+use crate::input::InputReader;
+use crate::output;
+use crate::quality::{self, Issue, MAX_SCORE, Review, ReviewRequest};
+use crate::translation::{
+    TranslationClient, Usage, canonicalize_source_language, resolve_source_language,
+};
+use crate::ui::{Spinner, Style};
+
+/// Options for the review command.
+pub struct ReviewOptions {
+    /// Source file path.
+    pub source: String,
+    /// Translated file path.
+    pub translation: String,
+    /// Target language code (the language of the translation).
+    pub to: Option<String>,
+    /// Source language code (`None` or `auto` detects it).
+    pub from: Option<String>,
+    /// Judge provider name.
+    pub provider: Option<String>,
+    /// Judge model name.
+    pub model: Option<String>,
+    /// Style the translation was made with.
+    pub style: Option<String>,
+    /// Lowest acceptable score; lower scores fail the command.
+    pub min_score: Option<f64>,
+}
+
+/// Result of a review, printed with `--output json`.
+#[derive(Debug, Serialize)]
+struct ReviewReport<'a> {
+    provider: &'a str,
+    model: &'a str,
+    source_language: Option<&'a str>,
+    target_language: &'a str,
+    #[serde(flatten)]
+    review: &'a Review,
+    usage: Option<Usage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_score: Option<f64>,
+    passed: bool,
+}
+
+/// Asks the judge model to score a translation and prints its review.
+pub async fn run_review(options: &ReviewOptions) -> Result<()> {
+    if let Some(min_score) = options.min_score
+        && !(1.0..=f64::from(MAX_SCORE)).contains(&min_score)
+    {
+        return Err(TlError::Usage(format!(
+            "Invalid --min-score: {min_score} (expected a score from 1 to {MAX_SCORE})"
+        ))
+        .into());
+    }
+
+    let (_manager, config_file) = load_config()?;
+    let resolve_options = ResolveOptions {
+        to: options.to.clone(),
+        style: options.style.clone(),
+        ..judge(options, &config_file)
+    };
+    let resolved = resolve_config(&resolve_options, &config_file)?;
+    let from = canonicalize_source_language(options.from.as_deref(), &config_file.aliases)?;
+
+    let source = InputReader::read(Some(&options.source))?;
+    let translation = InputReader::read(Some(&options.translation))?;
+    if source.is_empty() || translation.is_empty() {
+        return Err(TlError::Input("Nothing to review: a file is empty".to_string()).into());
+    }
+
+    let source_language = resolve_source_language(from.as_deref(), &source);
+    let target_language = resolved.target_language_for(source_language.as_deref());
+    let request = ReviewRequest {
+        source: &source,
+        translation: &translation,
+        source_language: source_language.as_deref(),
+        target_language: &target_language,
+        instructions: resolved.style_prompt.as_deref(),
+    };
+    let client = TranslationClient::new(resolved.endpoint.clone(), resolved.api_key.clone());
+
+    let spinner = (!output::is_json() && !output::is_quiet()).then(|| Spinner::new("Reviewing..."));
+    let result = quality::review(&client, &resolved.model, &request).await;
+    if let Some(spinner) = spinner {
+        spinner.stop();
+    }
+    let (review, usage) = result?;
+    let check = quality::check_scores(&review, options.min_score);
+
+    if output::is_json() {
+        output::print_json(&ReviewReport {
+            provider: &resolved.provider_name,
+            model: &resolved.model,
+            source_language: request.source_language,
+            target_language: request.target_language,
+            review: &review,
+            usage,
+            min_score: options.min_score,
+            passed: check.is_ok(),
+        })?;
+    } else {
+        print_review(
+            &review,
+            &format!("{}/{}", resolved.provider_name, resolved.model),
+        );
+    }
+    check
+}
+
+/// Returns the judge's provider and model.
+///
+/// `review_provider` and `review_model` in `[tl]` apply unless given on
+/// the command line. A judge provider other than the default one uses
+/// its first listed model unless a model is set.
+fn judge(options: &ReviewOptions, config_file: &ConfigFile) -> ResolveOptions {
+    let tl = &config_file.tl;
+    let provider = options
+        .provider
+        .clone()
+        .or_else(|| tl.review_provider.clone());
+    let model = options.model.clone().or_else(|| tl.review_model.clone());
+    let model = match provider {
+        Some(ref provider) if model.is_none() && tl.provider.as_ref() != Some(provider) => {
+            config_file
+                .providers
+                .get(provider)
+                .and_then(|p| p.models.first().cloned())
+        }
+        _ => model,
+    };
+    ResolveOptions {
+        provider,
+        model,
+        ..ResolveOptions::default()
+    }
+}
+
+fn print_review(review: &Review, judge: &str) {
+    println!("{} {}", Style::header("Review by"), Style::value(judge));
+    for (name, score) in review.scores.named() {
+        let text = format!("{score}/{MAX_SCORE}");
+        let text = if score >= 4.0 {
+            Style::success(text)
+        } else if score >= 3.0 {
+            Style::warning(text)
+        } else {
+            Style::error(text)
+        };
+        println!("  {}  {text}", Style::label(format!("{name:<11}")));
+    }
+    if !review.summary.is_empty() {
+        println!();
+        println!("{}", review.summary);
+    }
+
+    if review.issues.is_empty() {
+        return;
+    }
+    println!();
+    println!(
+        "{}",
+        Style::header(format!("Issues ({})", review.issues.len()))
+    );
+    for issue in &review.issues {
+        print_issue(issue);
+    }
+}
+
+fn print_issue(issue: &Issue) {
+    let severity = match issue.severity.as_str() {
+        "critical" | "major" => Style::error(&issue.severity),
+        _ => Style::warning(&issue.severity),
+    };
+    let segment = if issue.segment == 0 {
+        "-".to_string()
+    } else {
+        issue.segment.to_string()
+    };
+    println!(
+        "  [{segment}] {severity} · {}: {}",
+        issue.category, issue.comment
+    );
+    let excerpts = [
+        ("source", &issue.source),
+        ("translation", &issue.translation),
+        ("suggestion", &issue.suggestion),
+    ];
+    for (label, text) in excerpts {
+        if let Some(text) = text {
+            println!("      {} {text}", Style::label(format!("{label}:")));
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Serve command handler for the local HTTP API.
+
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+use super::load_config;
+use crate::cache::CacheManager;
+use crate::error::TlError;
+use crate::server::{self, ServerState};
+use crate::ui::Style;
+
+/// Options for the serve command.
+pub struct ServeOptions {
+    /// Address to bind to.
+    pub host: String,
+    /// Port to listen on (0 picks a free port).
+    pub port: u16,
+    /// Maximum number of translations in flight.
+    pub max_concurrency: usize,
+    /// Whether to bypass the cache.
+    pub no_cache: bool,
+}
+
+/// Runs the HTTP API server until interrupted.
+pub async fn run_serve(options: ServeOptions) -> Result<()> {
+    let (_manager, config_file) = load_config()?;
+    let cache = if options.no_cache {
+        None
+    } else {
+        Some(CacheManager::new()?)
+    };
+
+    let addr = format!("{}:{}", options.host, options.port);
+    let listener = TcpListener::bind(&addr)
+        .await
+        .with_context(|| TlError::Io(format!("Failed to bind to {addr}")))?;
+    let local_addr = listener
+        .local_addr()
+        .with_context(|| TlError::Io("Failed to read listening address".to_string()))?;
+
+    crate::info!(
+        "{} http://{local_addr} {}",
+        Style::success("Listening on"),
+        Style::hint("(Ctrl+C to stop)")
+    );
+
+    let state = ServerState::new(config_file, cache, options.max_concurrency);
+    server::serve(listener, server::router(Arc::new(state))).await
+}
@@ -1,17 +1,29 @@
 //! Styles command handler for managing translation styles.
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result};
 use inquire::{Confirm, Editor, Text};
+use serde::{Deserialize, Serialize};
 
 use super::load_config;
 use crate::config::CustomStyle;
-use crate::style::{PRESETS, get_preset, is_preset, sorted_custom_keys, validate_custom_key};
+use crate::error::TlError;
+use crate::input::InputReader;
+use crate::output;
+use crate::style::{
+    PRESETS, StyleError, StylePack, get_preset, is_preset, resolve_style, sorted_custom_keys,
+    styles_json, validate_custom_key,
+};
+use crate::translation::{StyleExample, validate_language, validate_template};
 use crate::ui::{Style, handle_prompt_cancellation};
 
 /// Lists all available styles (presets and custom).
 pub fn list_styles() -> Result<()> {
     let (_manager, config) = load_config()?;
 
+    if output::is_json() {
+        return output::print_json(&styles_json(&config.styles));
+    }
+
     // Print preset styles
     println!("{}", Style::header("Preset styles"));
     for preset in PRESETS {
@@ -43,6 +55,9 @@ pub fn list_styles() -> Result<()> {
 }
 
 /// Shows details of a style (description and prompt).
+///
+/// Custom and combined styles (`formal,technical`) show the fully
+/// expanded prompt, including the styles they extend.
 pub fn show_style(name: &str) -> Result<()> {
     // Check preset first
     if let Some(preset) = get_preset(name) {
@@ -60,25 +75,62 @@ pub fn show_style(name: &str) -> Result<()> {
         return Ok(());
     }
 
-    // Check custom styles
+    // Custom or combined styles
     let (_manager, config) = load_config()?;
+    let resolved =
+        resolve_style(name, &config.styles).map_err(|e| TlError::Usage(e.to_string()))?;
 
-    let custom = config
-        .styles
-        .get(name)
-        .ok_or_else(|| anyhow::anyhow!("Style '{name}' not found"))?;
-
-    println!("{}", Style::header("Custom style"));
-    println!();
-    println!("{}  {}", Style::label("Name:"), Style::value(name));
-    println!(
-        "{}  {}",
-        Style::label("Desc:"),
-        Style::secondary(&custom.description)
-    );
+    if let Some(custom) = config.styles.get(name) {
+        println!("{}", Style::header("Custom style"));
+        println!();
+        println!("{}  {}", Style::label("Name:"), Style::value(name));
+        println!(
+            "{}  {}",
+            Style::label("Desc:"),
+            Style::secondary(&custom.description)
+        );
+        if let Some(ref extends) = custom.extends {
+            println!("{}  {}", Style::label("Extends:"), Style::value(extends));
+        }
+    } else {
+        println!("{}", Style::header("Combined style"));
+        println!();
+        println!(
+            "{}  {}",
+            Style::label("Name:"),
+            Style::value(resolved.key())
+        );
+    }
+    if resolved.parts().len() > 1 {
+        println!(
+            "{}  {}",
+            Style::label("Expands to:"),
+            Style::secondary(resolved.parts().join(" + "))
+        );
+    }
     println!();
     println!("{}", Style::label("Prompt:"));
-    println!("{}", custom.prompt);
+    println!("{}", resolved.prompt());
+    if let Some(template) = resolved.template() {
+        println!();
+        println!("{}", Style::label("Prompt template:"));
+        println!("{template}");
+    }
+    if !resolved.examples().is_empty() {
+        println!();
+        println!("{}", Style::label("Examples:"));
+        for example in resolved.examples() {
+            println!(
+                "  {}",
+                Style::secondary(format!(
+                    "{} → {}",
+                    example.source_language, example.target_language
+                ))
+            );
+            println!("  {}", example.source);
+            println!("  {} {}", Style::secondary("→"), example.target);
+        }
+    }
 
     Ok(())
 }
@@ -99,11 +151,14 @@ fn add_style_inner() -> Result<()> {
     let name = name.trim().to_string();
 
     // Validate name
-    validate_custom_key(&name).map_err(|e| anyhow::anyhow!("{e}"))?;
+    validate_custom_key(&name).map_err(|e| TlError::Usage(e.to_string()))?;
 
     // Check if already exists
     if config.styles.contains_key(&name) {
-        bail!("Style '{name}' already exists. Use 'tl styles edit {name}' to modify it.");
+        return Err(TlError::Usage(format!(
+            "Style '{name}' already exists. Use 'tl styles edit {name}' to modify it."
+        ))
+        .into());
     }
 
     // Get style description (short, for display)
@@ -114,7 +169,7 @@ fn add_style_inner() -> Result<()> {
     let description = description.trim().to_string();
 
     if description.is_empty() {
-        bail!("Description cannot be empty");
+        return Err(TlError::Usage("Description cannot be empty".to_string()).into());
     }
 
     // Get style prompt (instructions for LLM) using editor
@@ -128,7 +183,7 @@ fn add_style_inner() -> Result<()> {
     let prompt = filter_comment_lines(&prompt);
 
     if prompt.is_empty() {
-        bail!("Prompt cannot be empty");
+        return Err(TlError::Usage("Prompt cannot be empty".to_string()).into());
     }
 
     // Save
@@ -137,6 +192,9 @@ fn add_style_inner() -> Result<()> {
         CustomStyle {
             description,
             prompt,
+            prompt_template: None,
+            extends: None,
+            examples: Vec::new(),
         },
     );
     manager.save(&config)?;
@@ -151,6 +209,54 @@ fn add_style_inner() -> Result<()> {
     Ok(())
 }
 
+/// Help text shown above the examples in the editor.
+const EXAMPLES_HELP: &str = "\
+# Example translations for this style, sent to the model before the text
+# to translate when it goes between the same languages. Add one
+# [[examples]] table per pair; delete them all to remove the examples.
+#
+# [[examples]]
+# source_language = \"en\"
+# target_language = \"ja\"
+# source = \"Thanks for your order!\"
+# target = \"ご注文ありがとうございます！\"
+";
+
+/// The examples of a style as edited in the editor.
+#[derive(Serialize, Deserialize)]
+struct ExamplesDocument {
+    #[serde(default)]
+    examples: Vec<StyleExample>,
+}
+
+/// Edits few-shot examples as TOML in the user's editor.
+fn edit_examples(current: &[StyleExample]) -> Result<Vec<StyleExample>> {
+    let document = ExamplesDocument {
+        examples: current.to_vec(),
+    };
+    let text = toml::to_string_pretty(&document).context("Failed to serialize examples")?;
+
+    let edited = Editor::new("Examples (opens editor):")
+        .with_help_message("Edit the example pairs. Save and close editor when done.")
+        .with_predefined_text(&format!("{EXAMPLES_HELP}\n{text}"))
+        .prompt()?;
+
+    let document: ExamplesDocument =
+        toml::from_str(&edited).map_err(|e| TlError::Usage(format!("Invalid examples: {e}")))?;
+    if document
+        .examples
+        .iter()
+        .any(|example| example.source.trim().is_empty() || example.target.trim().is_empty())
+    {
+        return Err(TlError::Usage("Examples need both a source and a target".to_string()).into());
+    }
+    for example in &document.examples {
+        validate_language(&example.source_language)?;
+        validate_language(&example.target_language)?;
+    }
+    Ok(document.examples)
+}
+
 /// Filters out comment lines (starting with #) and trims the result.
 fn filter_comment_lines(text: &str) -> String {
     text.lines()
@@ -169,14 +275,19 @@ pub fn edit_style(name: &str) -> Result<()> {
 fn edit_style_inner(name: &str) -> Result<()> {
     // Check if it's a preset
     if is_preset(name) {
-        bail!("Cannot edit preset style '{name}'. Preset styles are immutable.");
+        return Err(TlError::Usage(format!(
+            "Cannot edit preset style '{name}'. Preset styles are immutable."
+        ))
+        .into());
     }
 
     let (manager, mut config) = load_config()?;
 
     // Check if exists
     let current = config.styles.get(name).cloned().ok_or_else(|| {
-        anyhow::anyhow!("Style '{name}' not found. Use 'tl styles add' to create it.")
+        TlError::Usage(format!(
+            "Style '{name}' not found. Use 'tl styles add' to create it."
+        ))
     })?;
 
     println!(
@@ -194,7 +305,7 @@ fn edit_style_inner(name: &str) -> Result<()> {
     let description = description.trim().to_string();
 
     if description.is_empty() {
-        bail!("Description cannot be empty");
+        return Err(TlError::Usage("Description cannot be empty".to_string()).into());
     }
 
     // Get new prompt using editor
@@ -206,15 +317,31 @@ fn edit_style_inner(name: &str) -> Result<()> {
     let prompt = prompt.trim().to_string();
 
     if prompt.is_empty() {
-        bail!("Prompt cannot be empty");
+        return Err(TlError::Usage("Prompt cannot be empty".to_string()).into());
     }
 
+    // Optionally edit few-shot examples
+    let edit = Confirm::new(&format!(
+        "Edit examples ({} defined)?",
+        current.examples.len()
+    ))
+    .with_default(false)
+    .prompt()?;
+    let examples = if edit {
+        edit_examples(&current.examples)?
+    } else {
+        current.examples
+    };
+
     // Save
     config.styles.insert(
         name.to_string(),
         CustomStyle {
             description,
             prompt,
+            prompt_template: current.prompt_template,
+            extends: current.extends,
+            examples,
         },
     );
     manager.save(&config)?;
@@ -237,14 +364,17 @@ pub fn remove_style(name: &str) -> Result<()> {
 fn remove_style_inner(name: &str) -> Result<()> {
     // Check if it's a preset
     if is_preset(name) {
-        bail!("Cannot remove preset style '{name}'. Preset styles are immutable.");
+        return Err(TlError::Usage(format!(
+            "Cannot remove preset style '{name}'. Preset styles are immutable."
+        ))
+        .into());
     }
 
     let (manager, mut config) = load_config()?;
 
     // Check if exists
     if !config.styles.contains_key(name) {
-        bail!("Style '{name}' not found");
+        return Err(TlError::Usage(format!("Style '{name}' not found")).into());
     }
 
     // Confirm removal
@@ -278,3 +408,84 @@ fn remove_style_inner(name: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// Prints custom styles as a TOML style pack on stdout.
+///
+/// Styles extended by the named ones are included; with no names, all
+/// custom styles are exported.
+pub fn export_styles(names: &[String]) -> Result<()> {
+    let (_manager, config) = load_config()?;
+
+    let pack = StylePack::export(names, &config.styles).map_err(|e| match e {
+        StyleError::PresetImmutable(name) => TlError::Usage(format!(
+            "Cannot export preset style '{name}'. Preset styles are built in."
+        )),
+        e => TlError::Usage(e.to_string()),
+    })?;
+    if pack.styles.is_empty() {
+        return Err(TlError::Usage("No custom styles to export".to_string()).into());
+    }
+
+    let contents = toml::to_string_pretty(&pack).context("Failed to serialize style pack")?;
+    print!("{contents}");
+    Ok(())
+}
+
+/// Adds the custom styles from a TOML style pack (`-` reads stdin).
+///
+/// Styles that already exist with a different definition are conflicts:
+/// nothing is imported unless `overwrite` is set.
+pub fn import_styles(file: &str, overwrite: bool) -> Result<()> {
+    let contents = InputReader::read((file != "-").then_some(file))?;
+    let pack: StylePack = toml::from_str(&contents)
+        .map_err(|e| TlError::Input(format!("Invalid style pack '{file}': {e}")))?;
+    if pack.styles.is_empty() {
+        return Err(TlError::Input(format!("Style pack '{file}' contains no styles")).into());
+    }
+
+    pack.validate()
+        .map_err(|e| TlError::Input(format!("Invalid style pack '{file}': {e}")))?;
+    for (key, style) in &pack.styles {
+        if let Some(ref template) = style.prompt_template {
+            validate_template(template, &format!("style '{key}'"))?;
+        }
+    }
+
+    let (manager, mut config) = load_config()?;
+    let plan = pack.plan(&config.styles);
+    if !plan.conflicts.is_empty() && !overwrite {
+        return Err(TlError::Usage(format!(
+            "These styles already exist with a different definition: {}\n\n\
+             Use --overwrite to replace them",
+            plan.conflicts.join(", ")
+        ))
+        .into());
+    }
+
+    // Every imported style must resolve against the merged styles
+    let keys: Vec<String> = pack.styles.keys().cloned().collect();
+    config.styles.extend(pack.styles);
+    for key in &keys {
+        resolve_style(key, &config.styles)
+            .map_err(|e| TlError::Input(format!("Invalid style pack '{file}': {e}")))?;
+    }
+    manager.save(&config)?;
+
+    for (verb, keys) in [("Added", &plan.added), ("Replaced", &plan.conflicts)] {
+        if !keys.is_empty() {
+            println!(
+                "{} {verb} {}",
+                Style::success("✓"),
+                Style::value(keys.join(", "))
+            );
+        }
+    }
+    if !plan.unchanged.is_empty() {
+        println!(
+            "{}",
+            Style::secondary(format!("Unchanged: {}", plan.unchanged.join(", ")))
+        );
+    }
+
+    Ok(())
+}
@@ -1,22 +1,39 @@
-use anyhow::{Result, bail};
+use anyhow::Result;
 use futures_util::StreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::io::{self, Write};
+use std::time::Instant;
 
 use super::load_config;
 use crate::cache::CacheManager;
-use crate::config::{ResolveOptions, resolve_config};
+use crate::config::{ResolveOptions, ResolvedConfig, resolve_config};
+use crate::error::{self, TlError};
 use crate::fs::atomic_write;
 use crate::input::InputReader;
-use crate::output;
-use crate::translation::{TranslationClient, TranslationRequest};
-use crate::ui::Spinner;
+use crate::output::{self, StreamFormat};
+use crate::quality::{self, Problem, Verification};
+use crate::segment;
+use crate::translation::{
+    PromptOptions, TranslationClient, TranslationRequest, Usage, canonicalize_language,
+    canonicalize_source_language, is_detected, resolve_source_language,
+};
+use crate::ui::{Spinner, Style};
+
+mod incremental;
+mod multi;
 
 /// Options for the translate command.
+#[allow(clippy::struct_excessive_bools)]
 pub struct TranslateOptions {
     /// Input file path (reads from stdin if `None`).
     pub file: Option<String>,
-    /// Target language code.
+    /// Target language code, or several separated by commas.
     pub to: Option<String>,
+    /// Source language code (`None` or `auto` detects it from the input).
+    pub from: Option<String>,
+    /// Language pair (e.g. `ja,en`) for choosing the target from the source language.
+    pub pair: Option<String>,
     /// Provider name.
     pub provider: Option<String>,
     /// Model name.
@@ -27,6 +44,101 @@ pub struct TranslateOptions {
     pub no_cache: bool,
     /// Whether to overwrite the input file with the translation.
     pub write: bool,
+    /// Whether to translate the result back and report the similarity.
+    pub verify: bool,
+    /// Minimum back-translation similarity; lower scores fail the command.
+    pub verify_threshold: Option<f64>,
+    /// Whether to retry once if placeholders or markup were changed.
+    pub fix_placeholders: bool,
+    /// Whether changed placeholders or markup fail the command.
+    pub strict: bool,
+    /// Whether to translate only paragraphs changed since the last run,
+    /// into files next to the input.
+    pub incremental: bool,
+    /// Format of streamed output on stdout.
+    pub stream_format: StreamFormat,
+}
+
+/// How translation progress and results are rendered on stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Render {
+    /// Stream raw text with a spinner.
+    Text,
+    /// Print a single report object when finished.
+    Json,
+    /// Print one event per line as the translation progresses.
+    Ndjson,
+}
+
+/// Result of a translation, printed with `--output json`.
+#[derive(Debug, Serialize)]
+struct TranslationReport<'a> {
+    translation: &'a str,
+    source_language: Option<&'a str>,
+    target_language: &'a str,
+    provider: &'a str,
+    model: &'a str,
+    style: Option<&'a str>,
+    cached: bool,
+    duration_ms: u64,
+    usage: Option<Usage>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    placeholders: &'a [Problem],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verification: Option<VerificationReport<'a>>,
+}
+
+/// Result of `--verify`, included in JSON output and NDJSON `done` events.
+#[derive(Debug, Serialize)]
+struct VerificationReport<'a> {
+    #[serde(flatten)]
+    verification: &'a Verification,
+    threshold: Option<f64>,
+    passed: bool,
+}
+
+impl<'a> VerificationReport<'a> {
+    fn new(verification: &'a Verification, options: &TranslateOptions) -> Self {
+        let threshold = options.verify_threshold;
+        Self {
+            verification,
+            threshold,
+            passed: quality::check_similarity(verification.similarity, threshold).is_ok(),
+        }
+    }
+}
+
+/// An event printed with `--stream-format ndjson`.
+///
+/// Each event is one line of JSON tagged by its `event` field. A run emits
+/// `start`, zero or more `delta`s, and then either `done` or `error`. A
+/// `retry` event means the text so far is discarded: the `delta`s after it
+/// carry the corrected translation.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum NdjsonEvent<'a> {
+    Start {
+        source_language: Option<&'a str>,
+        target_language: &'a str,
+        provider: &'a str,
+        model: &'a str,
+        style: Option<&'a str>,
+    },
+    Delta {
+        text: &'a str,
+    },
+    Retry {
+        placeholders: &'a [Problem],
+    },
+    Done {
+        cached: bool,
+        duration_ms: u64,
+        usage: Option<Usage>,
+        #[serde(skip_serializing_if = "<[_]>::is_empty")]
+        placeholders: &'a [Problem],
+        #[serde(skip_serializing_if = "Option::is_none")]
+        verification: Option<VerificationReport<'a>>,
+    },
 }
 
 /// Runs the translate command.
@@ -34,57 +146,547 @@ pub struct TranslateOptions {
 /// Translates input from a file or stdin and outputs the result.
 /// Supports caching and streaming output.
 pub async fn run_translate(options: TranslateOptions) -> Result<()> {
+    let render = match options.stream_format {
+        StreamFormat::Ndjson => Render::Ndjson,
+        StreamFormat::Text if output::is_json() => Render::Json,
+        StreamFormat::Text => Render::Text,
+    };
+
+    if let Some(threshold) = options.verify_threshold
+        && !(0.0..=1.0).contains(&threshold)
+    {
+        return Err(TlError::Usage(format!(
+            "Invalid --verify-threshold: {threshold} (expected a score from 0.0 to 1.0)"
+        ))
+        .into());
+    }
+
+    let targets = parse_targets(options.to.as_deref());
+    let result = if options.incremental {
+        incremental::translate_incremental(&options, &targets, render).await
+    } else if targets.len() > 1 {
+        multi::translate_many(&options, &targets, render).await
+    } else {
+        translate(&options, render).await
+    };
+
+    // Report failures in-band so NDJSON consumers never have to parse stderr
+    if render == Render::Ndjson
+        && let Err(ref err) = result
+    {
+        let mut event = error::to_json(err);
+        event["event"] = "error".into();
+        output::print_json(&event)?;
+    }
+
+    result
+}
+
+/// Splits a comma-separated list of target languages, dropping duplicates.
+fn parse_targets(to: Option<&str>) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    for target in to.into_iter().flat_map(|to| to.split(',')).map(str::trim) {
+        if !target.is_empty() && !targets.iter().any(|t| t == target) {
+            targets.push(target.to_string());
+        }
+    }
+    targets
+}
+
+async fn translate(options: &TranslateOptions, render: Render) -> Result<()> {
     // Validate -w option requires a file
     if options.write && options.file.is_none() {
-        bail!("--write requires a file argument (cannot write to stdin)");
+        return Err(TlError::Usage(
+            "--write requires a file argument (cannot write to stdin)".to_string(),
+        )
+        .into());
     }
 
+    let started = Instant::now();
+
     let (_manager, config_file) = load_config()?;
     let resolve_options = ResolveOptions {
         to: options.to.clone(),
         provider: options.provider.clone(),
         model: options.model.clone(),
         style: options.style.clone(),
+        pair: options.pair.clone(),
     };
     let resolved = resolve_config(&resolve_options, &config_file)?;
+    let from = canonicalize_source_language(options.from.as_deref(), &config_file.aliases)?;
 
     let source_text = InputReader::read(options.file.as_deref())?;
 
     if source_text.is_empty() {
-        bail!("Input is empty");
+        return Err(TlError::Input("Input is empty".to_string()).into());
     }
 
     let cache_manager = CacheManager::new()?;
+    let request = translation_request(&resolved, source_text, from.as_deref());
 
-    // Create request first, moving values where possible
-    // Only endpoint needs clone (used by both client and request)
-    let request = TranslationRequest {
-        source_text,
-        target_language: resolved.target_language,
-        model: resolved.model,
-        endpoint: resolved.endpoint.clone(),
-        style: resolved.style_prompt,
+    let client = TranslationClient::new(resolved.endpoint.clone(), resolved.api_key.clone());
+
+    let report = |outcome: &Outcome, verification: Option<&Verification>| {
+        let verification =
+            verification.map(|verification| VerificationReport::new(verification, options));
+        match render {
+            Render::Text => Ok(()),
+            Render::Json => output::print_json(&TranslationReport {
+                translation: &outcome.translation,
+                source_language: request.source_language.as_deref(),
+                target_language: &request.target_language,
+                provider: &resolved.provider_name,
+                model: &request.model,
+                style: resolved.style_name.as_deref(),
+                cached: outcome.cached,
+                duration_ms: outcome.duration_ms,
+                usage: outcome.usage,
+                placeholders: &outcome.problems,
+                verification,
+            }),
+            Render::Ndjson => output::print_json(&NdjsonEvent::Done {
+                cached: outcome.cached,
+                duration_ms: outcome.duration_ms,
+                usage: outcome.usage,
+                placeholders: &outcome.problems,
+                verification,
+            }),
+        }
     };
 
-    // Create client with remaining values (endpoint cloned, api_key moved)
-    let client = TranslationClient::new(resolved.endpoint, resolved.api_key);
+    if render == Render::Ndjson {
+        output::print_json(&NdjsonEvent::Start {
+            source_language: request.source_language.as_deref(),
+            target_language: &request.target_language,
+            provider: &resolved.provider_name,
+            model: &request.model,
+            style: resolved.style_name.as_deref(),
+        })?;
+    }
+
+    let skipped = request.is_same_language();
+    let outcome = obtain_translation(&client, &request, &cache_manager, options, render).await?;
+    let mut outcome =
+        validate_placeholders(&client, &request, outcome, &cache_manager, options, render).await?;
+    outcome.duration_ms = started.elapsed().as_millis() as u64;
 
-    if !options.no_cache
-        && let Some(cached) = cache_manager.get(&request)?
+    let verification = verify(
+        &client,
+        &request,
+        &outcome,
+        &resolved,
+        &cache_manager,
+        options,
+        render,
+    )
+    .await?;
+    let check = run_checks(&outcome, verification.as_ref(), options);
+
+    // A failed check leaves the file untouched; a skipped file is already correct
+    if options.write
+        && check.is_ok()
+        && !skipped
+        && !outcome.translation.is_empty()
+        && let Some(ref file_path) = options.file
     {
-        if options.write {
-            if let Some(ref file_path) = options.file {
-                atomic_write(file_path, &cached)?;
-            }
-        } else {
-            print!("{cached}");
+        atomic_write(file_path, &outcome.translation)?;
+    }
+    if render == Render::Text && !options.write && options.fix_placeholders {
+        println!("{}", outcome.translation.trim_end_matches('\n'));
+    }
+
+    report(&outcome, verification.as_ref())?;
+    check
+}
+
+/// Canonicalizes target languages, dropping languages that aliases map
+/// to one already listed.
+fn canonical_targets(
+    requested: &[String],
+    aliases: &HashMap<String, String>,
+) -> Result<Vec<String>> {
+    let mut targets: Vec<String> = Vec::new();
+    for target in requested {
+        let target = canonicalize_language(target, aliases)?;
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    Ok(targets)
+}
+
+/// Builds the request for `source_text`, choosing the target language
+/// from the source language when a pair is configured.
+fn translation_request(
+    resolved: &ResolvedConfig,
+    source_text: String,
+    from: Option<&str>,
+) -> TranslationRequest {
+    let source_language = resolve_source_language(from, &source_text);
+    let target_language = resolved.target_language_for(source_language.as_deref());
+    TranslationRequest {
+        prompt: resolved.prompt_options_for(source_language.as_deref(), &target_language),
+        source_text,
+        target_language,
+        source_language,
+        source_language_detected: is_detected(from),
+        model: resolved.model.clone(),
+        endpoint: resolved.endpoint.clone(),
+        style: resolved.style_prompt.clone(),
+    }
+}
+
+/// Runs the checks that can fail the command (`--strict` and
+/// `--verify-threshold`).
+fn run_checks(
+    outcome: &Outcome,
+    verification: Option<&Verification>,
+    options: &TranslateOptions,
+) -> Result<()> {
+    if options.strict {
+        quality::check_placeholders(&outcome.problems)?;
+    }
+    verification.map_or(Ok(()), |verification| {
+        quality::check_similarity(verification.similarity, options.verify_threshold)
+    })
+}
+
+/// Returns the translation from the cache or the API, printing it as
+/// `render` requires.
+async fn obtain_translation(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+    cache_manager: &CacheManager,
+    options: &TranslateOptions,
+    render: Render,
+) -> Result<Outcome> {
+    if let Some((text, cached)) = lookup_without_api(request, cache_manager, options, render)? {
+        if render == Render::Ndjson {
+            output::print_json(&NdjsonEvent::Delta { text: &text })?;
+        } else if prints_while_streaming(options, render) {
+            print!("{text}");
             io::stdout().flush()?;
         }
-        return Ok(());
+        return Ok(Outcome {
+            translation: text,
+            cached,
+            duration_ms: 0,
+            usage: None,
+            problems: Vec::new(),
+        });
     }
 
-    // Only show spinner in non-quiet mode
-    let spinner = if output::is_quiet() {
+    let (translation, usage) = stream_translation(client, request, options, render).await?;
+    if !options.no_cache && !translation.is_empty() {
+        cache_manager.put(request, &translation)?;
+    }
+    Ok(Outcome {
+        translation,
+        cached: false,
+        duration_ms: 0,
+        usage,
+        problems: Vec::new(),
+    })
+}
+
+/// A finished translation.
+struct Outcome {
+    translation: String,
+    cached: bool,
+    duration_ms: u64,
+    usage: Option<Usage>,
+    /// Placeholders and markup the translation changed.
+    problems: Vec<Problem>,
+}
+
+/// Whether text output is printed as it streams in.
+///
+/// It is held back when writing to a file, and with `--fix-placeholders`
+/// because a retry may replace it.
+const fn prints_while_streaming(options: &TranslateOptions, render: Render) -> bool {
+    matches!(render, Render::Text) && !options.write && !options.fix_placeholders
+}
+
+/// Checks that placeholders and markup survived the translation and, in
+/// text mode, warns about the ones that did not.
+///
+/// With `--fix-placeholders`, a translation with problems is retried once
+/// with a corrective prompt (see [`retranslate`]). The retry is kept, and
+/// replaces the cached translation, if it has fewer problems.
+async fn validate_placeholders(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+    outcome: Outcome,
+    cache_manager: &CacheManager,
+    options: &TranslateOptions,
+    render: Render,
+) -> Result<Outcome> {
+    if request.is_same_language() {
+        return Ok(outcome);
+    }
+    let problems = quality::find_problems(&request.source_text, &outcome.translation);
+    let outcome = if problems.is_empty() || !options.fix_placeholders {
+        Outcome {
+            problems,
+            ..outcome
+        }
+    } else {
+        retry_placeholders(
+            client,
+            request,
+            outcome,
+            problems,
+            cache_manager,
+            options,
+            render,
+        )
+        .await?
+    };
+
+    if render == Render::Text && !options.strict && !outcome.problems.is_empty() {
+        crate::warn!(
+            "{} The translation changed placeholders or markup: {}",
+            Style::warning("Warning:"),
+            quality::describe(&outcome.problems)
+        );
+    }
+    Ok(outcome)
+}
+
+/// Translates again with instructions to fix `problems`.
+async fn retry_placeholders(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+    outcome: Outcome,
+    problems: Vec<Problem>,
+    cache_manager: &CacheManager,
+    options: &TranslateOptions,
+    render: Render,
+) -> Result<Outcome> {
+    match render {
+        Render::Text => {
+            crate::info!(
+                "{} Placeholders or markup changed ({}), retrying",
+                Style::hint("Note:"),
+                quality::describe(&problems)
+            );
+        }
+        Render::Ndjson => output::print_json(&NdjsonEvent::Retry {
+            placeholders: &problems,
+        })?,
+        Render::Json => {}
+    }
+
+    let spinner =
+        (render == Render::Text && !output::is_quiet()).then(|| Spinner::new("Retrying..."));
+    let result = retranslate(client, request, &outcome.translation, &problems).await;
+    if let Some(spinner) = spinner {
+        spinner.stop();
+    }
+    let (translation, usage) = result?;
+    let usage = match (outcome.usage, usage) {
+        (Some(first), Some(retry)) => Some(first + retry),
+        (first, retry) => first.or(retry),
+    };
+
+    let remaining = quality::find_problems(&request.source_text, &translation);
+    let outcome = if !translation.is_empty() && remaining.len() < problems.len() {
+        if !options.no_cache {
+            cache_manager.put(request, &translation)?;
+        }
+        Outcome {
+            translation,
+            cached: false,
+            usage,
+            problems: remaining,
+            ..outcome
+        }
+    } else {
+        Outcome {
+            usage,
+            problems,
+            ..outcome
+        }
+    };
+    if render == Render::Ndjson {
+        output::print_json(&NdjsonEvent::Delta {
+            text: &outcome.translation,
+        })?;
+    }
+    Ok(outcome)
+}
+
+/// Translates `request` again with instructions to fix `problems` in
+/// `translation`.
+///
+/// When the translation has as many paragraphs as the source, only the
+/// paragraphs with problems are sent again, and each retried paragraph is
+/// kept if it fixes some of them. Otherwise the whole text is sent again.
+async fn retranslate(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+    translation: &str,
+    problems: &[Problem],
+) -> Result<(String, Option<Usage>)> {
+    let Some(offending) = quality::paragraph_problems(&request.source_text, translation)
+        .filter(|offending| !offending.is_empty())
+    else {
+        return collect_translation(client, &quality::corrective_request(request, problems)).await;
+    };
+
+    let sources = segment::paragraphs(&request.source_text);
+    let mut replacements = Vec::new();
+    let mut total: Option<Usage> = None;
+    for (index, problems) in offending {
+        let paragraph = TranslationRequest {
+            source_text: sources[index].to_string(),
+            ..request.clone()
+        };
+        let (retried, usage) =
+            collect_translation(client, &quality::corrective_request(&paragraph, &problems))
+                .await?;
+        total = match (total, usage) {
+            (Some(total), Some(usage)) => Some(total + usage),
+            (total, usage) => total.or(usage),
+        };
+        let remaining = quality::find_problems(sources[index], &retried);
+        if !retried.trim().is_empty() && remaining.len() < problems.len() {
+            replacements.push((index, retried));
+        }
+    }
+    Ok((
+        segment::replace_paragraphs(translation, &replacements),
+        total,
+    ))
+}
+
+/// Translates without printing anything, returning the whole text.
+async fn collect_translation(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+) -> Result<(String, Option<Usage>)> {
+    let mut stream = client.translate_stream(request).await?;
+    let mut translation = String::new();
+    while let Some(chunk) = stream.next().await {
+        translation.push_str(&chunk?);
+    }
+    Ok((translation, stream.usage()))
+}
+
+/// Prompt options for back-translation: the configured template and
+/// native names, without the style's template or examples.
+fn back_translation_prompt(resolved: &ResolvedConfig) -> PromptOptions {
+    PromptOptions {
+        native_names: resolved.native_names,
+        template: resolved.prompt_template.clone(),
+        ..PromptOptions::default()
+    }
+}
+
+/// With `--verify`, translates the translation back and, in text mode,
+/// prints the score and the differences from the source to stderr.
+///
+/// Input already in the target language has nothing to verify.
+async fn verify(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+    outcome: &Outcome,
+    resolved: &ResolvedConfig,
+    cache_manager: &CacheManager,
+    options: &TranslateOptions,
+    render: Render,
+) -> Result<Option<Verification>> {
+    if !options.verify || request.is_same_language() {
+        return Ok(None);
+    }
+    let back = &quality::back_translation_request(
+        request,
+        &outcome.translation,
+        back_translation_prompt(resolved),
+    )?;
+    let source_text = &request.source_text;
+    let cached = if options.no_cache {
+        None
+    } else {
+        cache_manager.get(back)?
+    };
+    let verification = if let Some(back_translation) = cached {
+        Verification::new(source_text, back_translation, true, None)
+    } else {
+        let spinner =
+            (render == Render::Text && !output::is_quiet()).then(|| Spinner::new("Verifying..."));
+        let result = collect_translation(client, back).await;
+        if let Some(spinner) = spinner {
+            spinner.stop();
+        }
+        let (back_translation, usage) = result?;
+        if !options.no_cache && !back_translation.is_empty() {
+            cache_manager.put(back, &back_translation)?;
+        }
+        Verification::new(source_text, back_translation, false, usage)
+    };
+
+    if render == Render::Text {
+        let score = format!("{:.0}%", verification.similarity * 100.0);
+        let score = match options.verify_threshold {
+            Some(threshold) if verification.similarity < threshold => Style::error(score),
+            Some(_) => Style::success(score),
+            None => Style::value(score),
+        };
+        crate::info!("{} {score}", Style::label("Back-translation similarity:"));
+        if verification.similarity < 1.0 {
+            crate::info!(
+                "{}\n{}",
+                Style::hint("Differences ([-source-] {+back-translation+}):"),
+                quality::divergences(source_text, &verification.back_translation)
+            );
+        }
+    }
+    Ok(Some(verification))
+}
+
+/// Returns a translation that needs no API call, and whether it was cached.
+///
+/// Input already in the target language is echoed as-is; otherwise the
+/// cache is consulted unless disabled.
+fn lookup_without_api(
+    request: &TranslationRequest,
+    cache_manager: &CacheManager,
+    options: &TranslateOptions,
+    render: Render,
+) -> Result<Option<(String, bool)>> {
+    if request.is_same_language() {
+        if render == Render::Text {
+            crate::info!(
+                "{} Input is already in {}, skipping translation",
+                Style::hint("Note:"),
+                request.target_language
+            );
+        }
+        return Ok(Some((request.source_text.clone(), false)));
+    }
+
+    if options.no_cache {
+        return Ok(None);
+    }
+    Ok(cache_manager.get(request)?.map(|cached| (cached, true)))
+}
+
+/// Streams a translation, printing chunks to stdout as they arrive.
+///
+/// Text is not printed when writing to a file or in JSON mode; the
+/// caller handles the complete translation instead. In NDJSON mode
+/// every chunk is emitted as a `delta` event.
+async fn stream_translation(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+    options: &TranslateOptions,
+    render: Render,
+) -> Result<(String, Option<Usage>)> {
+    let print_text = prints_while_streaming(options, render);
+
+    // Only show spinner in non-quiet text mode
+    let spinner = if output::is_quiet() || render != Render::Text {
         None
     } else {
         let msg = if options.write {
@@ -98,7 +700,7 @@ pub async fn run_translate(options: TranslateOptions) -> Result<()> {
         Some(Spinner::new(&msg))
     };
 
-    let mut stream = client.translate_stream(&request).await?;
+    let mut stream = client.translate_stream(request).await?;
     let mut full_response = String::new();
     let mut spinner_active = spinner.is_some();
 
@@ -106,17 +708,19 @@ pub async fn run_translate(options: TranslateOptions) -> Result<()> {
         let chunk = chunk_result?;
 
         // When streaming to stdout, stop spinner on first chunk to show output
-        // When writing to file, keep spinner until completion
-        if spinner_active && !options.write {
+        // When holding output back, keep spinner until completion
+        if spinner_active && print_text {
             if let Some(ref s) = spinner {
                 s.stop();
             }
             spinner_active = false;
         }
 
-        if !options.write {
+        if print_text {
             print!("{chunk}");
             io::stdout().flush()?;
+        } else if render == Render::Ndjson {
+            output::print_json(&NdjsonEvent::Delta { text: &chunk })?;
         }
         full_response.push_str(&chunk);
     }
@@ -125,21 +729,9 @@ pub async fn run_translate(options: TranslateOptions) -> Result<()> {
         s.stop();
     }
 
-    if !options.write && !full_response.is_empty() {
+    if print_text && !full_response.is_empty() {
         println!();
     }
 
-    if !options.no_cache && !full_response.is_empty() {
-        cache_manager.put(&request, &full_response)?;
-    }
-
-    // Write to file if -w is specified
-    if options.write
-        && !full_response.is_empty()
-        && let Some(ref file_path) = options.file
-    {
-        atomic_write(file_path, &full_response)?;
-    }
-
-    Ok(())
+    Ok((full_response, stream.usage()))
 }
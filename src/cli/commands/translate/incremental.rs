@@ -0,0 +1,527 @@
+//! Incremental translation of files (`--incremental`).
+//!
+//! Each target is written next to the input as `<name>.<lang>.<ext>`,
+//! together with a lock file recording the source it was made from (see
+//! [`crate::snapshot`]). Later runs translate only the paragraphs that
+//! changed; the others keep their current translation, including edits
+//! made by hand. Those edits are also saved in the translation memory as
+//! preferred translations, so that later runs reuse them.
+
+use anyhow::Result;
+use futures_util::StreamExt;
+use futures_util::stream;
+use serde::Serialize;
+use std::fs;
+
+use super::{Render, TranslateOptions, canonical_targets, collect_translation};
+use crate::cache::CacheManager;
+use crate::cli::commands::load_config;
+use crate::config::{ResolveOptions, ResolvedConfig, resolve_config};
+use crate::error::TlError;
+use crate::fs::{atomic_write, localized_path};
+use crate::input::InputReader;
+use crate::output;
+use crate::quality;
+use crate::snapshot::{self, PlannedSegment, Previous, Snapshot, SnapshotSegment};
+use crate::translation::{
+    TranslationClient, TranslationRequest, Usage, canonicalize_source_language, is_detected,
+    resolve_source_language,
+};
+use crate::ui::{MultiSpinner, Style};
+
+/// Paragraphs translated at the same time.
+const CONCURRENCY: usize = 4;
+
+/// A translated file and what to do with each of its paragraphs.
+struct TargetFile<'a> {
+    target_language: String,
+    path: String,
+    lock_path: String,
+    /// Hash of the translation settings, recorded in the lock file.
+    settings: String,
+    segments: Vec<PlannedSegment<'a>>,
+    /// Hand-edited translations whose source paragraph changed or was
+    /// removed.
+    superseded: Vec<SnapshotSegment>,
+    /// New translations for paragraphs without a previous one.
+    translations: Vec<Option<String>>,
+    usage: Option<Usage>,
+    error: Option<anyhow::Error>,
+}
+
+impl TargetFile<'_> {
+    fn pending(&self) -> usize {
+        self.segments
+            .iter()
+            .filter(|segment| segment.previous.is_none())
+            .count()
+    }
+
+    /// Returns the paragraphs kept with a translation edited by hand.
+    fn edited(&self) -> usize {
+        self.segments
+            .iter()
+            .filter(|segment| segment.previous.as_ref().is_some_and(Previous::is_edited))
+            .count()
+    }
+}
+
+/// A paragraph to translate: the index of its file, its index in the
+/// file, and the request.
+type Job = (usize, usize, TranslationRequest);
+
+/// Result of an incremental run for one target, printed with `--output json`.
+#[derive(Debug, Serialize)]
+struct FileReport<'a> {
+    path: &'a str,
+    target_language: &'a str,
+    paragraphs: usize,
+    translated: usize,
+    kept: usize,
+    edited: usize,
+    usage: Option<Usage>,
+}
+
+/// Result of an incremental run, printed with `--output json`.
+#[derive(Debug, Serialize)]
+struct IncrementalReport<'a> {
+    files: Vec<FileReport<'a>>,
+}
+
+/// Translates the paragraphs of the input that changed since the last
+/// run into every target, and updates the translated files.
+pub(super) async fn translate_incremental(
+    options: &TranslateOptions,
+    requested: &[String],
+    render: Render,
+) -> Result<()> {
+    let Some(ref file) = options.file else {
+        return Err(TlError::Usage("--incremental requires a file argument".to_string()).into());
+    };
+    let conflict = [
+        (render == Render::Ndjson, "--stream-format ndjson"),
+        (options.write, "--write"),
+        (options.verify, "--verify"),
+    ]
+    .into_iter()
+    .find_map(|(set, flag)| set.then_some(flag));
+    if let Some(flag) = conflict {
+        return Err(TlError::Usage(format!("--incremental cannot be combined with {flag}")).into());
+    }
+
+    let (_manager, config_file) = load_config()?;
+    let targets = canonical_targets(requested, &config_file.aliases)?;
+    let from = canonicalize_source_language(options.from.as_deref(), &config_file.aliases)?;
+    let resolve_options = ResolveOptions {
+        to: targets.first().cloned(),
+        provider: options.provider.clone(),
+        model: options.model.clone(),
+        style: options.style.clone(),
+        pair: options.pair.clone().filter(|_| targets.is_empty()),
+    };
+    let resolved = resolve_config(&resolve_options, &config_file)?;
+
+    let source_text = InputReader::read(Some(file))?;
+    if source_text.is_empty() {
+        return Err(TlError::Input("Input is empty".to_string()).into());
+    }
+    let source_language = resolve_source_language(from.as_deref(), &source_text);
+    let targets = if targets.is_empty() {
+        vec![resolved.target_language_for(source_language.as_deref())]
+    } else {
+        targets
+    };
+
+    let request = |source: &str, target_language: &str| TranslationRequest {
+        source_text: source.to_string(),
+        target_language: target_language.to_string(),
+        source_language: source_language.clone(),
+        source_language_detected: is_detected(from.as_deref()),
+        model: resolved.model.clone(),
+        endpoint: resolved.endpoint.clone(),
+        style: resolved.style_prompt.clone(),
+        prompt: resolved.prompt_options_for(source_language.as_deref(), target_language),
+    };
+    let mut files = targets
+        .into_iter()
+        .map(|target| {
+            let settings = request("", &target).settings_hash();
+            plan_file(file, target, settings, &source_text, render)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let cache_manager = CacheManager::new()?;
+    if !options.no_cache {
+        for file in &files {
+            for (source, translation) in edits(file) {
+                cache_manager
+                    .put_preferred(&request(source, &file.target_language), translation)?;
+            }
+        }
+    }
+
+    let requests = |file: &TargetFile<'_>| -> Vec<(usize, TranslationRequest)> {
+        file.segments
+            .iter()
+            .enumerate()
+            .filter(|(_, segment)| segment.previous.is_none())
+            .map(|(slot, segment)| (slot, request(segment.source, &file.target_language)))
+            .collect()
+    };
+    let jobs: Vec<Job> = files
+        .iter()
+        .enumerate()
+        .flat_map(|(index, file)| {
+            requests(file)
+                .into_iter()
+                .map(move |(slot, request)| (index, slot, request))
+        })
+        .collect();
+    translate_segments(options, &resolved, &cache_manager, &mut files, jobs, render).await?;
+
+    finish(options, files, render)
+}
+
+/// Returns the source and translation of every paragraph of a target
+/// that was edited by hand since it was translated. Paragraphs deleted by
+/// hand are left out.
+fn edits<'a>(file: &'a TargetFile<'_>) -> impl Iterator<Item = (&'a str, &'a str)> {
+    let kept = file.segments.iter().filter_map(|segment| {
+        let previous = segment
+            .previous
+            .as_ref()
+            .filter(|p| p.is_edited() && !p.current.is_empty())?;
+        Some((segment.source, previous.current.as_str()))
+    });
+    let superseded = file
+        .superseded
+        .iter()
+        .map(|edit| (edit.source.as_str(), edit.translation.as_str()));
+    kept.chain(superseded)
+}
+
+/// Loads the snapshot and current translation of one target.
+///
+/// A translated file without a lock file for its language was not written
+/// by `--incremental` (it may have been translated by hand), so it is
+/// never overwritten. If the translation settings changed since the lock
+/// file was written, the paragraphs nobody edited are translated again.
+fn plan_file<'a>(
+    file: &str,
+    target_language: String,
+    settings: String,
+    source_text: &'a str,
+    render: Render,
+) -> Result<TargetFile<'a>> {
+    let path = localized_path(file, &target_language);
+    let lock_path = snapshot::lock_path(&path);
+    let snapshot = Snapshot::load(&lock_path)?;
+    let output = fs::read_to_string(&path).ok();
+    if output.is_some() {
+        let problem = match snapshot {
+            None => Some(format!("has no lock file ({lock_path})")),
+            Some(ref snapshot) if snapshot.target_language != target_language => Some(format!(
+                "has a lock file for {} rather than {target_language} ({lock_path})",
+                snapshot.target_language
+            )),
+            Some(_) => None,
+        };
+        if let Some(problem) = problem {
+            return Err(TlError::Input(format!(
+                "{path} already exists but {problem}, so it would be overwritten\n\n\
+                 Move or delete it to translate the whole file again"
+            ))
+            .into());
+        }
+    }
+    let snapshot = snapshot.filter(|snapshot| snapshot.target_language == target_language);
+
+    let mut plan = snapshot::plan(source_text, snapshot.as_ref(), output.as_deref());
+    let changed = snapshot
+        .as_ref()
+        .and_then(|snapshot| snapshot.settings.as_ref())
+        .is_some_and(|previous| *previous != settings);
+    if changed {
+        plan.discard_machine_translations();
+        if render == Render::Text {
+            crate::info!(
+                "{} The model, style, or prompt changed since {path} was translated; \
+                 translating its paragraphs again, except those edited by hand",
+                Style::hint("Note:")
+            );
+        }
+    }
+    if render == Render::Text && !plan.superseded.is_empty() {
+        crate::warn!(
+            "{} {} edited {} in {path} will be translated again because the source changed",
+            Style::warning("Warning:"),
+            plan.superseded.len(),
+            paragraphs(plan.superseded.len())
+        );
+    }
+    Ok(TargetFile {
+        translations: vec![None; plan.segments.len()],
+        target_language,
+        path,
+        lock_path,
+        settings,
+        segments: plan.segments,
+        superseded: plan.superseded,
+        usage: None,
+        error: None,
+    })
+}
+
+/// Translates the new and changed paragraphs of all targets.
+async fn translate_segments(
+    options: &TranslateOptions,
+    resolved: &ResolvedConfig,
+    cache_manager: &CacheManager,
+    files: &mut [TargetFile<'_>],
+    jobs: Vec<Job>,
+    render: Render,
+) -> Result<()> {
+    let labels: Vec<String> = files.iter().map(|f| f.target_language.clone()).collect();
+    let progress = (render == Render::Text && !output::is_quiet() && !jobs.is_empty())
+        .then(|| MultiSpinner::new(&labels, "waiting..."));
+    let client = TranslationClient::new(resolved.endpoint.clone(), resolved.api_key.clone());
+
+    let mut done = vec![0; files.len()];
+    let mut results = stream::iter(jobs)
+        .map(|(index, slot, request)| {
+            let client = &client;
+            async move {
+                let result = translate_segment(client, &request, cache_manager, options).await;
+                (index, slot, result)
+            }
+        })
+        .buffered(CONCURRENCY);
+    while let Some((index, slot, result)) = results.next().await {
+        let file = &mut files[index];
+        match result {
+            Ok((translation, usage)) => {
+                file.translations[slot] = Some(translation);
+                file.usage = match (file.usage, usage) {
+                    (Some(total), Some(usage)) => Some(total + usage),
+                    (total, usage) => total.or(usage),
+                };
+            }
+            Err(err) => {
+                file.error.get_or_insert(err);
+            }
+        }
+        done[index] += 1;
+        if let Some(ref progress) = progress {
+            let label = &file.target_language;
+            let pending = file.pending();
+            if file.error.is_some() && done[index] == pending {
+                progress.finish(label, Style::error("failed"));
+            } else if done[index] == pending {
+                progress.finish(label, Style::success("done"));
+            } else {
+                progress.set_message(
+                    label,
+                    format!("translating... {}/{pending} paragraphs", done[index]),
+                );
+            }
+        }
+    }
+    drop(results);
+    if let Some(progress) = progress {
+        for (file, done) in files.iter().zip(done) {
+            if done == 0 {
+                progress.finish(&file.target_language, Style::success("up to date"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Translates one paragraph, using a preferred translation or the cache
+/// when possible.
+///
+/// With `--fix-placeholders`, a translation that changed placeholders or
+/// markup is retried once with a corrective prompt, and the retry is kept
+/// if it has fewer problems. Preferred translations are never retried.
+async fn translate_segment(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+    cache_manager: &CacheManager,
+    options: &TranslateOptions,
+) -> Result<(String, Option<Usage>)> {
+    if request.is_same_language() {
+        return Ok((request.source_text.clone(), None));
+    }
+    if !options.no_cache {
+        if let Some(preferred) = cache_manager.get_preferred(request)? {
+            return Ok((preferred, None));
+        }
+        if let Some(cached) = cache_manager.get(request)? {
+            return fix_placeholders(client, request, cached, None, cache_manager, options).await;
+        }
+    }
+    let (translation, usage) = collect_translation(client, request).await?;
+    if !options.no_cache && !translation.is_empty() {
+        cache_manager.put(request, &translation)?;
+    }
+    fix_placeholders(client, request, translation, usage, cache_manager, options).await
+}
+
+/// Retries a paragraph whose translation changed placeholders or markup,
+/// with `--fix-placeholders`.
+async fn fix_placeholders(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+    translation: String,
+    usage: Option<Usage>,
+    cache_manager: &CacheManager,
+    options: &TranslateOptions,
+) -> Result<(String, Option<Usage>)> {
+    let problems = quality::find_problems(&request.source_text, &translation);
+    if !options.fix_placeholders || problems.is_empty() {
+        return Ok((translation, usage));
+    }
+
+    let (retried, retry_usage) =
+        collect_translation(client, &quality::corrective_request(request, &problems)).await?;
+    let usage = match (usage, retry_usage) {
+        (Some(first), Some(retry)) => Some(first + retry),
+        (first, retry) => first.or(retry),
+    };
+    let remaining = quality::find_problems(&request.source_text, &retried);
+    if retried.is_empty() || remaining.len() >= problems.len() {
+        return Ok((translation, usage));
+    }
+    if !options.no_cache {
+        cache_manager.put(request, &retried)?;
+    }
+    Ok((retried, usage))
+}
+
+/// Writes the translated files and their lock files, and reports the
+/// results. Targets that failed are left untouched.
+fn finish(
+    options: &TranslateOptions,
+    mut files: Vec<TargetFile<'_>>,
+    render: Render,
+) -> Result<()> {
+    let total = files.len();
+    let errors: Vec<Option<anyhow::Error>> =
+        files.iter_mut().map(|file| file.error.take()).collect();
+    let mut first_error = None;
+    let mut failed = 0;
+    let mut reports = Vec::new();
+    for (file, error) in files.iter().zip(errors) {
+        let written = error.map_or_else(|| write_file(options, file), Err);
+        match written {
+            Ok(translated) => reports.push(FileReport {
+                path: &file.path,
+                target_language: &file.target_language,
+                paragraphs: file.segments.len(),
+                translated,
+                kept: file.segments.len() - translated,
+                edited: file.edited(),
+                usage: file.usage,
+            }),
+            Err(err) => {
+                crate::warn!(
+                    "{} Translation to {} failed: {err}",
+                    Style::warning("Warning:"),
+                    file.target_language
+                );
+                failed += 1;
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+
+    match render {
+        Render::Json => output::print_json(&IncrementalReport { files: reports })?,
+        Render::Text => {
+            for report in &reports {
+                if report.translated == 0 {
+                    crate::info!("{} {} is up to date", Style::success("✓"), report.path);
+                } else if report.edited == 0 {
+                    crate::info!(
+                        "{} Wrote {} ({} of {} paragraphs translated)",
+                        Style::success("✓"),
+                        Style::value(report.path),
+                        report.translated,
+                        report.paragraphs
+                    );
+                } else {
+                    crate::info!(
+                        "{} Wrote {} ({} of {} paragraphs translated, {} edited {} kept)",
+                        Style::success("✓"),
+                        Style::value(report.path),
+                        report.translated,
+                        report.paragraphs,
+                        report.edited,
+                        paragraphs(report.edited)
+                    );
+                }
+            }
+        }
+        Render::Ndjson => {}
+    }
+
+    first_error.map_or(Ok(()), |err: anyhow::Error| {
+        Err(err.context(format!("{failed} of {total} translations failed")))
+    })
+}
+
+/// Returns "paragraph" or "paragraphs" for a count.
+const fn paragraphs(count: usize) -> &'static str {
+    if count == 1 {
+        "paragraph"
+    } else {
+        "paragraphs"
+    }
+}
+
+/// Assembles and writes one target, returning how many paragraphs were
+/// translated.
+fn write_file(options: &TranslateOptions, file: &TargetFile<'_>) -> Result<usize> {
+    let mut parts = Vec::with_capacity(file.segments.len());
+    let mut segments = Vec::with_capacity(file.segments.len());
+    for (segment, translation) in file.segments.iter().zip(&file.translations) {
+        let (current, machine) = match (&segment.previous, translation) {
+            (Some(previous), _) => (previous.current.clone(), previous.machine.clone()),
+            (None, Some(translation)) => (translation.clone(), translation.clone()),
+            (None, None) => unreachable!("every new paragraph is translated"),
+        };
+        parts.push(current);
+        segments.push(SnapshotSegment {
+            source: segment.source.to_string(),
+            translation: machine.trim().to_string(),
+        });
+    }
+    let text = snapshot::assemble(&parts);
+
+    let source = file
+        .segments
+        .iter()
+        .map(|segment| segment.source)
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let problems = quality::find_problems(&source, &text);
+    if options.strict {
+        quality::check_placeholders(&problems)?;
+    } else if !problems.is_empty() && !output::is_json() {
+        crate::warn!(
+            "{} {} has changed placeholders or markup: {}",
+            Style::warning("Warning:"),
+            file.path,
+            quality::describe(&problems)
+        );
+    }
+
+    atomic_write(&file.path, &text)?;
+    Snapshot {
+        version: snapshot::VERSION,
+        target_language: file.target_language.clone(),
+        settings: Some(file.settings.clone()),
+        segments,
+    }
+    .save(&file.lock_path)?;
+    Ok(file.pending())
+}
@@ -0,0 +1,302 @@
+//! Translation into several target languages at once (`-t ja,zh,ko`).
+//!
+//! Requests for all targets run concurrently against the same provider.
+//! Each target is cached independently, so rerunning after adding a
+//! language only calls the API for the new one.
+
+use anyhow::Result;
+use futures_util::StreamExt;
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::time::Instant;
+
+use super::{Render, TranslateOptions, TranslationReport, canonical_targets};
+use crate::cache::CacheManager;
+use crate::cli::commands::load_config;
+use crate::config::{ResolveOptions, ResolvedConfig, resolve_config};
+use crate::error::TlError;
+use crate::fs::{atomic_write, localized_path};
+use crate::input::InputReader;
+use crate::output;
+use crate::quality::{self, Problem};
+use crate::translation::{
+    TranslationClient, TranslationRequest, Usage, canonicalize_source_language, is_detected,
+    resolve_source_language,
+};
+use crate::ui::{MultiSpinner, Style};
+
+/// A finished translation into one target language.
+struct Outcome {
+    translation: String,
+    cached: bool,
+    duration_ms: u64,
+    usage: Option<Usage>,
+    /// Placeholders and markup the translation changed.
+    problems: Vec<Problem>,
+}
+
+/// Result of a multi-target translation, printed with `--output json`.
+#[derive(Debug, Serialize)]
+struct MultiReport<'a> {
+    translations: Vec<TranslationReport<'a>>,
+}
+
+/// Translates the input into every language in `targets`.
+///
+/// With `--write`, each translation is saved next to the input file as
+/// `<name>.<lang>.<ext>`; otherwise translations are printed in target
+/// order as labeled sections. Targets that succeed are written even if
+/// others fail.
+pub(super) async fn translate_many(
+    options: &TranslateOptions,
+    requested: &[String],
+    render: Render,
+) -> Result<()> {
+    if render == Render::Ndjson {
+        return Err(TlError::Usage(
+            "--stream-format ndjson supports a single target language".to_string(),
+        )
+        .into());
+    }
+    if options.verify {
+        return Err(
+            TlError::Usage("--verify supports a single target language".to_string()).into(),
+        );
+    }
+    if options.fix_placeholders {
+        return Err(TlError::Usage(
+            "--fix-placeholders supports a single target language".to_string(),
+        )
+        .into());
+    }
+    if options.write && options.file.is_none() {
+        return Err(TlError::Usage(
+            "--write requires a file argument (cannot write to stdin)".to_string(),
+        )
+        .into());
+    }
+
+    let (_manager, config_file) = load_config()?;
+    let targets = canonical_targets(requested, &config_file.aliases)?;
+    let from = canonicalize_source_language(options.from.as_deref(), &config_file.aliases)?;
+
+    let resolve_options = ResolveOptions {
+        to: targets.first().cloned(),
+        provider: options.provider.clone(),
+        model: options.model.clone(),
+        style: options.style.clone(),
+        pair: None,
+    };
+    let resolved = resolve_config(&resolve_options, &config_file)?;
+
+    let source_text = InputReader::read(options.file.as_deref())?;
+    if source_text.is_empty() {
+        return Err(TlError::Input("Input is empty".to_string()).into());
+    }
+
+    let cache_manager = CacheManager::new()?;
+    let source_language = resolve_source_language(from.as_deref(), &source_text);
+    let requests: Vec<TranslationRequest> = targets
+        .iter()
+        .map(|target| TranslationRequest {
+            source_text: source_text.clone(),
+            target_language: target.clone(),
+            source_language: source_language.clone(),
+            source_language_detected: is_detected(from.as_deref()),
+            model: resolved.model.clone(),
+            endpoint: resolved.endpoint.clone(),
+            style: resolved.style_prompt.clone(),
+            prompt: resolved.prompt_options_for(source_language.as_deref(), target),
+        })
+        .collect();
+    let client = TranslationClient::new(resolved.endpoint.clone(), resolved.api_key.clone());
+
+    let progress = (render == Render::Text && !output::is_quiet())
+        .then(|| MultiSpinner::new(&targets, "waiting..."));
+
+    let outcomes = join_all(requests.iter().map(|request| {
+        translate_one(
+            &client,
+            request,
+            &cache_manager,
+            options.no_cache,
+            progress.as_ref(),
+        )
+    }))
+    .await;
+    drop(progress);
+
+    let mut first_error = None;
+    let mut failed = 0;
+    let mut succeeded = Vec::new();
+    for (request, outcome) in requests.iter().zip(outcomes) {
+        match outcome.and_then(|outcome| check_placeholders(options, request, outcome, render)) {
+            Ok(outcome) => succeeded.push((request, outcome)),
+            Err(err) => {
+                crate::warn!(
+                    "{} Translation to {} failed: {err}",
+                    Style::warning("Warning:"),
+                    request.target_language
+                );
+                failed += 1;
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+
+    emit(options, &resolved, &succeeded, render)?;
+
+    // The first failure keeps its exit code; the message summarizes them all
+    first_error.map_or(Ok(()), |err| {
+        Err(err.context(format!("{failed} of {} translations failed", targets.len())))
+    })
+}
+
+/// Translates into a single target, using the cache when possible.
+async fn translate_one(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+    cache_manager: &CacheManager,
+    no_cache: bool,
+    progress: Option<&MultiSpinner>,
+) -> Result<Outcome> {
+    let started = Instant::now();
+    let target = request.target_language.as_str();
+    let finish = |translation: String, cached: bool, usage: Option<Usage>, note: &str| {
+        if let Some(progress) = progress {
+            progress.finish(target, Style::success(note));
+        }
+        Outcome {
+            translation,
+            cached,
+            duration_ms: started.elapsed().as_millis() as u64,
+            usage,
+            problems: Vec::new(),
+        }
+    };
+
+    if request.is_same_language() {
+        return Ok(finish(
+            request.source_text.clone(),
+            false,
+            None,
+            "already in target language",
+        ));
+    }
+    if !no_cache && let Some(cached) = cache_manager.get(request)? {
+        return Ok(finish(cached, true, None, "cached"));
+    }
+
+    let result = async {
+        let mut stream = client.translate_stream(request).await?;
+        let mut translation = String::new();
+        while let Some(chunk) = stream.next().await {
+            translation.push_str(&chunk?);
+            if let Some(progress) = progress {
+                progress.set_message(
+                    target,
+                    format!("translating... {} chars", translation.chars().count()),
+                );
+            }
+        }
+        anyhow::Ok((translation, stream.usage()))
+    }
+    .await;
+
+    let (translation, usage) = match result {
+        Ok(done) => done,
+        Err(err) => {
+            if let Some(progress) = progress {
+                progress.finish(target, Style::error("failed"));
+            }
+            return Err(err);
+        }
+    };
+
+    if !no_cache && !translation.is_empty() {
+        cache_manager.put(request, &translation)?;
+    }
+    Ok(finish(translation, false, usage, "done"))
+}
+
+/// Records the placeholders and markup a translation changed.
+///
+/// Under `--strict` such a translation counts as failed and is not written.
+fn check_placeholders(
+    options: &TranslateOptions,
+    request: &TranslationRequest,
+    outcome: Outcome,
+    render: Render,
+) -> Result<Outcome> {
+    if request.is_same_language() {
+        return Ok(outcome);
+    }
+    let problems = quality::find_problems(&request.source_text, &outcome.translation);
+    if options.strict {
+        quality::check_placeholders(&problems)?;
+    } else if render == Render::Text && !problems.is_empty() {
+        crate::warn!(
+            "{} The translation to {} changed placeholders or markup: {}",
+            Style::warning("Warning:"),
+            request.target_language,
+            quality::describe(&problems)
+        );
+    }
+    Ok(Outcome {
+        problems,
+        ..outcome
+    })
+}
+
+/// Writes or prints the successful translations in target order.
+fn emit(
+    options: &TranslateOptions,
+    resolved: &ResolvedConfig,
+    succeeded: &[(&TranslationRequest, Outcome)],
+    render: Render,
+) -> Result<()> {
+    if options.write
+        && let Some(ref file_path) = options.file
+    {
+        for (request, outcome) in succeeded {
+            let path = localized_path(file_path, &request.target_language);
+            atomic_write(&path, &outcome.translation)?;
+            if render == Render::Text {
+                crate::info!("{} Wrote {}", Style::success("✓"), Style::value(&path));
+            }
+        }
+    }
+
+    match render {
+        Render::Json => output::print_json(&MultiReport {
+            translations: succeeded
+                .iter()
+                .map(|(request, outcome)| TranslationReport {
+                    translation: &outcome.translation,
+                    source_language: request.source_language.as_deref(),
+                    target_language: &request.target_language,
+                    provider: &resolved.provider_name,
+                    model: &request.model,
+                    style: resolved.style_name.as_deref(),
+                    cached: outcome.cached,
+                    duration_ms: outcome.duration_ms,
+                    usage: outcome.usage,
+                    placeholders: &outcome.problems,
+                    verification: None,
+                })
+                .collect(),
+        }),
+        Render::Text if !options.write => {
+            for (index, (request, outcome)) in succeeded.iter().enumerate() {
+                if index > 0 {
+                    println!();
+                }
+                // Plain label: stdout is often redirected to a file
+                println!("=== {} ===", request.target_language);
+                println!("{}", outcome.translation.trim_end_matches('\n'));
+            }
+            Ok(())
+        }
+        Render::Text | Render::Ndjson => Ok(()),
+    }
+}
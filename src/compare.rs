@@ -0,0 +1,513 @@
+//! Comparing translations across models or providers.
+//!
+//! Used by `tl compare` and the chat `/compare` command. The same request
+//! is sent to every provider/model combination concurrently, and the
+//! results are shown side by side or as a diff against the first one.
+
+use anyhow::Result;
+use futures_util::StreamExt;
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::time::Instant;
+use unicode_width::UnicodeWidthStr;
+
+use crate::cache::CacheManager;
+use crate::config::{ConfigFile, ResolveOptions, ResolvedConfig, resolve_config};
+use crate::diff;
+use crate::error::TlError;
+use crate::translation::{TranslationClient, TranslationRequest, Usage};
+use crate::ui::{MultiSpinner, Style};
+
+/// Narrowest column in side-by-side output.
+const MIN_COLUMN_WIDTH: usize = 20;
+
+/// Width used when the terminal size is unknown (e.g. output is piped).
+const DEFAULT_WIDTH: usize = 120;
+
+/// Column separator in side-by-side output.
+const SEPARATOR: &str = " │ ";
+
+/// A provider/model combination to compare.
+///
+/// `None` fields fall back to the usual configuration when resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+}
+
+/// Builds the combinations to compare from comma-separated lists.
+///
+/// With several models, each runs on the given provider, or else on the
+/// provider whose `models` list contains it, or else on `fallback`. With
+/// several providers, each runs the given model or its first listed one.
+///
+/// # Errors
+///
+/// Returns a usage error unless there are at least two combinations,
+/// or if both several models and several providers are given.
+pub fn select_candidates(
+    models: &[String],
+    providers: &[String],
+    fallback: Option<&str>,
+    config_file: &ConfigFile,
+) -> Result<Vec<Candidate>> {
+    if models.len() > 1 && providers.len() > 1 {
+        return Err(TlError::Usage(
+            "Compare either several models or several providers, not both".to_string(),
+        )
+        .into());
+    }
+
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut add = |candidate: Candidate| {
+        if !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    };
+    if providers.len() > 1 {
+        for provider in providers {
+            let model = models.first().cloned().or_else(|| {
+                config_file
+                    .providers
+                    .get(provider)
+                    .and_then(|p| p.models.first().cloned())
+            });
+            add(Candidate {
+                provider: Some(provider.clone()),
+                model,
+            });
+        }
+    } else {
+        for model in models {
+            add(providers.first().map_or_else(
+                || route_model(model, fallback, config_file),
+                |provider| Candidate {
+                    provider: Some(provider.clone()),
+                    model: Some(model.clone()),
+                },
+            ));
+        }
+    }
+
+    if candidates.len() < 2 {
+        return Err(TlError::Usage(
+            "Compare needs at least two models or providers\n\n\
+             Example: tl compare -m gpt-4o,llama3.2 -t ja file.txt"
+                .to_string(),
+        )
+        .into());
+    }
+    Ok(candidates)
+}
+
+/// Resolves the configuration of every combination.
+///
+/// `base` supplies the target language, style, and pair; the provider and
+/// model come from each candidate.
+///
+/// # Errors
+///
+/// Returns an error if any combination cannot be resolved (e.g. an
+/// unknown provider or a missing API key).
+pub fn resolve_candidates(
+    candidates: &[Candidate],
+    base: &ResolveOptions,
+    config_file: &ConfigFile,
+) -> Result<Vec<ResolvedConfig>> {
+    candidates
+        .iter()
+        .map(|candidate| {
+            let options = ResolveOptions {
+                provider: candidate.provider.clone(),
+                model: candidate.model.clone(),
+                ..base.clone()
+            };
+            resolve_config(&options, config_file)
+        })
+        .collect()
+}
+
+/// Picks the provider for `model`: the first provider (by name) whose
+/// `models` list has it, or else `fallback`.
+///
+/// A `fallback` that lists the model itself is always kept.
+pub fn route_model(model: &str, fallback: Option<&str>, config_file: &ConfigFile) -> Candidate {
+    let lists_model = |name: &str| {
+        config_file
+            .providers
+            .get(name)
+            .is_some_and(|provider| provider.models.iter().any(|m| m == model))
+    };
+    let mut names: Vec<&String> = config_file
+        .providers
+        .keys()
+        .filter(|name| lists_model(name))
+        .collect();
+    names.sort();
+    let provider = fallback
+        .filter(|name| lists_model(name))
+        .or_else(|| names.first().map(|name| name.as_str()))
+        .or(fallback);
+    Candidate {
+        provider: provider.map(str::to_string),
+        model: Some(model.to_string()),
+    }
+}
+
+/// Splits a comma-separated option value.
+pub fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .into_iter()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// A finished translation from one combination.
+#[derive(Debug, Clone, Serialize)]
+pub struct Outcome {
+    pub translation: String,
+    pub cached: bool,
+    pub duration_ms: u64,
+    pub usage: Option<Usage>,
+}
+
+/// The result for one provider/model combination.
+#[derive(Debug)]
+pub struct Entry {
+    pub provider: String,
+    pub model: String,
+    pub outcome: Result<Outcome>,
+}
+
+impl Entry {
+    /// Returns the `provider/model` label shown in output.
+    pub fn label(&self) -> String {
+        label(&self.provider, &self.model)
+    }
+
+    /// Returns the timing and token summary, e.g. `1.2s · 85 tokens`.
+    pub fn stats(&self) -> String {
+        let Ok(ref outcome) = self.outcome else {
+            return "failed".to_string();
+        };
+        let mut stats = format!("{:.1}s", outcome.duration_ms as f64 / 1000.0);
+        if outcome.cached {
+            stats.push_str(" · cached");
+        } else if let Some(usage) = outcome.usage {
+            let _ = write!(stats, " · {} tokens", usage.total_tokens);
+        }
+        stats
+    }
+}
+
+/// Returns the `provider/model` label of a combination.
+pub fn label(provider: &str, model: &str) -> String {
+    format!("{provider}/{model}")
+}
+
+/// Sends `base` to every combination concurrently.
+///
+/// Only the model and endpoint of `base` change between combinations, so
+/// all of them get the same prompt. Results are returned in the order of
+/// `contenders`; a failure in one does not affect the others.
+pub async fn run(
+    base: &TranslationRequest,
+    contenders: &[ResolvedConfig],
+    cache: Option<&CacheManager>,
+    progress: Option<&MultiSpinner>,
+) -> Vec<Entry> {
+    let outcomes = join_all(contenders.iter().map(|resolved| async move {
+        let request = TranslationRequest {
+            model: resolved.model.clone(),
+            endpoint: resolved.endpoint.clone(),
+            ..base.clone()
+        };
+        let client = TranslationClient::new(resolved.endpoint.clone(), resolved.api_key.clone());
+        let label = label(&resolved.provider_name, &resolved.model);
+        let outcome = translate_one(&client, &request, cache, progress, &label).await;
+        if let Some(progress) = progress {
+            let message = match outcome {
+                Ok(ref outcome) if outcome.cached => Style::success("cached"),
+                Ok(_) => Style::success("done"),
+                Err(_) => Style::error("failed"),
+            };
+            progress.finish(&label, message);
+        }
+        outcome
+    }))
+    .await;
+
+    contenders
+        .iter()
+        .zip(outcomes)
+        .map(|(resolved, outcome)| Entry {
+            provider: resolved.provider_name.clone(),
+            model: resolved.model.clone(),
+            outcome,
+        })
+        .collect()
+}
+
+async fn translate_one(
+    client: &TranslationClient,
+    request: &TranslationRequest,
+    cache: Option<&CacheManager>,
+    progress: Option<&MultiSpinner>,
+    label: &str,
+) -> Result<Outcome> {
+    let started = Instant::now();
+    let elapsed = || started.elapsed().as_millis() as u64;
+
+    if request.is_same_language() {
+        return Ok(Outcome {
+            translation: request.source_text.clone(),
+            cached: false,
+            duration_ms: elapsed(),
+            usage: None,
+        });
+    }
+    if let Some(cache) = cache
+        && let Some(translation) = cache.get(request)?
+    {
+        return Ok(Outcome {
+            translation,
+            cached: true,
+            duration_ms: elapsed(),
+            usage: None,
+        });
+    }
+
+    let mut stream = client.translate_stream(request).await?;
+    let mut translation = String::new();
+    while let Some(chunk) = stream.next().await {
+        translation.push_str(&chunk?);
+        if let Some(progress) = progress {
+            progress.set_message(
+                label,
+                format!("translating... {} chars", translation.chars().count()),
+            );
+        }
+    }
+
+    if let Some(cache) = cache
+        && !translation.is_empty()
+    {
+        cache.put(request, &translation)?;
+    }
+    Ok(Outcome {
+        translation,
+        cached: false,
+        duration_ms: elapsed(),
+        usage: stream.usage(),
+    })
+}
+
+/// Returns the terminal width, or a default when it is unknown.
+pub fn terminal_width() -> usize {
+    crossterm::terminal::size().map_or(DEFAULT_WIDTH, |(columns, _)| usize::from(columns))
+}
+
+/// Renders the results in columns that fit in `width`.
+pub fn side_by_side(entries: &[Entry], width: usize) -> String {
+    let count = entries.len().max(1);
+    let column_width =
+        (width.saturating_sub(SEPARATOR.width() * (count - 1)) / count).max(MIN_COLUMN_WIDTH);
+
+    let columns: Vec<Vec<String>> = entries
+        .iter()
+        .map(|entry| {
+            let body = match entry.outcome {
+                Ok(ref outcome) => outcome.translation.trim_end().to_string(),
+                Err(ref err) => format!("Error: {err}"),
+            };
+            let mut lines = wrap(&entry.label(), column_width);
+            lines.extend(wrap(&entry.stats(), column_width));
+            lines.push("─".repeat(column_width));
+            lines.extend(wrap(&body, column_width));
+            lines
+        })
+        .collect();
+
+    let height = columns.iter().map(Vec::len).max().unwrap_or(0);
+    let mut out = String::new();
+    for row in 0..height {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|lines| {
+                let cell = lines.get(row).map_or("", String::as_str);
+                let padding = column_width.saturating_sub(cell.width());
+                format!("{cell}{}", " ".repeat(padding))
+            })
+            .collect();
+        let _ = writeln!(out, "{}", cells.join(SEPARATOR).trim_end());
+    }
+    out
+}
+
+/// Renders each result after the first as a word diff against the first.
+///
+/// Removed words are shown as `[-word-]` and added words as `{+word+}`
+/// (colored when colors are enabled).
+pub fn diff_view(entries: &[Entry]) -> String {
+    let mut out = String::new();
+    let Some((baseline, others)) = entries.split_first() else {
+        return out;
+    };
+    // Plain labels: stdout is often redirected to a file
+    let _ = writeln!(out, "=== {} ({}) ===", baseline.label(), baseline.stats());
+    let base_text = match baseline.outcome {
+        Ok(ref outcome) => outcome.translation.trim_end(),
+        Err(ref err) => {
+            let _ = writeln!(out, "Error: {err}");
+            ""
+        }
+    };
+    let _ = writeln!(out, "{base_text}");
+
+    for entry in others {
+        let _ = writeln!(out, "\n=== {} ({}) ===", entry.label(), entry.stats());
+        match entry.outcome {
+            Ok(ref outcome) => {
+                let _ = writeln!(
+                    out,
+                    "{}",
+                    diff::render_changes(&diff::diff_words(
+                        base_text,
+                        outcome.translation.trim_end()
+                    ))
+                );
+            }
+            Err(ref err) => {
+                let _ = writeln!(out, "Error: {err}");
+            }
+        }
+    }
+    out
+}
+
+/// Wraps text to `width` display columns, breaking between words where
+/// possible.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for token in diff::tokenize(paragraph) {
+            if line.width() + token.width() > width && !line.is_empty() {
+                lines.push(std::mem::take(&mut line).trim_end().to_string());
+            }
+            if line.is_empty() && token.trim().is_empty() {
+                continue;
+            }
+            if token.width() <= width {
+                line.push_str(token);
+                continue;
+            }
+            // A single token wider than the column is split by character
+            for c in token.chars() {
+                if line.width() + c.to_string().width() > width {
+                    lines.push(std::mem::take(&mut line));
+                }
+                line.push(c);
+            }
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::config::ProviderConfig;
+
+    fn config_file() -> ConfigFile {
+        let mut config = ConfigFile::default();
+        for (name, models) in [("ollama", vec!["llama3.2"]), ("openai", vec!["gpt-4o"])] {
+            config.providers.insert(
+                name.to_string(),
+                ProviderConfig {
+                    endpoint: format!("http://{name}"),
+                    api_key: None,
+                    api_key_env: None,
+                    models: models.into_iter().map(str::to_string).collect(),
+                    prompt_template: None,
+                },
+            );
+        }
+        config
+    }
+
+    fn entry(model: &str, translation: &str) -> Entry {
+        Entry {
+            provider: "test".to_string(),
+            model: model.to_string(),
+            outcome: Ok(Outcome {
+                translation: translation.to_string(),
+                cached: false,
+                duration_ms: 1250,
+                usage: Some(Usage {
+                    prompt_tokens: 10,
+                    completion_tokens: 5,
+                    total_tokens: 15,
+                }),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_select_candidates_routes_models_to_providers() {
+        let models = split_list(Some("gpt-4o, llama3.2,other"));
+        let candidates = select_candidates(&models, &[], Some("ollama"), &config_file()).unwrap();
+        let providers: Vec<_> = candidates
+            .iter()
+            .map(|c| c.provider.as_deref().unwrap())
+            .collect();
+        assert_eq!(providers, ["openai", "ollama", "ollama"]);
+    }
+
+    #[test]
+    fn test_select_candidates_by_provider() {
+        let providers = split_list(Some("ollama,openai"));
+        let candidates = select_candidates(&[], &providers, None, &config_file()).unwrap();
+        assert_eq!(
+            candidates[1],
+            Candidate {
+                provider: Some("openai".to_string()),
+                model: Some("gpt-4o".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_select_candidates_needs_two() {
+        let config = config_file();
+        assert!(select_candidates(&split_list(Some("gpt-4o")), &[], None, &config).is_err());
+        let both = split_list(Some("a,b"));
+        assert!(select_candidates(&both, &both, None, &config).is_err());
+    }
+
+    #[test]
+    fn test_side_by_side_columns() {
+        let entries = [entry("a", "Hello world"), entry("b", "こんにちは世界")];
+        let output = side_by_side(&entries, 50);
+        let lines: Vec<_> = output.lines().collect();
+        assert!(lines[0].starts_with("test/a"));
+        assert!(lines[0].contains(" │ test/b"));
+        assert!(lines[1].contains("1.2s · 15 tokens"));
+        assert!(lines[3].contains("Hello world"));
+        assert!(lines[3].contains("こんにちは世界"));
+    }
+
+    #[test]
+    fn test_wrap_breaks_between_words_and_wide_characters() {
+        assert_eq!(wrap("one two three", 7), ["one two", "three"]);
+        assert_eq!(wrap("日本語です", 4), ["日本", "語で", "す"]);
+        assert_eq!(wrap("abcdefgh", 3), ["abc", "def", "gh"]);
+    }
+}
@@ -1,11 +1,15 @@
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::error::TlError;
 use crate::paths;
 use crate::style;
+use crate::translation::{
+    LanguagePair, PromptOptions, StyleExample, canonicalize_language, validate_template,
+};
 use crate::ui::Style;
 
 /// Default settings in the `[tl]` section of config.toml.
@@ -19,6 +23,21 @@ pub struct TlConfig {
     pub to: Option<String>,
     /// Default translation style.
     pub style: Option<String>,
+    /// Bidirectional language pair, e.g. `["ja", "en"]`.
+    ///
+    /// Input in the first language is translated into the second, and
+    /// everything else into the first. Takes precedence over `to`.
+    pub pair: Option<Vec<String>>,
+    /// Whether to add native language names in the prompt ("Japanese (日本語)").
+    pub native_names: Option<bool>,
+    /// System prompt template replacing the built-in one.
+    pub prompt_template: Option<String>,
+    /// Whether chat sessions and input are saved (default: true).
+    pub chat_history: Option<bool>,
+    /// Provider of the judge model for `tl review` (default: `provider`).
+    pub review_provider: Option<String>,
+    /// Judge model for `tl review` (default: `model`).
+    pub review_model: Option<String>,
 }
 
 /// Configuration for a translation provider.
@@ -37,6 +56,9 @@ pub struct ProviderConfig {
     /// List of available models for this provider.
     #[serde(default)]
     pub models: Vec<String>,
+    /// System prompt template for this provider (overrides the global one).
+    #[serde(default)]
+    pub prompt_template: Option<String>,
 }
 
 impl ProviderConfig {
@@ -58,12 +80,25 @@ impl ProviderConfig {
 }
 
 /// A custom translation style defined by the user.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CustomStyle {
     /// Short description displayed in lists.
     pub description: String,
     /// The actual prompt sent to the LLM.
     pub prompt: String,
+    /// System prompt template used with this style (overrides provider and global ones).
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+    /// Styles this style builds on, e.g. `"formal"` or `"formal,technical"`.
+    ///
+    /// Their prompts come before this style's own prompt.
+    #[serde(default)]
+    pub extends: Option<String>,
+    /// Example translations showing the style (few-shot prompting).
+    ///
+    /// Each is only sent when translating between its languages.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<StyleExample>,
 }
 
 /// The complete configuration file structure.
@@ -80,6 +115,12 @@ pub struct ConfigFile {
     /// Custom translation styles keyed by name.
     #[serde(default)]
     pub styles: HashMap<String, CustomStyle>,
+    /// Language aliases, e.g. `br = "pt-BR"`.
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    /// Extra prompt instructions per target language, e.g. `de = "Use du."`.
+    #[serde(default)]
+    pub language_prompts: HashMap<String, String>,
 }
 
 /// Resolved configuration after merging CLI arguments and config file.
@@ -99,6 +140,70 @@ pub struct ResolvedConfig {
     pub style_name: Option<String>,
     /// The resolved translation style prompt (for LLM).
     pub style_prompt: Option<String>,
+    /// Language pair for automatic target selection, if enabled.
+    pub pair: Option<LanguagePair>,
+    /// Whether to add native language names in the prompt.
+    pub native_names: bool,
+    /// Extra prompt instructions keyed by canonical language tag.
+    pub language_prompts: HashMap<String, String>,
+    /// System prompt template from the provider or `[tl]` (`None` uses the built-in one).
+    pub prompt_template: Option<String>,
+    /// System prompt template of the custom style; takes precedence over `prompt_template`.
+    pub style_template: Option<String>,
+    /// Few-shot examples of the style, for every language pair.
+    pub style_examples: Vec<StyleExample>,
+}
+
+impl ResolvedConfig {
+    /// Returns the target language for input in `source`.
+    ///
+    /// With a language pair the target depends on the source language;
+    /// otherwise it is always `target_language`.
+    pub fn target_language_for(&self, source: Option<&str>) -> String {
+        self.pair.as_ref().map_or_else(
+            || self.target_language.clone(),
+            |pair| pair.target_for(source).to_string(),
+        )
+    }
+
+    /// Sets the style-related fields from `style`, or clears them with `None`.
+    pub fn apply_style(&mut self, style: Option<&style::ResolvedStyle>) {
+        self.style_name = style.map(|s| s.key().to_string());
+        self.style_prompt = style.map(|s| s.prompt().to_string());
+        self.style_template = style
+            .and_then(style::ResolvedStyle::template)
+            .map(str::to_string);
+        self.style_examples = style.map(|s| s.examples().to_vec()).unwrap_or_default();
+    }
+
+    /// Returns the prompt options for translating from `source` into `target`.
+    ///
+    /// The language addendum is looked up by the full tag first and then
+    /// by its primary language, so a `pt` entry also applies to `pt-BR`.
+    /// Only style examples for these languages are included.
+    pub fn prompt_options_for(&self, source: Option<&str>, target: &str) -> PromptOptions {
+        let primary = target.split('-').next().unwrap_or(target);
+        let language_prompt = self
+            .language_prompts
+            .get(target)
+            .or_else(|| self.language_prompts.get(primary))
+            .cloned();
+        PromptOptions {
+            native_names: self.native_names,
+            language_prompt,
+            template: self
+                .style_template
+                .clone()
+                .or_else(|| self.prompt_template.clone()),
+            examples: self
+                .style_examples
+                .iter()
+                .filter(|example| example.applies_to(source, target))
+                .map(StyleExample::pair)
+                .collect(),
+            context: Vec::new(),
+        }
+    }
 }
 
 /// Options for resolving configuration.
@@ -114,6 +219,8 @@ pub struct ResolveOptions {
     pub model: Option<String>,
     /// Style name override.
     pub style: Option<String>,
+    /// Language pair override, e.g. `ja,en` (ignored when `to` is given).
+    pub pair: Option<String>,
 }
 
 /// Resolves configuration by merging CLI options with config file settings.
@@ -128,6 +235,10 @@ pub fn resolve_config(
     options: &ResolveOptions,
     config_file: &ConfigFile,
 ) -> Result<ResolvedConfig> {
+    // Resolve target language (and language pair) first, so that invalid
+    // language codes are reported before configuration problems
+    let (target_language, pair) = resolve_target_language(options, config_file)?;
+
     // Resolve provider
     let provider_name = options
         .provider
@@ -135,11 +246,12 @@ pub fn resolve_config(
         .or(config_file.tl.provider.as_ref())
         .cloned()
         .ok_or_else(|| {
-            anyhow::anyhow!(
+            TlError::Config(
                 "Missing required configuration: 'provider'\n\n\
                  Please provide it via:\n  \
                  - CLI option: tl --provider <name>\n  \
                  - Config file: ~/.config/tl/config.toml"
+                    .to_string(),
             )
         })?;
 
@@ -147,12 +259,12 @@ pub fn resolve_config(
     let provider_config = config_file.providers.get(&provider_name).ok_or_else(|| {
         let available: Vec<_> = config_file.providers.keys().collect();
         if available.is_empty() {
-            anyhow::anyhow!(
+            TlError::Config(format!(
                 "Provider '{provider_name}' not found\n\n\
                  No providers configured. Add providers to ~/.config/tl/config.toml"
-            )
+            ))
         } else {
-            anyhow::anyhow!(
+            TlError::Config(format!(
                 "Provider '{provider_name}' not found\n\n\
                  Available providers:\n  \
                  - {}\n\n\
@@ -162,7 +274,7 @@ pub fn resolve_config(
                     .map(|s| s.as_str())
                     .collect::<Vec<_>>()
                     .join("\n  - ")
-            )
+            ))
         }
     })?;
 
@@ -173,11 +285,12 @@ pub fn resolve_config(
         .or(config_file.tl.model.as_ref())
         .cloned()
         .ok_or_else(|| {
-            anyhow::anyhow!(
+            TlError::Config(
                 "Missing required configuration: 'model'\n\n\
                  Please provide it via:\n  \
                  - CLI option: tl --model <name>\n  \
                  - Config file: ~/.config/tl/config.toml"
+                    .to_string(),
             )
         })?;
 
@@ -194,55 +307,163 @@ pub fn resolve_config(
         );
     }
 
-    // Resolve target language
-    let target_language = options
-        .to
-        .as_ref()
-        .or(config_file.tl.to.as_ref())
-        .cloned()
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "Missing required configuration: 'to' (target language)\n\n\
-                 Please provide it via:\n  \
-                 - CLI option: tl --to <lang>\n  \
-                 - Config file: ~/.config/tl/config.toml"
-            )
-        })?;
-
     // Get API key
     let api_key = provider_config.get_api_key();
 
     // Check if API key is required but missing
     if provider_config.requires_api_key() && api_key.is_none() {
         let env_var = provider_config.api_key_env.as_deref().unwrap_or("API_KEY");
-        bail!(
-            "Provider '{provider_name}' requires an API key\n\n\
-             Set the {env_var} environment variable:\n  \
-             export {env_var}=\"your-api-key\"\n\n\
-             Or set api_key in ~/.config/tl/config.toml"
-        );
+        return Err(TlError::Auth {
+            message: format!(
+                "Provider '{provider_name}' requires an API key\n\n\
+                 Set the {env_var} environment variable:\n  \
+                 export {env_var}=\"your-api-key\"\n\n\
+                 Or set api_key in ~/.config/tl/config.toml"
+            ),
+            status: None,
+        }
+        .into());
     }
 
     // Resolve style (optional)
-    let style_key = options.style.as_ref().or(config_file.tl.style.as_ref());
+    let style = resolve_style_option(options, config_file)?;
+    if let Some(ref style) = style {
+        checked_template(style.template(), &format!("style '{}'", style.key()))?;
+    }
 
-    let (style_name, style_prompt) = if let Some(key) = style_key {
-        let resolved =
-            style::resolve_style(key, &config_file.styles).map_err(|e| anyhow::anyhow!("{e}"))?;
-        (Some(key.clone()), Some(resolved.prompt().to_string()))
-    } else {
-        (None, None)
-    };
+    let prompt_template = resolve_prompt_template(&provider_name, provider_config, config_file)?;
 
-    Ok(ResolvedConfig {
+    let mut resolved = ResolvedConfig {
         provider_name,
         endpoint: provider_config.endpoint.clone(),
         model,
         api_key,
         target_language,
-        style_name,
-        style_prompt,
-    })
+        style_name: None,
+        style_prompt: None,
+        pair,
+        native_names: config_file.tl.native_names.unwrap_or(false),
+        language_prompts: resolve_language_prompts(config_file)?,
+        prompt_template,
+        style_template: None,
+        style_examples: Vec::new(),
+    };
+    resolved.apply_style(style.as_ref());
+    Ok(resolved)
+}
+
+/// Resolves the prompt template of the provider, falling back to `[tl]`.
+fn resolve_prompt_template(
+    provider_name: &str,
+    provider_config: &ProviderConfig,
+    config_file: &ConfigFile,
+) -> Result<Option<String>> {
+    let section = format!("[providers.{provider_name}]");
+    let template = checked_template(provider_config.prompt_template.as_deref(), &section)?;
+    if template.is_some() {
+        return Ok(template);
+    }
+    checked_template(config_file.tl.prompt_template.as_deref(), "[tl]")
+}
+
+/// Validates a configured prompt template.
+///
+/// An unknown placeholder is a configuration error naming the section
+/// the template came from.
+fn checked_template(template: Option<&str>, section: &str) -> Result<Option<String>> {
+    template
+        .map(|template| {
+            validate_template(template, section)?;
+            Ok(template.to_string())
+        })
+        .transpose()
+}
+
+/// Canonicalizes the keys of `[language_prompts]`.
+///
+/// An invalid language key is a configuration error.
+fn resolve_language_prompts(config_file: &ConfigFile) -> Result<HashMap<String, String>> {
+    config_file
+        .language_prompts
+        .iter()
+        .map(|(lang, prompt)| {
+            let lang = canonicalize_language(lang, &config_file.aliases)
+                .map_err(|e| TlError::Config(format!("Invalid key in [language_prompts]: {e}")))?;
+            Ok((lang, prompt.clone()))
+        })
+        .collect()
+}
+
+/// Resolves the target language and the optional language pair.
+///
+/// An explicit `--to` disables the pair; otherwise the pair's primary
+/// language becomes the default target. Languages are canonicalized
+/// through `[aliases]`; invalid ones are usage errors on the command line
+/// and configuration errors in the config file.
+fn resolve_target_language(
+    options: &ResolveOptions,
+    config_file: &ConfigFile,
+) -> Result<(String, Option<LanguagePair>)> {
+    let aliases = &config_file.aliases;
+    let config_error = |key: &str, e: anyhow::Error| {
+        TlError::Config(format!("Invalid '{key}' in config file: {e}"))
+    };
+
+    if let Some(ref to) = options.to {
+        return Ok((canonicalize_language(to, aliases)?, None));
+    }
+
+    let pair = if let Some(ref pair) = options.pair {
+        Some(LanguagePair::parse(pair, aliases)?)
+    } else {
+        config_file
+            .tl
+            .pair
+            .as_deref()
+            .map(|codes| {
+                LanguagePair::from_codes(codes, aliases).map_err(|e| config_error("pair", e))
+            })
+            .transpose()?
+    };
+    if let Some(ref pair) = pair {
+        return Ok((pair.primary.clone(), Some(pair.clone())));
+    }
+
+    let to = config_file.tl.to.as_deref().ok_or_else(|| {
+        TlError::Config(
+            "Missing required configuration: 'to' (target language)\n\n\
+             Please provide it via:\n  \
+             - CLI option: tl --to <lang>\n  \
+             - Config file: ~/.config/tl/config.toml"
+                .to_string(),
+        )
+    })?;
+    let target_language = canonicalize_language(to, aliases).map_err(|e| config_error("to", e))?;
+
+    Ok((target_language, pair))
+}
+
+/// Resolves the optional (possibly combined) style.
+///
+/// An unknown style is a usage error when given on the command line,
+/// and a configuration error when it comes from the config file.
+fn resolve_style_option(
+    options: &ResolveOptions,
+    config_file: &ConfigFile,
+) -> Result<Option<style::ResolvedStyle>, TlError> {
+    let Some(key) = options.style.as_ref().or(config_file.tl.style.as_ref()) else {
+        return Ok(None);
+    };
+
+    let resolved = style::resolve_style(key, &config_file.styles).map_err(|e| {
+        if options.style.is_some() {
+            TlError::Usage(e.to_string())
+        } else {
+            TlError::Config(e.to_string())
+        }
+    })?;
+
+    Ok(Some(resolved))
 }
 
 /// Manages loading and saving configuration files.
@@ -267,11 +488,14 @@ impl ConfigManager {
 
     pub fn load(&self) -> Result<ConfigFile> {
         let contents = fs::read_to_string(&self.config_path).with_context(|| {
-            format!("Failed to read config file: {}", self.config_path.display())
+            TlError::Io(format!(
+                "Failed to read config file: {}",
+                self.config_path.display()
+            ))
         })?;
 
-        let config_file: ConfigFile =
-            toml::from_str(&contents).with_context(|| "Failed to parse config file")?;
+        let config_file: ConfigFile = toml::from_str(&contents)
+            .with_context(|| TlError::Config("Failed to parse config file".to_string()))?;
 
         Ok(config_file)
     }
@@ -279,17 +503,20 @@ impl ConfigManager {
     pub fn save(&self, config: &ConfigFile) -> Result<()> {
         if let Some(parent) = self.config_path.parent() {
             fs::create_dir_all(parent).with_context(|| {
-                format!("Failed to create config directory: {}", parent.display())
+                TlError::Io(format!(
+                    "Failed to create config directory: {}",
+                    parent.display()
+                ))
             })?;
         }
 
         let contents = toml::to_string_pretty(config).context("Failed to serialize config")?;
 
         fs::write(&self.config_path, contents).with_context(|| {
-            format!(
+            TlError::Io(format!(
                 "Failed to write config file: {}",
                 self.config_path.display()
-            )
+            ))
         })?;
 
         Ok(())
@@ -301,15 +528,15 @@ impl ConfigManager {
     /// This prevents accidental overwrite of a malformed config file when saving.
     pub fn load_or_default(&self) -> Result<ConfigFile> {
         match fs::read_to_string(&self.config_path) {
-            Ok(contents) => {
-                toml::from_str(&contents).with_context(|| "Failed to parse config file")
-            }
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| TlError::Config("Failed to parse config file".to_string())),
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ConfigFile::default()),
-            Err(e) => Err(anyhow::anyhow!(
+            Err(e) => Err(TlError::Io(format!(
                 "Failed to read config file: {}: {}",
                 self.config_path.display(),
                 e
-            )),
+            ))
+            .into()),
         }
     }
 }
@@ -318,6 +545,7 @@ impl ConfigManager {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::error::find_tl_error;
     use tempfile::TempDir;
 
     fn create_test_manager(temp_dir: &TempDir) -> ConfigManager {
@@ -339,6 +567,7 @@ mod tests {
                 api_key: None,
                 api_key_env: None,
                 models: vec!["gemma3:12b".to_string(), "llama3.2".to_string()],
+                prompt_template: None,
             },
         );
 
@@ -348,9 +577,17 @@ mod tests {
                 model: Some("gemma3:12b".to_string()),
                 to: Some("ja".to_string()),
                 style: None,
+                pair: None,
+                native_names: None,
+                prompt_template: None,
+                chat_history: None,
+                review_provider: None,
+                review_model: None,
             },
             providers,
             styles: HashMap::new(),
+            aliases: HashMap::new(),
+            language_prompts: HashMap::new(),
         };
 
         manager.save(&config).unwrap();
@@ -383,6 +620,7 @@ mod tests {
             api_key: Some("fallback-key".to_string()),
             api_key_env: Some("TEST_API_KEY".to_string()),
             models: vec![],
+            prompt_template: None,
         };
 
         // Environment variable takes priority
@@ -406,6 +644,7 @@ mod tests {
             api_key: Some("fallback-key".to_string()),
             api_key_env: Some("NONEXISTENT_KEY".to_string()),
             models: vec![],
+            prompt_template: None,
         };
 
         // Falls back to api_key when env var not set
@@ -419,6 +658,7 @@ mod tests {
             api_key: Some("key".to_string()),
             api_key_env: None,
             models: vec![],
+            prompt_template: None,
         };
         assert!(provider_with_key.requires_api_key());
 
@@ -427,6 +667,7 @@ mod tests {
             api_key: None,
             api_key_env: Some("API_KEY".to_string()),
             models: vec![],
+            prompt_template: None,
         };
         assert!(provider_with_env.requires_api_key());
 
@@ -435,6 +676,7 @@ mod tests {
             api_key: None,
             api_key_env: None,
             models: vec![],
+            prompt_template: None,
         };
         assert!(!provider_without.requires_api_key());
     }
@@ -447,6 +689,7 @@ mod tests {
             provider: Some("ollama".to_string()),
             model: Some("gemma3:12b".to_string()),
             style: None,
+            pair: None,
         }
     }
 
@@ -459,6 +702,7 @@ mod tests {
                 api_key: None,
                 api_key_env: None,
                 models: vec!["gemma3:12b".to_string()],
+                prompt_template: None,
             },
         );
         providers.insert(
@@ -468,6 +712,7 @@ mod tests {
                 api_key: None,
                 api_key_env: Some("TL_TEST_NONEXISTENT_API_KEY".to_string()),
                 models: vec!["gpt-4o".to_string()],
+                prompt_template: None,
             },
         );
 
@@ -477,9 +722,17 @@ mod tests {
                 model: Some("gemma3:12b".to_string()),
                 to: Some("ja".to_string()),
                 style: None,
+                pair: None,
+                native_names: None,
+                prompt_template: None,
+                chat_history: None,
+                review_provider: None,
+                review_model: None,
             },
             providers,
             styles: HashMap::new(),
+            aliases: HashMap::new(),
+            language_prompts: HashMap::new(),
         }
     }
 
@@ -523,6 +776,61 @@ mod tests {
         assert_eq!(resolved.target_language, "ja");
     }
 
+    #[test]
+    fn test_resolve_config_pair_from_config() {
+        let mut options = create_test_options();
+        options.to = None;
+        let mut config = create_test_config();
+        config.tl.pair = Some(vec!["en".to_string(), "fr".to_string()]);
+
+        let resolved = resolve_config(&options, &config).unwrap();
+
+        assert_eq!(resolved.target_language, "en");
+        assert_eq!(resolved.target_language_for(Some("en")), "fr");
+        assert_eq!(resolved.target_language_for(Some("ja")), "en");
+        assert_eq!(resolved.target_language_for(None), "en");
+    }
+
+    #[test]
+    fn test_resolve_config_to_disables_pair() {
+        let mut options = create_test_options();
+        options.pair = Some("en,fr".to_string());
+        let config = create_test_config();
+
+        let resolved = resolve_config(&options, &config).unwrap();
+
+        assert!(resolved.pair.is_none());
+        assert_eq!(resolved.target_language_for(Some("ja")), "ja");
+    }
+
+    #[test]
+    fn test_resolve_config_language_prompts() {
+        let options = create_test_options();
+        let mut config = create_test_config();
+        config
+            .language_prompts
+            .insert("PT".to_string(), "Use European spelling.".to_string());
+        config
+            .language_prompts
+            .insert("pt-br".to_string(), "Use você.".to_string());
+
+        let resolved = resolve_config(&options, &config).unwrap();
+
+        let prompt_for = |lang| resolved.prompt_options_for(None, lang).language_prompt;
+        assert_eq!(prompt_for("pt-BR").as_deref(), Some("Use você."));
+        assert_eq!(
+            prompt_for("pt-PT").as_deref(),
+            Some("Use European spelling.")
+        );
+        assert_eq!(prompt_for("ja"), None);
+
+        config
+            .language_prompts
+            .insert("xx".to_string(), "Invalid.".to_string());
+        let err = resolve_config(&options, &config).unwrap_err();
+        assert!(err.to_string().contains("[language_prompts]"));
+    }
+
     #[test]
     fn test_resolve_config_missing_provider() {
         let options = ResolveOptions {
@@ -530,6 +838,7 @@ mod tests {
             provider: None,
             model: Some("model".to_string()),
             style: None,
+            pair: None,
         };
         let config = ConfigFile::default();
 
@@ -590,7 +899,42 @@ mod tests {
         let result = resolve_config(&options, &config);
 
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("API key"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("API key"));
+        assert!(matches!(
+            find_tl_error(&err),
+            Some(TlError::Auth { status: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_config_missing_provider_is_config_error() {
+        let mut options = create_test_options();
+        options.provider = None;
+        let config = ConfigFile::default();
+
+        let err = resolve_config(&options, &config).unwrap_err();
+        assert!(matches!(find_tl_error(&err), Some(TlError::Config(_))));
+    }
+
+    #[test]
+    fn test_resolve_config_unknown_cli_style_is_usage_error() {
+        let mut options = create_test_options();
+        options.style = Some("nonexistent".to_string());
+        let config = create_test_config();
+
+        let err = resolve_config(&options, &config).unwrap_err();
+        assert!(matches!(find_tl_error(&err), Some(TlError::Usage(_))));
+    }
+
+    #[test]
+    fn test_resolve_config_unknown_config_style_is_config_error() {
+        let options = create_test_options();
+        let mut config = create_test_config();
+        config.tl.style = Some("nonexistent".to_string());
+
+        let err = resolve_config(&options, &config).unwrap_err();
+        assert!(matches!(find_tl_error(&err), Some(TlError::Config(_))));
     }
 
     #[test]
@@ -632,7 +976,9 @@ mod tests {
         // Should return error for malformed config
         let result = manager.load_or_default();
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("parse"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("parse"));
+        assert!(matches!(find_tl_error(&err), Some(TlError::Config(_))));
     }
 
     #[test]
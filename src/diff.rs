@@ -0,0 +1,280 @@
+//! Text diffing for comparing translations.
+//!
+//! Text is split into words, whitespace, and punctuation. Wide characters
+//! (CJK ideographs, kana, Hangul) are tokens of their own, since those
+//! scripts do not separate words with spaces.
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::ui::Style;
+
+/// Largest token grid diffed token by token; bigger inputs are diffed
+/// by line to bound memory use.
+const MAX_CELLS: usize = 4_000_000;
+
+/// One step of a diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change<T> {
+    /// Present in both sequences.
+    Equal(T),
+    /// Only in the old sequence.
+    Delete(T),
+    /// Only in the new sequence.
+    Insert(T),
+}
+
+/// Splits text into word, whitespace, and punctuation tokens.
+///
+/// Concatenating the tokens gives back the original text.
+pub fn tokenize(text: &str) -> Vec<&str> {
+    #[derive(PartialEq, Eq, Clone, Copy)]
+    enum Kind {
+        Word,
+        Space,
+        Single,
+    }
+    let kind_of = |c: char| {
+        if c.is_whitespace() {
+            Kind::Space
+        } else if c.is_alphanumeric() && c.width() != Some(2) {
+            Kind::Word
+        } else {
+            Kind::Single
+        }
+    };
+
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous: Option<Kind> = None;
+    for (index, c) in text.char_indices() {
+        let kind = kind_of(c);
+        if let Some(previous) = previous
+            && (kind != previous || kind == Kind::Single)
+        {
+            tokens.push(&text[start..index]);
+            start = index;
+        }
+        previous = Some(kind);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// Computes a shortest edit script between two sequences (LCS based).
+pub fn diff<T: PartialEq + Copy>(old: &[T], new: &[T]) -> Vec<Change<T>> {
+    // Common prefix and suffix need no table
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut changes: Vec<Change<T>> = old[..prefix].iter().map(|&t| Change::Equal(t)).collect();
+    changes.extend(diff_middle(old_mid, new_mid));
+    changes.extend(old[old.len() - suffix..].iter().map(|&t| Change::Equal(t)));
+    changes
+}
+
+fn diff_middle<T: PartialEq + Copy>(old: &[T], new: &[T]) -> Vec<Change<T>> {
+    let (n, m) = (old.len(), new.len());
+    // lengths[i][j]: LCS length of old[i..] and new[j..]
+    let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[at(i, j)] = if old[i] == new[j] {
+                lengths[at(i + 1, j + 1)] + 1
+            } else {
+                lengths[at(i + 1, j)].max(lengths[at(i, j + 1)])
+            };
+        }
+    }
+
+    let mut changes = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            changes.push(Change::Equal(old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[at(i + 1, j)] >= lengths[at(i, j + 1)] {
+            changes.push(Change::Delete(old[i]));
+            i += 1;
+        } else {
+            changes.push(Change::Insert(new[j]));
+            j += 1;
+        }
+    }
+    changes.extend(old[i..].iter().map(|&t| Change::Delete(t)));
+    changes.extend(new[j..].iter().map(|&t| Change::Insert(t)));
+    changes
+}
+
+/// Diffs two texts word by word (or line by line when they are large).
+pub fn diff_words<'a>(old: &'a str, new: &'a str) -> Vec<Change<&'a str>> {
+    let (old_tokens, new_tokens) = (tokenize(old), tokenize(new));
+    if old_tokens.len().saturating_mul(new_tokens.len()) <= MAX_CELLS {
+        return diff(&old_tokens, &new_tokens);
+    }
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    diff(&old_lines, &new_lines)
+}
+
+/// Formats a word diff with `[-removed-]` and `{+added+}` markers.
+///
+/// Each changed stretch is shown as everything removed followed by
+/// everything added; whitespace between changed words is folded into
+/// the stretch so markers wrap whole phrases.
+pub fn render_changes(changes: &[Change<&str>]) -> String {
+    let is_changed = |index: usize| !matches!(changes.get(index), None | Some(Change::Equal(_)));
+    let mut out = String::new();
+    let (mut removed, mut added) = (String::new(), String::new());
+    for (index, change) in changes.iter().enumerate() {
+        match *change {
+            Change::Equal(text)
+                if text.trim().is_empty()
+                    && index > 0
+                    && is_changed(index - 1)
+                    && is_changed(index + 1) =>
+            {
+                removed.push_str(text);
+                added.push_str(text);
+            }
+            Change::Equal(text) => {
+                flush_changes(&mut out, &mut removed, &mut added);
+                out.push_str(text);
+            }
+            Change::Delete(text) => removed.push_str(text),
+            Change::Insert(text) => added.push_str(text),
+        }
+    }
+    flush_changes(&mut out, &mut removed, &mut added);
+    out
+}
+
+fn flush_changes(out: &mut String, removed: &mut String, added: &mut String) {
+    if !removed.is_empty() {
+        out.push_str(&Style::error(format!("[-{removed}-]")));
+        removed.clear();
+    }
+    if !added.is_empty() {
+        out.push_str(&Style::success(format!("{{+{added}+}}")));
+        added.clear();
+    }
+}
+
+/// Returns how similar two texts are, from 0.0 (nothing shared) to 1.0.
+///
+/// This is the share of words (ignoring case, whitespace, and
+/// punctuation) the texts have in common, in order.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    fn words(text: &str) -> Vec<&str> {
+        tokenize(text)
+            .into_iter()
+            .filter(|token| token.chars().any(char::is_alphanumeric))
+            .collect()
+    }
+    let (a, b) = (a.to_lowercase(), b.to_lowercase());
+    let (a_words, b_words) = (words(&a), words(&b));
+    let total = a_words.len() + b_words.len();
+    if total == 0 {
+        return 1.0;
+    }
+
+    let shared = if a_words.len().saturating_mul(b_words.len()) <= MAX_CELLS {
+        diff(&a_words, &b_words)
+            .iter()
+            .filter(|change| matches!(change, Change::Equal(_)))
+            .count()
+    } else {
+        // Too large to align: fall back to counting shared words
+        let mut pool = b_words.clone();
+        a_words
+            .iter()
+            .filter(|word| {
+                pool.iter()
+                    .position(|w| w == *word)
+                    .map(|index| pool.swap_remove(index))
+                    .is_some()
+            })
+            .count()
+    };
+    (2 * shared) as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_round_trips() {
+        let text = "Hello, world!  日本語です。";
+        let tokens = tokenize(text);
+        assert_eq!(
+            tokens,
+            [
+                "Hello", ",", " ", "world", "!", "  ", "日", "本", "語", "で", "す", "。"
+            ]
+        );
+        assert_eq!(tokens.concat(), text);
+    }
+
+    #[test]
+    fn test_diff_words() {
+        let changes = diff_words("the quick fox", "the slow fox");
+        assert_eq!(
+            changes,
+            [
+                Change::Equal("the"),
+                Change::Equal(" "),
+                Change::Delete("quick"),
+                Change::Insert("slow"),
+                Change::Equal(" "),
+                Change::Equal("fox"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_generic_sequences() {
+        let changes = diff(&[1, 2, 3], &[1, 3, 4]);
+        assert_eq!(
+            changes,
+            [
+                Change::Equal(1),
+                Change::Delete(2),
+                Change::Equal(3),
+                Change::Insert(4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert!((similarity("Hello world", "hello, world!") - 1.0).abs() < f64::EPSILON);
+        assert!((similarity("", "") - 1.0).abs() < f64::EPSILON);
+        assert!(similarity("one two", "three four").abs() < f64::EPSILON);
+        let score = similarity("the cat sat on the mat", "the dog sat on the mat");
+        assert!(score > 0.8 && score < 0.9, "{score}");
+    }
+
+    #[test]
+    fn test_render_changes_groups_runs() {
+        let changes = diff_words("a big red car", "a small blue car");
+        assert_eq!(
+            render_changes(&changes),
+            format!(
+                "a {}{} car",
+                Style::error("[-big red-]"),
+                Style::success("{+small blue+}")
+            )
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Typed errors and exit code classification.
+//!
+//! Errors that reach the user carry a [`TlError`] somewhere in their chain,
+//! which determines the process exit code. Modules attach it either directly
+//! (`return Err(TlError::Config(..).into())`) or as context on a lower-level
+//! error (`.with_context(|| TlError::Io(..))`), so the message shown to the
+//! user does not depend on how the error is classified.
+//!
+//! Exit codes follow BSD-style `sysexits.h` via the `exitcode` crate, which
+//! is cross-platform compatible (Windows, macOS, Linux).
+
+use reqwest::StatusCode;
+
+/// An error with a known category.
+///
+/// The category decides the exit code; the message is what the user sees.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlError {
+    /// Authentication failed or a required API key is missing.
+    Auth {
+        message: String,
+        status: Option<u16>,
+    },
+    /// The API endpoint could not be reached or returned a server error.
+    Network {
+        message: String,
+        status: Option<u16>,
+    },
+    /// The API rejected the request because of rate limiting.
+    RateLimit {
+        message: String,
+        status: Option<u16>,
+    },
+    /// The API rejected the request for another reason (e.g., unknown model).
+    Api { message: String, status: u16 },
+    /// Configuration is missing or invalid.
+    Config(String),
+    /// Invalid command-line usage (bad arguments or option values).
+    Usage(String),
+    /// Input could not be found or is not acceptable (missing, empty, too large).
+    Input(String),
+    /// A file system or database operation failed.
+    Io(String),
+    /// A translation failed a quality check (e.g. `--verify-threshold`).
+    Quality(String),
+}
+
+impl TlError {
+    /// Builds an error from a non-success HTTP response.
+    ///
+    /// The category is derived from the status code:
+    /// 401/403 are authentication errors, 429 is rate limiting,
+    /// 5xx means the service is unavailable, and anything else is an API error.
+    pub fn from_status(status: StatusCode, body: &str) -> Self {
+        let message = format!("API request failed with status {status}: {body}");
+        let code = status.as_u16();
+
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Auth {
+                message,
+                status: Some(code),
+            },
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimit {
+                message,
+                status: Some(code),
+            },
+            s if s.is_server_error() => Self::Network {
+                message,
+                status: Some(code),
+            },
+            _ => Self::Api {
+                message,
+                status: code,
+            },
+        }
+    }
+
+    /// Returns the HTTP status code associated with this error, if any.
+    pub const fn status(&self) -> Option<u16> {
+        match self {
+            Self::Auth { status, .. }
+            | Self::Network { status, .. }
+            | Self::RateLimit { status, .. } => *status,
+            Self::Api { status, .. } => Some(*status),
+            Self::Config(_) | Self::Usage(_) | Self::Input(_) | Self::Io(_) | Self::Quality(_) => {
+                None
+            }
+        }
+    }
+
+    /// Returns the message shown to the user.
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Auth { message, .. }
+            | Self::Network { message, .. }
+            | Self::RateLimit { message, .. }
+            | Self::Api { message, .. }
+            | Self::Config(message)
+            | Self::Usage(message)
+            | Self::Input(message)
+            | Self::Io(message)
+            | Self::Quality(message) => message,
+        }
+    }
+
+    /// Returns a stable, machine-readable code for this error's category.
+    ///
+    /// Used in JSON output; these values must not change between releases.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Auth { .. } => "auth",
+            Self::Network { .. } => "network",
+            Self::RateLimit { .. } => "rate_limit",
+            Self::Api { .. } => "api",
+            Self::Config(_) => "config",
+            Self::Usage(_) => "usage",
+            Self::Input(_) => "input",
+            Self::Io(_) => "io",
+            Self::Quality(_) => "quality",
+        }
+    }
+
+    /// Returns the process exit code for this error.
+    pub const fn exit_code(&self) -> exitcode::ExitCode {
+        match self {
+            Self::Auth { .. } => exitcode::NOPERM,
+            Self::Network { .. } => exitcode::UNAVAILABLE,
+            Self::RateLimit { .. } => exitcode::TEMPFAIL,
+            Self::Api { .. } => exitcode::PROTOCOL,
+            Self::Config(_) => exitcode::CONFIG,
+            Self::Usage(_) => exitcode::USAGE,
+            Self::Input(_) => exitcode::NOINPUT,
+            Self::Io(_) => exitcode::IOERR,
+            Self::Quality(_) => exitcode::DATAERR,
+        }
+    }
+}
+
+impl std::fmt::Display for TlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for TlError {}
+
+/// Finds the outermost [`TlError`] attached to an error.
+///
+/// Looks through context layers first, then the `source()` chain.
+pub fn find_tl_error(err: &anyhow::Error) -> Option<&TlError> {
+    err.downcast_ref::<TlError>().or_else(|| {
+        err.chain()
+            .find_map(|cause| cause.downcast_ref::<TlError>())
+    })
+}
+
+/// Classifies an error and returns the appropriate exit code.
+///
+/// Uses the attached [`TlError`] when present. Errors without one fall back
+/// to their underlying type (`std::io::Error`, `reqwest::Error`), and
+/// finally to `SOFTWARE` for internal errors.
+pub fn exit_code(err: &anyhow::Error) -> exitcode::ExitCode {
+    if let Some(tl_err) = find_tl_error(err) {
+        return tl_err.exit_code();
+    }
+
+    if let Some(io_err) = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<std::io::Error>())
+    {
+        return if io_err.kind() == std::io::ErrorKind::NotFound {
+            exitcode::NOINPUT
+        } else {
+            exitcode::IOERR
+        };
+    }
+
+    if err
+        .chain()
+        .any(|cause| cause.downcast_ref::<reqwest::Error>().is_some())
+    {
+        return exitcode::UNAVAILABLE;
+    }
+
+    exitcode::SOFTWARE
+}
+
+/// Builds the JSON error object printed to stderr in JSON output mode.
+///
+/// Errors without an attached [`TlError`] use the code `"internal"`.
+pub fn to_json(err: &anyhow::Error) -> serde_json::Value {
+    let tl_err = find_tl_error(err);
+
+    serde_json::json!({
+        "error": {
+            "code": tl_err.map_or("internal", TlError::code),
+            "message": err.to_string(),
+            "status": tl_err.and_then(TlError::status),
+            "exit_code": exit_code(err),
+        }
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_from_status_unauthorized_is_auth() {
+        let err = TlError::from_status(StatusCode::UNAUTHORIZED, "bad key");
+        assert!(matches!(err, TlError::Auth { .. }));
+        assert_eq!(err.status(), Some(401));
+        assert_eq!(err.exit_code(), exitcode::NOPERM);
+        assert!(err.to_string().contains("401"));
+        assert!(err.to_string().contains("bad key"));
+    }
+
+    #[test]
+    fn test_from_status_forbidden_is_auth() {
+        let err = TlError::from_status(StatusCode::FORBIDDEN, "");
+        assert!(matches!(err, TlError::Auth { .. }));
+        assert_eq!(err.status(), Some(403));
+    }
+
+    #[test]
+    fn test_from_status_too_many_requests_is_rate_limit() {
+        let err = TlError::from_status(StatusCode::TOO_MANY_REQUESTS, "slow down");
+        assert!(matches!(err, TlError::RateLimit { .. }));
+        assert_eq!(err.status(), Some(429));
+        assert_eq!(err.exit_code(), exitcode::TEMPFAIL);
+    }
+
+    #[test]
+    fn test_from_status_server_error_is_network() {
+        let err = TlError::from_status(StatusCode::SERVICE_UNAVAILABLE, "");
+        assert!(matches!(err, TlError::Network { .. }));
+        assert_eq!(err.status(), Some(503));
+        assert_eq!(err.exit_code(), exitcode::UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_from_status_client_error_is_api() {
+        let err = TlError::from_status(StatusCode::NOT_FOUND, "model not found");
+        assert!(matches!(err, TlError::Api { status: 404, .. }));
+        assert_eq!(err.exit_code(), exitcode::PROTOCOL);
+    }
+
+    #[test]
+    fn test_exit_code_per_category() {
+        assert_eq!(
+            TlError::Config("x".to_string()).exit_code(),
+            exitcode::CONFIG
+        );
+        assert_eq!(TlError::Usage("x".to_string()).exit_code(), exitcode::USAGE);
+        assert_eq!(
+            TlError::Input("x".to_string()).exit_code(),
+            exitcode::NOINPUT
+        );
+        assert_eq!(TlError::Io("x".to_string()).exit_code(), exitcode::IOERR);
+        assert_eq!(
+            TlError::Quality("x".to_string()).exit_code(),
+            exitcode::DATAERR
+        );
+    }
+
+    #[test]
+    fn test_status_none_for_non_http_errors() {
+        assert_eq!(TlError::Config("x".to_string()).status(), None);
+        assert_eq!(
+            TlError::Auth {
+                message: "missing key".to_string(),
+                status: None
+            }
+            .status(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_exit_code_direct_tl_error() {
+        let err: anyhow::Error = TlError::Config("Missing provider".to_string()).into();
+        assert_eq!(exit_code(&err), exitcode::CONFIG);
+    }
+
+    #[test]
+    fn test_exit_code_tl_error_as_context() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let err = anyhow::Error::new(io_err).context(TlError::Input("Failed to read".to_string()));
+        assert_eq!(exit_code(&err), exitcode::NOINPUT);
+        assert_eq!(err.to_string(), "Failed to read");
+    }
+
+    #[test]
+    fn test_exit_code_outer_context_wins_over_inner() {
+        let err: anyhow::Error = TlError::Io("inner".to_string()).into();
+        let err = err.context(TlError::Config("outer".to_string()));
+        assert_eq!(exit_code(&err), exitcode::CONFIG);
+    }
+
+    #[test]
+    fn test_exit_code_tl_error_under_plain_context() {
+        let err: anyhow::Error = TlError::Usage("bad flag".to_string()).into();
+        let err = err.context("while running");
+        assert_eq!(exit_code(&err), exitcode::USAGE);
+    }
+
+    #[test]
+    fn test_exit_code_io_not_found_fallback() {
+        let err = Err::<(), _>(std::io::Error::from(std::io::ErrorKind::NotFound))
+            .context("plain context")
+            .unwrap_err();
+        assert_eq!(exit_code(&err), exitcode::NOINPUT);
+    }
+
+    #[test]
+    fn test_exit_code_io_other_fallback() {
+        let err: anyhow::Error = std::io::Error::from(std::io::ErrorKind::PermissionDenied).into();
+        assert_eq!(exit_code(&err), exitcode::IOERR);
+    }
+
+    #[test]
+    fn test_code_is_stable() {
+        assert_eq!(
+            TlError::from_status(StatusCode::UNAUTHORIZED, "").code(),
+            "auth"
+        );
+        assert_eq!(
+            TlError::from_status(StatusCode::TOO_MANY_REQUESTS, "").code(),
+            "rate_limit"
+        );
+        assert_eq!(TlError::Config("x".to_string()).code(), "config");
+        assert_eq!(TlError::Usage("x".to_string()).code(), "usage");
+        assert_eq!(TlError::Quality("x".to_string()).code(), "quality");
+    }
+
+    #[test]
+    fn test_to_json_with_tl_error() {
+        let err: anyhow::Error = TlError::from_status(StatusCode::TOO_MANY_REQUESTS, "").into();
+        let json = to_json(&err);
+        assert_eq!(json["error"]["code"], "rate_limit");
+        assert_eq!(json["error"]["status"], 429);
+        assert_eq!(json["error"]["exit_code"], exitcode::TEMPFAIL);
+        assert!(json["error"]["message"].as_str().unwrap().contains("429"));
+    }
+
+    #[test]
+    fn test_to_json_without_tl_error() {
+        let err = anyhow::anyhow!("something unexpected");
+        let json = to_json(&err);
+        assert_eq!(json["error"]["code"], "internal");
+        assert!(json["error"]["status"].is_null());
+        assert_eq!(json["error"]["exit_code"], exitcode::SOFTWARE);
+    }
+
+    #[test]
+    fn test_exit_code_unclassified_is_software() {
+        let err = anyhow::anyhow!("something unexpected");
+        assert_eq!(exit_code(&err), exitcode::SOFTWARE);
+    }
+}
@@ -28,6 +28,22 @@ pub fn atomic_write(file_path: &str, content: &str) -> Result<()> {
     Ok(())
 }
 
+/// Returns the path of a per-language copy of `file_path`.
+///
+/// The language code is inserted before the extension, so `notes.md`
+/// becomes `notes.ja.md` and `README` becomes `README.ja`.
+pub fn localized_path(file_path: &str, lang: &str) -> String {
+    let path = Path::new(file_path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = path.extension().map_or_else(
+        || format!("{stem}.{lang}"),
+        |ext| format!("{stem}.{lang}.{}", ext.to_string_lossy()),
+    );
+    path.with_file_name(file_name)
+        .to_string_lossy()
+        .into_owned()
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -84,4 +100,15 @@ mod tests {
         let read_content = fs::read_to_string(&file_path).unwrap();
         assert_eq!(read_content, content);
     }
+
+    #[test]
+    fn test_localized_path() {
+        assert_eq!(localized_path("notes.md", "ja"), "notes.ja.md");
+        assert_eq!(
+            localized_path("docs/release.md", "zh"),
+            "docs/release.zh.md"
+        );
+        assert_eq!(localized_path("README", "de"), "README.de");
+        assert_eq!(localized_path("a.b.txt", "fr"), "a.b.fr.txt");
+    }
 }
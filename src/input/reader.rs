@@ -1,7 +1,9 @@
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result};
 use std::fs;
 use std::io::{self, Read};
 
+use crate::error::TlError;
+
 /// Maximum input size (1 MB).
 const MAX_INPUT_SIZE: usize = 1024 * 1024;
 
@@ -26,19 +28,31 @@ impl InputReader {
     }
 
     fn read_file(path: &str) -> Result<String> {
-        let metadata =
-            fs::metadata(path).with_context(|| format!("Failed to access file: {path}"))?;
+        let metadata = fs::metadata(path).map_err(|e| {
+            let message = format!("Failed to access file: {path}");
+            let kind = if e.kind() == io::ErrorKind::NotFound {
+                TlError::Input(message)
+            } else {
+                TlError::Io(message)
+            };
+            anyhow::Error::new(e).context(kind)
+        })?;
 
         let size = metadata.len() as usize;
         if size > MAX_INPUT_SIZE {
-            bail!(
+            return Err(TlError::Input(format!(
                 "Input size ({:.1} MB) exceeds maximum allowed size (1 MB).\n\n\
                  Consider splitting the file into smaller parts.",
                 size as f64 / 1024.0 / 1024.0
-            );
+            ))
+            .into());
         }
 
-        fs::read_to_string(path).with_context(|| format!("Failed to read file: {path}"))
+        let bytes =
+            fs::read(path).with_context(|| TlError::Io(format!("Failed to read file: {path}")))?;
+
+        String::from_utf8(bytes)
+            .with_context(|| TlError::Input(format!("File is not valid UTF-8: {path}")))
     }
 
     #[allow(clippy::significant_drop_tightening)]
@@ -50,7 +64,7 @@ impl InputReader {
         loop {
             let bytes_read = stdin
                 .read(&mut chunk)
-                .context("Failed to read from stdin")?;
+                .with_context(|| TlError::Io("Failed to read from stdin".to_string()))?;
 
             if bytes_read == 0 {
                 break;
@@ -59,15 +73,17 @@ impl InputReader {
             buffer.extend_from_slice(&chunk[..bytes_read]);
 
             if buffer.len() > MAX_INPUT_SIZE {
-                bail!(
+                return Err(TlError::Input(format!(
                     "Input size ({:.1} MB) exceeds maximum allowed size (1 MB).\n\n\
                      Consider splitting the input into smaller parts.",
                     buffer.len() as f64 / 1024.0 / 1024.0
-                );
+                ))
+                .into());
             }
         }
 
-        String::from_utf8(buffer).context("Input is not valid UTF-8")
+        String::from_utf8(buffer)
+            .with_context(|| TlError::Input("Input is not valid UTF-8".to_string()))
     }
 }
 
@@ -75,6 +91,7 @@ impl InputReader {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use crate::error::find_tl_error;
     use std::io::Write;
     use tempfile::{NamedTempFile, TempDir};
 
@@ -93,6 +110,21 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_read_nonexistent_file_is_input_error() {
+        let err = InputReader::read(Some("/nonexistent/path/to/file.txt")).unwrap_err();
+        assert!(matches!(find_tl_error(&err), Some(TlError::Input(_))));
+    }
+
+    #[test]
+    fn test_read_invalid_utf8_is_input_error() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&[0xff, 0xfe, 0xfd]).unwrap();
+
+        let err = InputReader::read(Some(temp_file.path().to_str().unwrap())).unwrap_err();
+        assert!(matches!(find_tl_error(&err), Some(TlError::Input(_))));
+    }
+
     #[test]
     fn test_max_input_size_constant() {
         assert_eq!(MAX_INPUT_SIZE, 1024 * 1024);
@@ -127,7 +159,9 @@ mod tests {
 
         let result = InputReader::read(Some(file_path.to_str().unwrap()));
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("exceeds maximum"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("exceeds maximum"));
+        assert!(matches!(find_tl_error(&err), Some(TlError::Input(_))));
     }
 
     #[test]
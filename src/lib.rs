@@ -50,9 +50,18 @@ pub mod chat;
 /// Command-line interface definitions and handlers.
 pub mod cli;
 
+/// Comparing translations across models and providers.
+pub mod compare;
+
 /// Configuration file management and provider settings.
 pub mod config;
 
+/// Word-level text diffing and similarity.
+pub mod diff;
+
+/// Typed errors and exit code classification.
+pub mod error;
+
 /// File system utilities.
 pub mod fs;
 
@@ -65,6 +74,18 @@ pub mod output;
 /// XDG-style path utilities for configuration and cache.
 pub mod paths;
 
+/// Translation quality checks (round-trip verification, judge review).
+pub mod quality;
+
+/// Splitting documents into segments (paragraphs).
+pub mod segment;
+
+/// Source snapshots for incremental translation.
+pub mod snapshot;
+
+/// Local HTTP API server (`tl serve`).
+pub mod server;
+
 /// Translation style management (presets and custom styles).
 pub mod style;
 
@@ -10,13 +10,36 @@
 //! - Errors always go to stderr
 //! - Quiet mode suppresses non-essential output
 //! - Colors can be disabled via flag or NO_COLOR environment variable
+//! - JSON mode prints a single machine-readable object per command
+//! - NDJSON streaming prints one event object per line to stdout
 
+use serde::Serialize;
 use std::io::{self, Write};
 use std::sync::OnceLock;
 
 /// Global output configuration.
 static OUTPUT_CONFIG: OnceLock<OutputConfig> = OnceLock::new();
 
+/// Format of command results on stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text (streamed translations, colored lists).
+    #[default]
+    Text,
+    /// A single JSON object per command (for scripting).
+    Json,
+}
+
+/// Format of streamed translation output on stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum StreamFormat {
+    /// Raw translated text, printed as it arrives.
+    #[default]
+    Text,
+    /// One JSON event per line (`start`, `delta`, `done`, `error`).
+    Ndjson,
+}
+
 /// Output configuration settings.
 #[derive(Debug, Clone)]
 pub struct OutputConfig {
@@ -24,6 +47,8 @@ pub struct OutputConfig {
     pub quiet: bool,
     /// Disable colored output.
     pub no_color: bool,
+    /// Format of command results.
+    pub format: OutputFormat,
 }
 
 impl Default for OutputConfig {
@@ -32,6 +57,7 @@ impl Default for OutputConfig {
             quiet: false,
             // Check NO_COLOR environment variable (https://no-color.org/)
             no_color: std::env::var("NO_COLOR").is_ok(),
+            format: OutputFormat::Text,
         }
     }
 }
@@ -59,6 +85,20 @@ pub fn is_no_color() -> bool {
     config().no_color
 }
 
+/// Check if JSON output is enabled.
+pub fn is_json() -> bool {
+    config().format == OutputFormat::Json
+}
+
+/// Print a value as a single line of JSON to stdout.
+pub fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer(&mut stdout, value)?;
+    writeln!(stdout)?;
+    stdout.flush()?;
+    Ok(())
+}
+
 /// Print a status message to stderr (respects quiet mode).
 ///
 /// Use this for progress indicators, informational messages, etc.
@@ -116,6 +156,7 @@ mod tests {
         // Note: This test may be affected by NO_COLOR env var in test environment
         let config = OutputConfig::default();
         assert!(!config.quiet);
+        assert_eq!(config.format, OutputFormat::Text);
     }
 
     #[test]
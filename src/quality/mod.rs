@@ -0,0 +1,17 @@
+//! Translation quality checks.
+//!
+//! Checks that run after a translation finishes and can fail the command,
+//! so they are usable in CI.
+
+mod placeholders;
+mod review;
+mod verify;
+
+pub use placeholders::{
+    Problem, check_placeholders, corrective_request, describe, extract, find_problems,
+    paragraph_problems,
+};
+pub use review::{
+    Issue, MAX_SCORE, Review, ReviewRequest, Scores, check_scores, parse_review, review,
+};
+pub use verify::{Verification, back_translation_request, check_similarity, divergences};
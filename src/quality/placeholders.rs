@@ -0,0 +1,456 @@
+//! Placeholder and markup integrity checks.
+//!
+//! Format placeholders (`{name}`, `{{name}}`, `%s`, `%1$d`, `%(name)s`),
+//! markup tags (`<b>`, `</b>`, `<br/>`) and ICU `MessageFormat` arguments
+//! (`{count, plural, one {…} other {…}}`) must come through a translation
+//! unchanged. The text inside plural and select branches is translated and
+//! languages differ in their plural forms, so for those only the argument,
+//! its type, the select keys and the placeholders inside the branches are
+//! compared.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::error::TlError;
+use crate::segment;
+use crate::translation::TranslationRequest;
+
+/// printf conversion characters (`%s`, `%d`, `%@`, ...).
+const CONVERSIONS: &str = "diouxXeEfFgGaAcspn@";
+
+/// A placeholder that a translation lost, added or changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Problem {
+    /// In the source but not in the translation.
+    Missing { placeholder: String },
+    /// In the translation but not in the source.
+    Extra { placeholder: String },
+    /// Replaced by a different placeholder of the same kind.
+    Altered { source: String, translation: String },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { placeholder } => write!(f, "missing {placeholder}"),
+            Self::Extra { placeholder } => write!(f, "unexpected {placeholder}"),
+            Self::Altered {
+                source,
+                translation,
+            } => write!(f, "{source} changed to {translation}"),
+        }
+    }
+}
+
+/// Returns the placeholders and tags in `text`, in order of appearance.
+pub fn extract(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(['{', '%', '<']) {
+        let tail = &rest[start..];
+        let scanned = match tail.as_bytes()[0] {
+            b'{' => scan_brace(tail),
+            b'%' => scan_printf(tail),
+            _ => scan_tag(tail),
+        };
+        rest = match scanned {
+            Some((tokens, len)) => {
+                found.extend(tokens);
+                &tail[len..]
+            }
+            None => &tail[1..],
+        };
+    }
+    found
+}
+
+/// Compares the placeholders of a source and its translation.
+///
+/// Placeholders may be reordered. A missing placeholder and an unexpected
+/// one of the same kind are reported together as an alteration.
+pub fn find_problems(source: &str, translation: &str) -> Vec<Problem> {
+    let mut extra = extract(translation);
+    let mut missing = extract(source);
+    missing.retain(|token| {
+        extra
+            .iter()
+            .position(|t| t == token)
+            .map(|index| extra.remove(index))
+            .is_none()
+    });
+
+    let mut problems = Vec::new();
+    for placeholder in missing {
+        match extra.iter().position(|t| kind(t) == kind(&placeholder)) {
+            Some(index) => problems.push(Problem::Altered {
+                source: placeholder,
+                translation: extra.remove(index),
+            }),
+            None => problems.push(Problem::Missing { placeholder }),
+        }
+    }
+    problems.extend(
+        extra
+            .into_iter()
+            .map(|placeholder| Problem::Extra { placeholder }),
+    );
+    problems
+}
+
+/// Returns the problems of each translated paragraph that has any, by
+/// paragraph index.
+///
+/// Returns `None` if the translation does not have as many paragraphs as
+/// the source, since they cannot be matched up.
+pub fn paragraph_problems(source: &str, translation: &str) -> Option<Vec<(usize, Vec<Problem>)>> {
+    let sources = segment::paragraphs(source);
+    let translations = segment::paragraphs(translation);
+    if sources.len() != translations.len() {
+        return None;
+    }
+    let problems = sources
+        .iter()
+        .zip(&translations)
+        .map(|(source, translation)| find_problems(source, translation))
+        .enumerate()
+        .filter(|(_, problems)| !problems.is_empty())
+        .collect();
+    Some(problems)
+}
+
+/// Lists problems on one line (`missing {name}, %s changed to %d`).
+pub fn describe(problems: &[Problem]) -> String {
+    problems
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns `request` with instructions to fix the given problems, for a
+/// second attempt.
+pub fn corrective_request(
+    request: &TranslationRequest,
+    problems: &[Problem],
+) -> TranslationRequest {
+    let note = format!(
+        "A previous translation of this text broke placeholders or markup ({}). \
+         Keep every placeholder (such as {{name}}, %s or %1$d), markup tag and ICU \
+         plural/select structure exactly as in the source; translate only the text \
+         around them and inside plural or select branches.",
+        describe(problems)
+    );
+    TranslationRequest {
+        style: Some(match request.style {
+            Some(ref style) => format!("{style}\n\n{note}"),
+            None => note,
+        }),
+        ..request.clone()
+    }
+}
+
+/// Fails with a quality error if there are any problems (`--strict`).
+pub fn check_placeholders(problems: &[Problem]) -> Result<()> {
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(TlError::Quality(format!(
+        "The translation changed placeholders or markup: {}",
+        describe(problems)
+    ))
+    .into())
+}
+
+/// Groups placeholders that can replace each other by mistake.
+fn kind(token: &str) -> &str {
+    if token.starts_with("{{") {
+        "{{"
+    } else if token.starts_with("</") {
+        "</"
+    } else {
+        &token[..1]
+    }
+}
+
+fn is_argument(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Returns the byte index of the `}` closing the `{` at the start of `text`.
+fn matching_brace(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (index, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Scans `{name}`, `{{name}}` and ICU arguments.
+fn scan_brace(text: &str) -> Option<(Vec<String>, usize)> {
+    if let Some(inner) = text.strip_prefix("{{") {
+        let end = inner.find("}}")?;
+        let name = inner[..end].trim();
+        let name = name.strip_prefix(['#', '/']).unwrap_or(name);
+        return is_argument(name.split_whitespace().next()?)
+            .then(|| (vec![format!("{{{{{}}}}}", inner[..end].trim())], end + 4));
+    }
+
+    let close = matching_brace(text)?;
+    let content = &text[1..close];
+    let mut parts = content.splitn(3, ',').map(str::trim);
+    let argument = parts.next()?;
+    if !is_argument(argument) {
+        return None;
+    }
+    let tokens = match (parts.next(), parts.next()) {
+        (None, _) => vec![format!("{{{argument}}}")],
+        (Some(kind @ ("plural" | "selectordinal" | "select")), Some(branches)) => {
+            let (keys, inner) = scan_branches(branches)?;
+            let head = if kind == "select" {
+                let keys: Vec<&str> = keys.into_iter().collect();
+                format!("{{{argument}, select, {}}}", keys.join("|"))
+            } else {
+                format!("{{{argument}, {kind}}}")
+            };
+            std::iter::once(head).chain(inner).collect()
+        }
+        (Some(kind), style) if !kind.is_empty() && kind.chars().all(char::is_alphabetic) => {
+            let style = style.map(|style| format!(", {style}")).unwrap_or_default();
+            vec![format!("{{{argument}, {kind}{style}}}")]
+        }
+        _ => return None,
+    };
+    Some((tokens, close + 1))
+}
+
+/// Scans ICU branches (`one {…} other {…}`), returning the keys and the
+/// distinct placeholders inside them.
+fn scan_branches(mut text: &str) -> Option<(BTreeSet<&str>, BTreeSet<String>)> {
+    let mut keys = BTreeSet::new();
+    let mut inner = BTreeSet::new();
+    loop {
+        text = text.trim_start();
+        if text.is_empty() {
+            break;
+        }
+        if let Some(rest) = text.strip_prefix("offset:") {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            text = &rest[digits..];
+            continue;
+        }
+        let key_end = text.find(|c: char| c == '{' || c.is_whitespace())?;
+        keys.insert(&text[..key_end]);
+        text = text[key_end..].trim_start();
+        let close = matching_brace(text)?;
+        inner.extend(extract(&text[1..close]));
+        text = &text[close + 1..];
+    }
+    (!keys.is_empty()).then_some((keys, inner))
+}
+
+/// Scans `%s`, `%1$d`, `%-5.2f`, `%(name)s` and `%%`.
+fn scan_printf(text: &str) -> Option<(Vec<String>, usize)> {
+    let bytes = text.as_bytes();
+    let mut index = 1;
+    match bytes.get(1) {
+        Some(b'%') => return Some((Vec::new(), 2)),
+        Some(b'(') => {
+            let close = text.find(')')?;
+            if !is_argument(&text[2..close]) {
+                return None;
+            }
+            index = close + 1;
+        }
+        _ => {
+            let digits = count(&bytes[index..], |b| b.is_ascii_digit());
+            if digits > 0 && bytes.get(index + digits) == Some(&b'$') {
+                index += digits + 1;
+            }
+        }
+    }
+    index += count(&bytes[index..], |b| matches!(b, b'-' | b'+' | b'0' | b'#'));
+    index += count(&bytes[index..], |b| b.is_ascii_digit());
+    if bytes.get(index) == Some(&b'.') {
+        index += 1 + count(&bytes[index + 1..], |b| b.is_ascii_digit());
+    }
+    index += count(&bytes[index..], |b| {
+        matches!(b, b'h' | b'l' | b'L' | b'z' | b'j' | b't')
+    })
+    .min(2);
+    let conversion = *bytes.get(index)?;
+    CONVERSIONS
+        .contains(char::from(conversion))
+        .then(|| (vec![text[..=index].to_string()], index + 1))
+}
+
+fn count(bytes: &[u8], accept: impl Fn(u8) -> bool) -> usize {
+    bytes.iter().take_while(|&&b| accept(b)).count()
+}
+
+/// Scans `<b>`, `</b>` and `<br/>`; attributes are ignored because their
+/// values (`title`, `alt`) may be translated.
+fn scan_tag(text: &str) -> Option<(Vec<String>, usize)> {
+    let closing = text[1..].starts_with('/');
+    let name_start = if closing { 2 } else { 1 };
+    let name_len = text[name_start..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(text.len() - name_start);
+    let name = &text[name_start..name_start + name_len];
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let after = &text[name_start + name_len..];
+    if !after.starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+        return None;
+    }
+    let end = after.find(['>', '<', '\n'])?;
+    if after.as_bytes()[end] != b'>' {
+        return None;
+    }
+    let token = if closing {
+        format!("</{name}>")
+    } else if after[..end].ends_with('/') {
+        format!("<{name}/>")
+    } else {
+        format!("<{name}>")
+    };
+    Some((vec![token], name_start + name_len + end + 1))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract() {
+        assert_eq!(
+            extract("Hi {name}, {{user}} has %s and %1$d items, %-5.2f%% or %(count)d."),
+            vec!["{name}", "{{user}}", "%s", "%1$d", "%-5.2f", "%(count)d"]
+        );
+        assert_eq!(
+            extract(r#"<p class="x">Click <b>here</b><br/></p>"#),
+            vec!["<p>", "<b>", "</b>", "<br/>", "</p>"]
+        );
+        assert_eq!(
+            extract("{count, plural, one {# file in {dir}} other {# files in {dir}}}"),
+            vec!["{count, plural}", "{dir}"]
+        );
+        assert_eq!(
+            extract("{n, number, integer}"),
+            vec!["{n, number, integer}"]
+        );
+    }
+
+    #[test]
+    fn test_extract_ignores_prose_and_code() {
+        for text in [
+            "50% off, 10%-20% more",
+            "a < b and c > d",
+            "<https://example.com>",
+            r#"{"key": 1}"#,
+            "{ }",
+        ] {
+            assert!(extract(text).is_empty(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_find_problems() {
+        assert!(
+            find_problems("Hi {name}, %s <b>x</b>", "<b>x</b> %s こんにちは {name}").is_empty()
+        );
+        assert_eq!(
+            find_problems(
+                "Hi {name}, you have %d <b>new</b> items",
+                "{nom}、%d 件の新着 <b>"
+            ),
+            vec![
+                Problem::Altered {
+                    source: "{name}".to_string(),
+                    translation: "{nom}".to_string(),
+                },
+                Problem::Missing {
+                    placeholder: "</b>".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            describe(&find_problems("Hello", "Hello %s")),
+            "unexpected %s"
+        );
+    }
+
+    #[test]
+    fn test_icu_plural_forms_may_differ() {
+        let source = "{count, plural, one {# item for {user}} other {# items for {user}}}";
+        assert!(find_problems(source, "{count, plural, other {{user} の # 件}}").is_empty());
+        assert_eq!(
+            describe(&find_problems(source, "{count, plural, other {# 件}}")),
+            "missing {user}"
+        );
+        assert_eq!(
+            describe(&find_problems(
+                "{g, select, male {He} female {She} other {They}}",
+                "{g, select, male {Il} other {Elle}}"
+            )),
+            "{g, select, female|male|other} changed to {g, select, male|other}"
+        );
+    }
+
+    #[test]
+    fn test_paragraph_problems() {
+        let source = "Hi {name}\n\nPlain\n\n<b>Bold</b>";
+        let problems = paragraph_problems(source, "やあ {name}\n\n普通\n\n太字").unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].0, 2);
+        assert_eq!(describe(&problems[0].1), "missing <b>, missing </b>");
+
+        assert!(paragraph_problems(source, "やあ {name}\n\n普通").is_none());
+    }
+
+    #[test]
+    fn test_check_and_corrective_request() {
+        assert!(check_placeholders(&[]).is_ok());
+        let problems = find_problems("Hi {name}", "こんにちは");
+        let err = check_placeholders(&problems).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The translation changed placeholders or markup: missing {name}"
+        );
+
+        let request = TranslationRequest {
+            source_text: "Hi {name}".to_string(),
+            target_language: "ja".to_string(),
+            source_language: None,
+            source_language_detected: false,
+            model: "m".to_string(),
+            endpoint: "e".to_string(),
+            style: Some("Be formal.".to_string()),
+            prompt: crate::translation::PromptOptions::default(),
+        };
+        let retry = corrective_request(&request, &problems);
+        let style = retry.style.as_deref().unwrap_or_default();
+        assert!(style.starts_with("Be formal.\n\n"));
+        assert!(style.contains("(missing {name})"));
+        assert_ne!(retry.cache_key(), request.cache_key());
+    }
+}
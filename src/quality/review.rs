@@ -0,0 +1,282 @@
+//! Translation review by a judge model (`tl review`).
+//!
+//! The judge gets the source and the translation split into numbered
+//! segments and answers with JSON: scores for fluency, accuracy and
+//! terminology, and a list of issues, each tied to a segment.
+
+use anyhow::Result;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+use crate::error::TlError;
+use crate::segment;
+use crate::translation::{TranslationClient, Usage, prompt_language_name};
+
+/// Highest score the judge gives.
+pub const MAX_SCORE: u8 = 5;
+
+const SYSTEM_PROMPT: &str = r#"You are an expert reviewer of translations from {source_language} to {target_language}.
+Review the translation below against its source. Both are split into numbered segments; segment numbers may not line up if the translator merged or split paragraphs.
+
+Score the whole translation from 1 (unusable) to 5 (flawless) on:
+- fluency: natural, grammatical {target_language}
+- accuracy: meaning preserved, nothing added or omitted
+- terminology: correct and consistent domain terms, names kept as appropriate
+{instructions}
+Respond with only a JSON object, no other text:
+{"scores": {"fluency": 1-5, "accuracy": 1-5, "terminology": 1-5},
+ "summary": "one or two sentences",
+ "issues": [{"segment": <source segment number>, "category": "fluency" | "accuracy" | "terminology",
+             "severity": "minor" | "major" | "critical", "source": "<quoted source excerpt>",
+             "translation": "<quoted translation excerpt>", "comment": "<what is wrong>",
+             "suggestion": "<better translation of the excerpt>"}]}
+Use an empty "issues" list if there is nothing to report."#;
+
+/// Scores given by the judge, from 1 to [`MAX_SCORE`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Scores {
+    pub fluency: f64,
+    pub accuracy: f64,
+    pub terminology: f64,
+}
+
+impl Scores {
+    /// Returns the scores with their names, in display order.
+    pub const fn named(&self) -> [(&'static str, f64); 3] {
+        [
+            ("fluency", self.fluency),
+            ("accuracy", self.accuracy),
+            ("terminology", self.terminology),
+        ]
+    }
+}
+
+/// A problem the judge found in one segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Issue {
+    /// Number of the source segment, starting at 1 (0 if not given).
+    #[serde(default)]
+    pub segment: usize,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub severity: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<String>,
+    #[serde(default)]
+    pub comment: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+/// The judge's verdict.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Review {
+    pub scores: Scores,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub issues: Vec<Issue>,
+}
+
+impl Review {
+    /// Returns the lowest of the scores.
+    pub fn min_score(&self) -> f64 {
+        self.scores
+            .named()
+            .into_iter()
+            .map(|(_, score)| score)
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+/// What to review.
+#[derive(Debug, Clone)]
+pub struct ReviewRequest<'a> {
+    pub source: &'a str,
+    pub translation: &'a str,
+    /// Source language code (`None` if unknown).
+    pub source_language: Option<&'a str>,
+    pub target_language: &'a str,
+    /// Instructions the translation was made with (e.g. a style).
+    pub instructions: Option<&'a str>,
+}
+
+impl ReviewRequest<'_> {
+    /// Builds the judge's system prompt.
+    #[allow(clippy::literal_string_with_formatting_args)]
+    pub fn system_prompt(&self) -> String {
+        let source_language = self.source_language.map_or_else(
+            || "the source language".to_string(),
+            |lang| prompt_language_name(lang, false),
+        );
+        let instructions = self.instructions.map_or_else(String::new, |text| {
+            format!("The translator was asked to follow these instructions:\n{text}\n")
+        });
+        SYSTEM_PROMPT
+            .replace("{source_language}", &source_language)
+            .replace(
+                "{target_language}",
+                &prompt_language_name(self.target_language, false),
+            )
+            .replace("{instructions}", &instructions)
+    }
+
+    /// Builds the user message: both texts as numbered segments.
+    pub fn input(&self) -> String {
+        let mut out = String::from("SOURCE\n");
+        number_segments(&mut out, self.source);
+        out.push_str("\nTRANSLATION\n");
+        number_segments(&mut out, self.translation);
+        out
+    }
+}
+
+fn number_segments(out: &mut String, text: &str) {
+    for (index, paragraph) in segment::paragraphs(text).iter().enumerate() {
+        let _ = writeln!(out, "[{}] {paragraph}", index + 1);
+    }
+}
+
+/// Asks the judge model to review a translation.
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the judge does not answer
+/// with a valid review.
+pub async fn review(
+    client: &TranslationClient,
+    model: &str,
+    request: &ReviewRequest<'_>,
+) -> Result<(Review, Option<Usage>)> {
+    let mut stream = client
+        .complete_stream(model, &request.system_prompt(), &request.input())
+        .await?;
+    let mut response = String::new();
+    while let Some(chunk) = stream.next().await {
+        response.push_str(&chunk?);
+    }
+    Ok((parse_review(&response)?, stream.usage()))
+}
+
+/// Parses the judge's answer, tolerating Markdown code fences and text
+/// around the JSON object.
+pub fn parse_review(response: &str) -> Result<Review> {
+    let invalid = |reason: String| TlError::Api {
+        message: format!("The judge model did not return a valid review: {reason}"),
+        status: 200,
+    };
+    let json = match (response.find('{'), response.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => return Err(invalid("no JSON object in the response".to_string()).into()),
+    };
+    let review: Review = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+
+    let max = f64::from(MAX_SCORE);
+    if let Some((name, score)) = review
+        .scores
+        .named()
+        .into_iter()
+        .find(|(_, score)| !(1.0..=max).contains(score))
+    {
+        return Err(invalid(format!("{name} score {score} is not between 1 and {max}")).into());
+    }
+    Ok(review)
+}
+
+/// Fails with a quality error if any score is below `min_score`.
+pub fn check_scores(review: &Review, min_score: Option<f64>) -> Result<()> {
+    let Some(min_score) = min_score else {
+        return Ok(());
+    };
+    let low: Vec<String> = review
+        .scores
+        .named()
+        .into_iter()
+        .filter(|(_, score)| *score < min_score)
+        .map(|(name, score)| format!("{name} {score}"))
+        .collect();
+    if low.is_empty() {
+        return Ok(());
+    }
+    Err(TlError::Quality(format!(
+        "Review scores below {min_score}: {}",
+        low.join(", ")
+    ))
+    .into())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::error::find_tl_error;
+
+    const JUDGMENT: &str = r#"{"scores": {"fluency": 5, "accuracy": 3, "terminology": 4},
+        "summary": "Mostly fine.",
+        "issues": [{"segment": 2, "category": "accuracy", "severity": "major",
+                    "source": "twice", "translation": "一度", "comment": "Wrong count",
+                    "suggestion": "二度"}]}"#;
+
+    fn request() -> ReviewRequest<'static> {
+        ReviewRequest {
+            source: "Title\n\nRun it twice.\n",
+            translation: "タイトル\n\n一度実行する。\n",
+            source_language: Some("en"),
+            target_language: "ja",
+            instructions: None,
+        }
+    }
+
+    #[test]
+    fn test_prompt_and_numbered_input() {
+        let request = request();
+        let prompt = request.system_prompt();
+        assert!(prompt.contains("from English to Japanese"));
+        assert!(!prompt.contains("{instructions}"));
+        assert_eq!(
+            request.input(),
+            "SOURCE\n[1] Title\n[2] Run it twice.\n\nTRANSLATION\n[1] タイトル\n[2] 一度実行する。\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_review_accepts_code_fences() {
+        let review = parse_review(&format!("Here you go:\n```json\n{JUDGMENT}\n```")).unwrap();
+        assert!((review.scores.accuracy - 3.0).abs() < f64::EPSILON);
+        assert!((review.min_score() - 3.0).abs() < f64::EPSILON);
+        assert_eq!(review.issues[0].segment, 2);
+        assert_eq!(review.issues[0].suggestion.as_deref(), Some("二度"));
+    }
+
+    #[test]
+    fn test_parse_review_rejects_invalid_answers() {
+        for response in [
+            "I cannot review this.",
+            r#"{"scores": {"fluency": 5}}"#,
+            r#"{"scores": {"fluency": 9, "accuracy": 3, "terminology": 4}}"#,
+        ] {
+            let err = parse_review(response).unwrap_err();
+            assert!(
+                matches!(find_tl_error(&err), Some(TlError::Api { .. })),
+                "{response}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_scores() {
+        let review = parse_review(JUDGMENT).unwrap();
+        assert!(check_scores(&review, None).is_ok());
+        assert!(check_scores(&review, Some(3.0)).is_ok());
+        let err = check_scores(&review, Some(4.5)).unwrap_err();
+        assert!(matches!(find_tl_error(&err), Some(TlError::Quality(_))));
+        assert_eq!(
+            err.to_string(),
+            "Review scores below 4.5: accuracy 3, terminology 4"
+        );
+    }
+}
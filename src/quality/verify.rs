@@ -0,0 +1,136 @@
+//! Round-trip verification (`--verify`).
+//!
+//! The translation is translated back into the source language and
+//! compared with the original. A low similarity hints that meaning was
+//! lost, though paraphrases also lower the score, so thresholds are best
+//! tuned per document set.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::diff;
+use crate::error::TlError;
+use crate::translation::{PromptOptions, TranslationRequest, Usage};
+
+/// The result of translating a translation back.
+#[derive(Debug, Clone, Serialize)]
+pub struct Verification {
+    /// The translation translated back into the source language.
+    pub back_translation: String,
+    /// Similarity of the back-translation to the source (0.0 to 1.0).
+    pub similarity: f64,
+    /// Whether the back-translation came from the cache.
+    pub cached: bool,
+    /// Token usage of the back-translation, if reported.
+    pub usage: Option<Usage>,
+}
+
+impl Verification {
+    /// Compares `back_translation` with the original `source`.
+    pub fn new(source: &str, back_translation: String, cached: bool, usage: Option<Usage>) -> Self {
+        Self {
+            similarity: diff::similarity(source, &back_translation),
+            back_translation,
+            cached,
+            usage,
+        }
+    }
+}
+
+/// Builds the request that translates `translation` back into the
+/// source language of `request`.
+///
+/// The style is dropped so the back-translation stays close to the
+/// translation. Returns a usage error if the source language is unknown.
+pub fn back_translation_request(
+    request: &TranslationRequest,
+    translation: &str,
+    prompt: PromptOptions,
+) -> Result<TranslationRequest> {
+    let Some(ref source_language) = request.source_language else {
+        return Err(TlError::Usage(
+            "--verify needs the source language, which could not be detected\n\n\
+             Pass it with --from (e.g. --from en)"
+                .to_string(),
+        )
+        .into());
+    };
+    Ok(TranslationRequest {
+        source_text: translation.to_string(),
+        target_language: source_language.clone(),
+        source_language: Some(request.target_language.clone()),
+        source_language_detected: false,
+        model: request.model.clone(),
+        endpoint: request.endpoint.clone(),
+        style: None,
+        prompt,
+    })
+}
+
+/// Fails with a quality error if `similarity` is below `threshold`.
+pub fn check_similarity(similarity: f64, threshold: Option<f64>) -> Result<()> {
+    match threshold {
+        Some(threshold) if similarity < threshold => Err(TlError::Quality(format!(
+            "Back-translation similarity {:.0}% is below the threshold of {:.0}%",
+            similarity * 100.0,
+            threshold * 100.0
+        ))
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// Renders the differences between the source and its back-translation.
+pub fn divergences(source: &str, back_translation: &str) -> String {
+    diff::render_changes(&diff::diff_words(
+        source.trim_end(),
+        back_translation.trim_end(),
+    ))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::error::find_tl_error;
+
+    fn request(source_language: Option<&str>) -> TranslationRequest {
+        TranslationRequest {
+            source_text: "Hello".to_string(),
+            target_language: "ja".to_string(),
+            source_language: source_language.map(str::to_string),
+            source_language_detected: false,
+            model: "test-model".to_string(),
+            endpoint: "http://localhost".to_string(),
+            style: Some("Be casual".to_string()),
+            prompt: PromptOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_back_translation_request_swaps_languages() {
+        let back =
+            back_translation_request(&request(Some("en")), "こんにちは", PromptOptions::default())
+                .unwrap();
+        assert_eq!(back.source_text, "こんにちは");
+        assert_eq!(back.source_language.as_deref(), Some("ja"));
+        assert_eq!(back.target_language, "en");
+        assert!(back.style.is_none());
+
+        let err = back_translation_request(&request(None), "こんにちは", PromptOptions::default())
+            .unwrap_err();
+        assert!(matches!(find_tl_error(&err), Some(TlError::Usage(_))));
+    }
+
+    #[test]
+    fn test_check_similarity() {
+        assert!(check_similarity(0.5, None).is_ok());
+        assert!(check_similarity(0.8, Some(0.8)).is_ok());
+        let err = check_similarity(0.62, Some(0.8)).unwrap_err();
+        assert!(matches!(find_tl_error(&err), Some(TlError::Quality(_))));
+        assert_eq!(
+            err.to_string(),
+            "Back-translation similarity 62% is below the threshold of 80%"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Splitting documents into segments.
+//!
+//! A segment is a paragraph: a run of non-blank lines. A fenced code
+//! block is kept in one segment even if it contains blank lines. Segments
+//! are the unit that quality checks report on and that incremental
+//! translation re-translates.
+
+/// Returns the paragraphs of `text`, without surrounding blank lines.
+pub fn paragraphs(text: &str) -> Vec<&str> {
+    let mut paragraphs = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+    let mut offset = 0;
+    let mut in_fence = false;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if line.trim().is_empty() && !in_fence {
+            if let Some(begin) = start.take() {
+                paragraphs.push(&text[begin..end]);
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.trim_end().len();
+        }
+        offset += line.len();
+    }
+    if let Some(begin) = start {
+        paragraphs.push(&text[begin..end]);
+    }
+    paragraphs
+}
+
+/// Returns `text` with the paragraphs at the given indices replaced.
+///
+/// Everything between paragraphs is kept as is.
+pub fn replace_paragraphs(text: &str, replacements: &[(usize, String)]) -> String {
+    let mut replaced = String::with_capacity(text.len());
+    let mut offset = 0;
+    for (index, paragraph) in paragraphs(text).into_iter().enumerate() {
+        // Paragraphs are slices of `text`, in order
+        let start = offset + text[offset..].find(paragraph).unwrap_or(0);
+        replaced.push_str(&text[offset..start]);
+        match replacements.iter().find(|(i, _)| *i == index) {
+            Some((_, replacement)) => replaced.push_str(replacement.trim()),
+            None => replaced.push_str(paragraph),
+        }
+        offset = start + paragraph.len();
+    }
+    replaced.push_str(&text[offset..]);
+    replaced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paragraphs() {
+        let text = "\n# Title\n\nFirst line\nsecond line\n  \n\nLast\n";
+        assert_eq!(
+            paragraphs(text),
+            ["# Title", "First line\nsecond line", "Last"]
+        );
+        assert!(paragraphs(" \n\n").is_empty());
+        assert_eq!(paragraphs("one\r\n\r\ntwo"), ["one", "two"]);
+        assert_eq!(
+            paragraphs("Run:\n\n```sh\na\n\nb\n```\n\nDone"),
+            ["Run:", "```sh\na\n\nb\n```", "Done"]
+        );
+    }
+
+    #[test]
+    fn test_replace_paragraphs() {
+        let text = "\n# Title\n\nFirst\n  \n\nLast\n";
+        assert_eq!(
+            replace_paragraphs(text, &[(1, "Second\n".to_string())]),
+            "\n# Title\n\nSecond\n  \n\nLast\n"
+        );
+        assert_eq!(replace_paragraphs(text, &[]), text);
+    }
+}
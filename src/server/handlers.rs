@@ -0,0 +1,360 @@
+//! Request handlers for the HTTP API.
+
+use axum::Json;
+use axum::extract::State;
+use axum::extract::rejection::JsonRejection;
+use axum::http::StatusCode;
+use axum::response::sse::{Event, Sse};
+use axum::response::{IntoResponse, Response};
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::ServerState;
+use crate::config::{ResolveOptions, resolve_config};
+use crate::error::{self, TlError};
+use crate::style::styles_json;
+use crate::translation::{
+    TranslationClient, TranslationRequest, Usage, canonicalize_source_language, is_detected,
+    languages_json, resolve_source_language,
+};
+
+/// Body of a translation request.
+#[derive(Debug, Deserialize)]
+pub struct TranslateBody {
+    /// Text to translate.
+    text: String,
+    /// Target language code (defaults to the configured one).
+    to: Option<String>,
+    /// Source language code (detected from the text if omitted or `auto`).
+    from: Option<String>,
+    /// Provider name (defaults to the configured one).
+    provider: Option<String>,
+    /// Model name (defaults to the configured one).
+    model: Option<String>,
+    /// Style name (defaults to the configured one).
+    style: Option<String>,
+    /// Whether to bypass the cache.
+    #[serde(default)]
+    no_cache: bool,
+}
+
+/// Settings a translation was resolved with, echoed back to the caller.
+#[derive(Debug, Clone, Serialize)]
+struct TranslationInfo {
+    source_language: Option<String>,
+    target_language: String,
+    provider: String,
+    model: String,
+    style: Option<String>,
+}
+
+/// Response of `POST /v1/translate`.
+#[derive(Debug, Serialize)]
+struct TranslateResponse {
+    translation: String,
+    #[serde(flatten)]
+    info: TranslationInfo,
+    cached: bool,
+    duration_ms: u64,
+    usage: Option<Usage>,
+}
+
+/// Payload of the final `done` event of a streamed translation.
+#[derive(Debug, Serialize)]
+struct DoneEvent {
+    cached: bool,
+    duration_ms: u64,
+    usage: Option<Usage>,
+}
+
+/// An error response with the same JSON body as `--output json` errors.
+pub struct ApiError {
+    status: StatusCode,
+    body: serde_json::Value,
+}
+
+impl ApiError {
+    fn busy() -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            body: serde_json::json!({
+                "error": {
+                    "code": "busy",
+                    "message": "Too many translations in progress, retry later",
+                    "status": null,
+                }
+            }),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        let status = match error::find_tl_error(&err) {
+            Some(TlError::Usage(_) | TlError::Input(_) | TlError::Config(_)) => {
+                StatusCode::BAD_REQUEST
+            }
+            Some(TlError::RateLimit { .. }) => StatusCode::TOO_MANY_REQUESTS,
+            // Upstream rejected or failed the request
+            Some(
+                TlError::Auth {
+                    status: Some(_), ..
+                }
+                | TlError::Network { .. }
+                | TlError::Api { .. },
+            ) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self {
+            status,
+            body: error::to_json(&err),
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        let err = TlError::Usage(rejection.body_text()).into();
+        Self {
+            status: rejection.status(),
+            body: error::to_json(&err),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+/// Resolves a request body against the server configuration.
+fn prepare(
+    state: &ServerState,
+    body: TranslateBody,
+) -> anyhow::Result<(TranslationClient, TranslationRequest, TranslationInfo)> {
+    let from = canonicalize_source_language(body.from.as_deref(), &state.config.aliases)?;
+    if body.text.is_empty() {
+        return Err(TlError::Input("Input is empty".to_string()).into());
+    }
+
+    let options = ResolveOptions {
+        to: body.to,
+        provider: body.provider,
+        model: body.model,
+        style: body.style,
+        pair: None,
+    };
+    let resolved = resolve_config(&options, &state.config)?;
+
+    let source_language = resolve_source_language(from.as_deref(), &body.text);
+    let target_language = resolved.target_language_for(source_language.as_deref());
+    let prompt = resolved.prompt_options_for(source_language.as_deref(), &target_language);
+    let info = TranslationInfo {
+        source_language: source_language.clone(),
+        target_language: target_language.clone(),
+        provider: resolved.provider_name,
+        model: resolved.model.clone(),
+        style: resolved.style_name,
+    };
+    let request = TranslationRequest {
+        source_text: body.text,
+        target_language,
+        source_language,
+        source_language_detected: is_detected(from.as_deref()),
+        model: resolved.model,
+        endpoint: resolved.endpoint.clone(),
+        style: resolved.style_prompt,
+        prompt,
+    };
+    let client = TranslationClient::new(resolved.endpoint, resolved.api_key);
+
+    Ok((client, request, info))
+}
+
+/// Returns the cached translation for `request`, unless caching is off.
+///
+/// The cache is a blocking `SQLite` connection, so it is queried on the
+/// blocking thread pool.
+async fn cached(
+    state: &Arc<ServerState>,
+    request: &TranslationRequest,
+    no_cache: bool,
+) -> anyhow::Result<Option<String>> {
+    if no_cache || state.cache.is_none() {
+        return Ok(None);
+    }
+    let state = Arc::clone(state);
+    let request = request.clone();
+    tokio::task::spawn_blocking(move || {
+        state
+            .cache
+            .as_ref()
+            .map_or_else(|| Ok(None), |cache| cache.get(&request))
+    })
+    .await?
+}
+
+/// Stores a completed translation, unless caching is off.
+async fn store(
+    state: &Arc<ServerState>,
+    request: &TranslationRequest,
+    translation: String,
+    no_cache: bool,
+) -> anyhow::Result<()> {
+    if no_cache || state.cache.is_none() || translation.is_empty() {
+        return Ok(());
+    }
+    let state = Arc::clone(state);
+    let request = request.clone();
+    tokio::task::spawn_blocking(move || {
+        state
+            .cache
+            .as_ref()
+            .map_or_else(|| Ok(()), |cache| cache.put(&request, &translation))
+    })
+    .await?
+}
+
+/// `POST /v1/translate`
+pub async fn translate(
+    State(state): State<Arc<ServerState>>,
+    body: Result<Json<TranslateBody>, JsonRejection>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let started = Instant::now();
+    let Json(body) = body?;
+    let no_cache = body.no_cache;
+    let _permit = state.try_acquire().ok_or_else(ApiError::busy)?;
+    let (client, request, info) = prepare(&state, body)?;
+
+    let (translation, cached, usage) = if request.is_same_language() {
+        (request.source_text.clone(), false, None)
+    } else if let Some(hit) = cached(&state, &request, no_cache).await? {
+        (hit, true, None)
+    } else {
+        let mut stream = client.translate_stream(&request).await?;
+        let mut translation = String::new();
+        while let Some(chunk) = stream.next().await {
+            translation.push_str(&chunk?);
+        }
+        store(&state, &request, translation.clone(), no_cache).await?;
+        (translation, false, stream.usage())
+    };
+
+    let response = TranslateResponse {
+        translation,
+        info,
+        cached,
+        duration_ms: started.elapsed().as_millis() as u64,
+        usage,
+    };
+    Ok(Json(
+        serde_json::to_value(response).map_err(anyhow::Error::from)?,
+    ))
+}
+
+/// `POST /v1/translate/stream`
+///
+/// Streams `start`, `delta` (one per chunk), and then `done` or `error`
+/// events. Each event's data is a JSON object.
+pub async fn translate_stream(
+    State(state): State<Arc<ServerState>>,
+    body: Result<Json<TranslateBody>, JsonRejection>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let started = Instant::now();
+    let Json(body) = body?;
+    let no_cache = body.no_cache;
+    let permit = state.try_acquire().ok_or_else(ApiError::busy)?;
+    let (client, request, info) = prepare(&state, body)?;
+
+    let events = async_stream::stream! {
+        // Hold the slot until the stream finishes or the client disconnects
+        let _permit = permit;
+        yield Ok(json_event("start", &info));
+
+        if request.is_same_language() {
+            yield Ok(delta_event(&request.source_text));
+            yield Ok(done_event(false, started, None));
+            return;
+        }
+
+        let hit = match cached(&state, &request, no_cache).await {
+            Ok(hit) => hit,
+            Err(err) => {
+                yield Ok(error_event(&err));
+                return;
+            }
+        };
+        if let Some(hit) = hit {
+            yield Ok(delta_event(&hit));
+            yield Ok(done_event(true, started, None));
+            return;
+        }
+
+        let mut stream = match client.translate_stream(&request).await {
+            Ok(stream) => stream,
+            Err(err) => {
+                yield Ok(error_event(&err));
+                return;
+            }
+        };
+        let mut translation = String::new();
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(text) => {
+                    translation.push_str(&text);
+                    yield Ok(delta_event(&text));
+                }
+                Err(err) => {
+                    yield Ok(error_event(&err));
+                    return;
+                }
+            }
+        }
+
+        if let Err(err) = store(&state, &request, translation, no_cache).await {
+            yield Ok(error_event(&err));
+            return;
+        }
+        yield Ok(done_event(false, started, stream.usage()));
+    };
+
+    Ok(Sse::new(events))
+}
+
+/// `GET /v1/languages`
+pub async fn languages() -> Json<serde_json::Value> {
+    Json(languages_json())
+}
+
+/// `GET /v1/styles`
+pub async fn styles(State(state): State<Arc<ServerState>>) -> Json<serde_json::Value> {
+    Json(styles_json(&state.config.styles))
+}
+
+fn delta_event(text: &str) -> Event {
+    json_event("delta", &serde_json::json!({ "text": text }))
+}
+
+fn done_event(cached: bool, started: Instant, usage: Option<Usage>) -> Event {
+    let done = DoneEvent {
+        cached,
+        duration_ms: started.elapsed().as_millis() as u64,
+        usage,
+    };
+    json_event("done", &done)
+}
+
+fn error_event(err: &anyhow::Error) -> Event {
+    json_event("error", &error::to_json(err))
+}
+
+fn json_event<T: Serialize>(name: &str, data: &T) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(data)
+        .unwrap_or_else(|_| Event::default().event(name))
+}
@@ -0,0 +1,77 @@
+//! Local HTTP API exposing translation to other tools.
+//!
+//! `tl serve` runs this server so that internal tools can share one
+//! configuration (providers, styles) and one translation cache.
+//! `tl proxy` runs the OpenAI-compatible proxy in [`proxy`] instead.
+//!
+//! ## Endpoints
+//!
+//! - `POST /v1/translate` – translate and return a single JSON object
+//! - `POST /v1/translate/stream` – translate and stream events over SSE
+//! - `GET /v1/languages` – list supported language codes
+//! - `GET /v1/styles` – list preset and custom styles
+//!
+//! Translation requests are limited to a fixed number in flight; requests
+//! beyond the limit are rejected with `503 Service Unavailable`.
+
+mod handlers;
+
+/// OpenAI-compatible caching proxy (`tl proxy`).
+pub mod proxy;
+
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::routing::{get, post};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::cache::CacheManager;
+use crate::config::ConfigFile;
+use crate::error::TlError;
+
+/// Shared state for all requests handled by the server.
+pub struct ServerState {
+    config: ConfigFile,
+    cache: Option<CacheManager>,
+    limiter: Arc<Semaphore>,
+}
+
+impl ServerState {
+    /// Creates server state.
+    ///
+    /// Pass `None` as `cache` to disable caching. `max_concurrency` is the
+    /// number of translations allowed in flight at once (at least one).
+    pub fn new(config: ConfigFile, cache: Option<CacheManager>, max_concurrency: usize) -> Self {
+        Self {
+            config,
+            cache,
+            limiter: Arc::new(Semaphore::new(max_concurrency.max(1))),
+        }
+    }
+
+    /// Reserves a translation slot, or returns `None` if all are in use.
+    fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        Arc::clone(&self.limiter).try_acquire_owned().ok()
+    }
+}
+
+/// Builds the API router.
+pub fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/v1/translate", post(handlers::translate))
+        .route("/v1/translate/stream", post(handlers::translate_stream))
+        .route("/v1/languages", get(handlers::languages))
+        .route("/v1/styles", get(handlers::styles))
+        .with_state(state)
+}
+
+/// Serves `router` on `listener` until Ctrl+C is received.
+pub async fn serve(listener: TcpListener, router: Router) -> Result<()> {
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .with_context(|| TlError::Io("HTTP server failed".to_string()))
+}
@@ -0,0 +1,435 @@
+//! OpenAI-compatible caching proxy (`tl proxy`).
+//!
+//! Accepts `POST /v1/chat/completions`, forwards the request body unchanged
+//! to a configured provider (adding its API key), and caches the assistant
+//! content of complete responses. Cache hits are replayed as a regular
+//! chat completion, or as an SSE stream when the client asked for one.
+
+use anyhow::Context;
+use axum::Json;
+use axum::Router;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderValue, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use futures_util::StreamExt;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::handlers::ApiError;
+use crate::cache::CacheManager;
+use crate::config::ConfigFile;
+use crate::error::TlError;
+use crate::translation::{StreamEvent, parse_sse_events};
+
+/// Response header reporting whether the cache was used (`hit` or `miss`).
+const CACHE_HEADER: &str = "x-tl-cache";
+
+/// Shared state for the proxy.
+pub struct ProxyState {
+    config: ConfigFile,
+    provider: Option<String>,
+    cache: Option<CacheManager>,
+    client: reqwest::Client,
+}
+
+impl ProxyState {
+    /// Creates proxy state.
+    ///
+    /// `provider` pins every request to one provider; otherwise the
+    /// provider is chosen by the request's model. Pass `None` as `cache`
+    /// to disable caching.
+    pub fn new(config: ConfigFile, provider: Option<String>, cache: Option<CacheManager>) -> Self {
+        Self {
+            config,
+            provider,
+            cache,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+/// Builds the proxy router.
+pub fn router(state: Arc<ProxyState>) -> Router {
+    Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .with_state(state)
+}
+
+/// The provider a request is forwarded to.
+struct Upstream {
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+/// Selects the provider for a request.
+///
+/// Uses the pinned provider if any, then a provider whose `models` list
+/// contains the requested model, then the default provider.
+fn select_upstream(state: &ProxyState, model: Option<&str>) -> anyhow::Result<Upstream> {
+    let providers = &state.config.providers;
+    let by_model = model.and_then(|model| {
+        let mut names: Vec<_> = providers
+            .iter()
+            .filter(|(_, provider)| provider.models.iter().any(|m| m == model))
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+        names.first().copied()
+    });
+
+    let name = state
+        .provider
+        .as_ref()
+        .or(by_model)
+        .or(state.config.tl.provider.as_ref())
+        .ok_or_else(|| {
+            TlError::Config(
+                "Missing required configuration: 'provider'\n\n\
+                 Please provide it via:\n  \
+                 - CLI option: tl proxy --provider <name>\n  \
+                 - Config file: ~/.config/tl/config.toml"
+                    .to_string(),
+            )
+        })?;
+
+    let provider = providers
+        .get(name)
+        .ok_or_else(|| TlError::Config(format!("Provider '{name}' not found")))?;
+
+    let api_key = provider.get_api_key();
+    if provider.requires_api_key() && api_key.is_none() {
+        return Err(TlError::Auth {
+            message: format!("Provider '{name}' requires an API key"),
+            status: None,
+        }
+        .into());
+    }
+
+    Ok(Upstream {
+        endpoint: provider.endpoint.clone(),
+        api_key,
+    })
+}
+
+/// Computes the cache key for a chat completion request.
+///
+/// Like `TranslationRequest::cache_key`, this is a SHA-256 hash of the
+/// request parameters and endpoint. Streaming options are ignored so that
+/// streamed and non-streamed requests share entries.
+pub fn cache_key(body: &Value, endpoint: &str) -> String {
+    let mut request = body.clone();
+    if let Some(object) = request.as_object_mut() {
+        object.remove("stream");
+        object.remove("stream_options");
+    }
+
+    let cache_input = json!({
+        "request": request,
+        "endpoint": endpoint,
+    });
+
+    let mut hasher = Sha256::new();
+    hasher.update(cache_input.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Returns `true` if the response to `body` can be cached as plain text.
+///
+/// Tool calls and multiple choices cannot be represented by the cached
+/// assistant content, so those requests always go upstream.
+fn is_cacheable(body: &Value) -> bool {
+    body.get("tools").is_none()
+        && body.get("functions").is_none()
+        && body.get("n").and_then(Value::as_u64).unwrap_or(1) == 1
+}
+
+/// `POST /v1/chat/completions`
+async fn chat_completions(
+    State(state): State<Arc<ProxyState>>,
+    Json(body): Json<Value>,
+) -> Result<Response, ApiError> {
+    let model = body
+        .get("model")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let stream = body.get("stream").and_then(Value::as_bool).unwrap_or(false);
+    let include_usage = body["stream_options"]["include_usage"]
+        .as_bool()
+        .unwrap_or(false);
+    let upstream = select_upstream(&state, Some(&model))?;
+
+    let key = (state.cache.is_some() && is_cacheable(&body))
+        .then(|| cache_key(&body, &upstream.endpoint));
+
+    if let (Some(cache), Some(key)) = (&state.cache, &key)
+        && let Some(content) = cache.get_completion(key)?
+    {
+        let response = if stream {
+            sse_response(replay_sse(&model, &content, include_usage))
+        } else {
+            Json(replay_json(&model, &content)).into_response()
+        };
+        return Ok(with_cache_header(response, "hit"));
+    }
+
+    let url = format!(
+        "{}/v1/chat/completions",
+        upstream.endpoint.trim_end_matches('/')
+    );
+    let mut request = state.client.post(&url).json(&body);
+    if let Some(api_key) = &upstream.api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request.send().await.with_context(|| TlError::Network {
+        message: format!("Failed to connect to API endpoint: {url}"),
+        status: None,
+    })?;
+
+    let status = response.status();
+    let content_type = response.headers().get(header::CONTENT_TYPE).cloned();
+
+    // Errors and uncacheable responses pass through untouched
+    let store = key.filter(|_| status.is_success()).map(|key| CacheEntry {
+        state: Arc::clone(&state),
+        key,
+        model,
+        endpoint: upstream.endpoint,
+    });
+    let body = match store {
+        Some(entry) if stream => caching_stream(response, entry),
+        Some(entry) => {
+            let bytes = response.bytes().await.with_context(|| TlError::Network {
+                message: format!("Failed to read response from {url}"),
+                status: None,
+            })?;
+            if let Ok(json) = serde_json::from_slice::<Value>(&bytes)
+                && let Some(content) = json["choices"][0]["message"]["content"].as_str()
+            {
+                entry.store(content)?;
+            }
+            Body::from(bytes)
+        }
+        None => Body::from_stream(response.bytes_stream()),
+    };
+
+    let mut response = Response::new(body);
+    *response.status_mut() = status;
+    if let Some(content_type) = content_type {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, content_type);
+    }
+    Ok(with_cache_header(response, "miss"))
+}
+
+/// Where a completed response is stored.
+struct CacheEntry {
+    state: Arc<ProxyState>,
+    key: String,
+    model: String,
+    endpoint: String,
+}
+
+impl CacheEntry {
+    fn store(&self, content: &str) -> anyhow::Result<()> {
+        match self.state.cache {
+            Some(ref cache) if !content.is_empty() => {
+                cache.put_completion(&self.key, &self.model, &self.endpoint, content)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Forwards an upstream SSE stream unchanged, caching its content once
+/// the stream completes with `[DONE]`.
+fn caching_stream(response: reqwest::Response, entry: CacheEntry) -> Body {
+    let mut upstream = response.bytes_stream();
+    Body::from_stream(async_stream::stream! {
+        let mut buffer = String::new();
+        let mut content = String::new();
+        let mut done = false;
+
+        while let Some(chunk) = upstream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            };
+
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+            while let Some(line_end) = buffer.find('\n') {
+                let line: String = buffer.drain(..=line_end).collect();
+                let line = line.trim();
+                if line == "data: [DONE]" {
+                    done = true;
+                }
+                for event in parse_sse_events(line) {
+                    if let StreamEvent::Text(text) = event {
+                        content.push_str(&text);
+                    }
+                }
+            }
+
+            yield Ok(chunk);
+        }
+
+        // An interrupted stream must not be cached as if it were complete
+        if done {
+            let _ = entry.store(&content);
+        }
+    })
+}
+
+/// Token usage reported for a cached response; no tokens were spent.
+fn cached_usage() -> Value {
+    json!({ "prompt_tokens": 0, "completion_tokens": 0, "total_tokens": 0 })
+}
+
+/// Builds a non-streaming chat completion from cached content.
+fn replay_json(model: &str, content: &str) -> Value {
+    json!({
+        "id": "chatcmpl-tl-cache",
+        "object": "chat.completion",
+        "created": unix_time(),
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": content },
+            "finish_reason": "stop",
+        }],
+        "usage": cached_usage(),
+    })
+}
+
+/// Builds an SSE chat completion stream from cached content.
+///
+/// With `include_usage`, the stream ends with a usage chunk as the client
+/// asked for in `stream_options`.
+fn replay_sse(model: &str, content: &str, include_usage: bool) -> String {
+    let created = unix_time();
+    let chunk = |delta: Value, finish_reason: Value| {
+        json!({
+            "id": "chatcmpl-tl-cache",
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": model,
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+        })
+    };
+
+    let mut body = String::new();
+    let mut events = vec![
+        chunk(
+            json!({ "role": "assistant", "content": content }),
+            Value::Null,
+        ),
+        chunk(json!({}), json!("stop")),
+    ];
+    if include_usage {
+        events.push(json!({
+            "id": "chatcmpl-tl-cache",
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": model,
+            "choices": [],
+            "usage": cached_usage(),
+        }));
+    }
+    for event in events {
+        let _ = write!(body, "data: {event}\n\n");
+    }
+    body.push_str("data: [DONE]\n\n");
+    body
+}
+
+fn sse_response(body: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/event-stream"),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+fn with_cache_header(mut response: Response, value: &'static str) -> Response {
+    response
+        .headers_mut()
+        .insert(CACHE_HEADER, HeaderValue::from_static(value));
+    response
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key_ignores_stream_options() {
+        let streamed = json!({
+            "model": "m",
+            "messages": [{ "role": "user", "content": "Hi" }],
+            "stream": true,
+            "stream_options": { "include_usage": true },
+        });
+        let plain = json!({
+            "model": "m",
+            "messages": [{ "role": "user", "content": "Hi" }],
+        });
+        assert_eq!(
+            cache_key(&streamed, "http://a"),
+            cache_key(&plain, "http://a")
+        );
+        assert_ne!(cache_key(&plain, "http://a"), cache_key(&plain, "http://b"));
+    }
+
+    #[test]
+    fn test_is_cacheable() {
+        assert!(is_cacheable(&json!({ "model": "m" })));
+        assert!(is_cacheable(&json!({ "model": "m", "n": 1 })));
+        assert!(!is_cacheable(&json!({ "model": "m", "n": 2 })));
+        assert!(!is_cacheable(&json!({ "model": "m", "tools": [] })));
+    }
+
+    #[test]
+    fn test_replay_sse_is_parseable() {
+        let body = replay_sse("m", "こんにちは", false);
+        let text: String = body
+            .lines()
+            .flat_map(parse_sse_events)
+            .filter_map(|event| match event {
+                StreamEvent::Text(text) => Some(text),
+                StreamEvent::Usage(_) => None,
+            })
+            .collect();
+        assert_eq!(text, "こんにちは");
+        assert!(body.ends_with("data: [DONE]\n\n"));
+    }
+
+    #[test]
+    fn test_replay_sse_includes_usage_when_asked() {
+        let has_usage = |body: String| {
+            body.lines()
+                .flat_map(parse_sse_events)
+                .any(|event| matches!(event, StreamEvent::Usage(_)))
+        };
+        assert!(has_usage(replay_sse("m", "こんにちは", true)));
+        assert!(!has_usage(replay_sse("m", "こんにちは", false)));
+    }
+}
@@ -0,0 +1,429 @@
+//! Source snapshots for incremental translation (`--incremental`).
+//!
+//! Next to each translated file, a lock file records every source
+//! paragraph together with the translation the model produced for it.
+//! On the next run the source is compared with the snapshot paragraph by
+//! paragraph: unchanged paragraphs keep the text they have in the
+//! translated file, which may have been edited by hand, and only new or
+//! changed ones are translated again. The snapshot also records a hash
+//! of the translation settings (model, style, and prompt); when they
+//! change, the paragraphs nobody edited are translated again too.
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+
+use crate::diff::{self, Change};
+use crate::error::TlError;
+use crate::fs::atomic_write;
+use crate::segment;
+
+/// Version of the lock file format.
+pub const VERSION: u32 = 1;
+
+/// A source paragraph and the machine translation made from it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotSegment {
+    pub source: String,
+    pub translation: String,
+}
+
+/// The source a translated file was generated from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub target_language: String,
+    /// Hash of the settings the translations were made with (see
+    /// `TranslationRequest::settings_hash`). Lock files written before it
+    /// was recorded have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<String>,
+    pub segments: Vec<SnapshotSegment>,
+}
+
+/// Returns the path of the lock file kept next to `output_path`.
+pub fn lock_path(output_path: &str) -> String {
+    format!("{output_path}.tl-lock")
+}
+
+impl Snapshot {
+    /// Reads a lock file, returning `None` if it does not exist.
+    pub fn load(path: &str) -> Result<Option<Self>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| TlError::Io(format!("Failed to read lock file: {path}")));
+            }
+        };
+        let invalid = || {
+            TlError::Input(format!(
+                "Invalid lock file: {path} (delete it to translate the whole file again)"
+            ))
+        };
+        let snapshot: Self = serde_json::from_str(&content).with_context(invalid)?;
+        if snapshot.version != VERSION {
+            return Err(anyhow!("unsupported version {}", snapshot.version)).with_context(invalid);
+        }
+        Ok(Some(snapshot))
+    }
+
+    /// Writes the lock file atomically.
+    pub fn save(&self, path: &str) -> Result<()> {
+        let mut content = serde_json::to_string_pretty(self)?;
+        content.push('\n');
+        atomic_write(path, &content)
+    }
+}
+
+/// A translation made for a source paragraph on an earlier run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Previous {
+    /// What the model produced.
+    pub machine: String,
+    /// What the translated file contains now.
+    pub current: String,
+}
+
+impl Previous {
+    /// Whether the translation was changed by hand since the model
+    /// produced it. Differences in blank lines do not count.
+    pub fn is_edited(&self) -> bool {
+        segment::paragraphs(&self.machine) != segment::paragraphs(&self.current)
+    }
+}
+
+/// A source paragraph and its earlier translation, if it is unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedSegment<'a> {
+    pub source: &'a str,
+    pub previous: Option<Previous>,
+}
+
+/// The paragraphs of a source matched with a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan<'a> {
+    pub segments: Vec<PlannedSegment<'a>>,
+    /// Translations edited by hand whose source paragraph has changed or
+    /// was removed, paired with that old source.
+    pub superseded: Vec<SnapshotSegment>,
+}
+
+impl Plan<'_> {
+    /// Drops the earlier translations that were not edited by hand, so
+    /// that their paragraphs are translated again.
+    pub fn discard_machine_translations(&mut self) {
+        for segment in &mut self.segments {
+            if segment.previous.as_ref().is_some_and(|p| !p.is_edited()) {
+                segment.previous = None;
+            }
+        }
+    }
+}
+
+/// Matches the paragraphs of `source` with a snapshot.
+///
+/// `output` is the current content of the translated file. Its text is
+/// used for the unchanged paragraphs so that edits made by hand survive;
+/// without it, the snapshot's translations are.
+pub fn plan<'a>(source: &'a str, snapshot: Option<&Snapshot>, output: Option<&str>) -> Plan<'a> {
+    let paragraphs = segment::paragraphs(source);
+    let Some(snapshot) = snapshot else {
+        let segments = paragraphs
+            .into_iter()
+            .map(|source| PlannedSegment {
+                source,
+                previous: None,
+            })
+            .collect();
+        return Plan {
+            segments,
+            superseded: Vec::new(),
+        };
+    };
+
+    let current = output.map(|output| align(snapshot, output));
+    let previous = |index: usize| {
+        let machine = snapshot.segments[index].translation.clone();
+        let current = current
+            .as_ref()
+            .map_or_else(|| machine.clone(), |current| current[index].clone());
+        Previous { machine, current }
+    };
+    let old: Vec<&str> = snapshot
+        .segments
+        .iter()
+        .map(|s| s.source.as_str())
+        .collect();
+
+    let mut segments = Vec::with_capacity(paragraphs.len());
+    let mut superseded = Vec::new();
+    let (mut old_index, mut new_index) = (0, 0);
+    for change in diff::diff(&old, &paragraphs) {
+        let kept = match change {
+            Change::Delete(_) => {
+                let previous = previous(old_index);
+                if previous.is_edited() && !previous.current.is_empty() {
+                    superseded.push(SnapshotSegment {
+                        source: snapshot.segments[old_index].source.clone(),
+                        translation: previous.current,
+                    });
+                }
+                old_index += 1;
+                continue;
+            }
+            Change::Insert(_) => None,
+            Change::Equal(_) => {
+                old_index += 1;
+                Some(previous(old_index - 1))
+            }
+        };
+        segments.push(PlannedSegment {
+            source: paragraphs[new_index],
+            previous: kept,
+        });
+        new_index += 1;
+    }
+    Plan {
+        segments,
+        superseded,
+    }
+}
+
+/// Splits the translated file into the parts that belong to each
+/// snapshot segment.
+///
+/// The paragraphs of the file are matched with the machine translations
+/// using [`diff::diff`], so paragraphs added, split, merged, or removed by
+/// hand stay with the segment they were edited in. Within a run of
+/// changes, the new paragraphs are spread over the segments of the machine
+/// paragraphs they replace, or join the preceding segment when they
+/// replace none.
+fn align(snapshot: &Snapshot, output: &str) -> Vec<String> {
+    let machine: Vec<(usize, &str)> = snapshot
+        .segments
+        .iter()
+        .enumerate()
+        .flat_map(|(index, s)| {
+            segment::paragraphs(&s.translation)
+                .into_iter()
+                .map(move |paragraph| (index, paragraph))
+        })
+        .collect();
+    let machine_text: Vec<&str> = machine.iter().map(|(_, paragraph)| *paragraph).collect();
+    let paragraphs = segment::paragraphs(output);
+
+    let mut parts: Vec<Vec<&str>> = vec![Vec::new(); snapshot.segments.len()];
+    let mut replaced = Vec::new();
+    let mut added = Vec::new();
+    let mut preceding = None;
+    let (mut old_index, mut new_index) = (0, 0);
+    for change in diff::diff(&machine_text, &paragraphs) {
+        match change {
+            Change::Equal(_) => {
+                let following = machine[old_index].0;
+                assign(
+                    &mut parts,
+                    &mut replaced,
+                    &mut added,
+                    preceding.unwrap_or(following),
+                );
+                parts[following].push(paragraphs[new_index]);
+                preceding = Some(following);
+                old_index += 1;
+                new_index += 1;
+            }
+            Change::Delete(_) => {
+                replaced.push(machine[old_index].0);
+                old_index += 1;
+            }
+            Change::Insert(_) => {
+                added.push(paragraphs[new_index]);
+                new_index += 1;
+            }
+        }
+    }
+    assign(
+        &mut parts,
+        &mut replaced,
+        &mut added,
+        preceding.unwrap_or(0),
+    );
+
+    parts.into_iter().map(|part| part.join("\n\n")).collect()
+}
+
+/// Assigns the paragraphs `added` in a run of changes to segments, and
+/// clears the run.
+///
+/// `replaced` holds the segment of each machine paragraph the run removed;
+/// without any, the paragraphs go to segment `fallback`.
+fn assign<'a>(
+    parts: &mut [Vec<&'a str>],
+    replaced: &mut Vec<usize>,
+    added: &mut Vec<&'a str>,
+    fallback: usize,
+) {
+    let count = added.len();
+    for (index, paragraph) in added.drain(..).enumerate() {
+        let segment = if replaced.is_empty() {
+            fallback
+        } else {
+            replaced[index * replaced.len() / count]
+        };
+        if let Some(part) = parts.get_mut(segment) {
+            part.push(paragraph);
+        }
+    }
+    replaced.clear();
+}
+
+/// Joins translated paragraphs into a document.
+pub fn assemble<S: AsRef<str>>(parts: &[S]) -> String {
+    let mut text = parts
+        .iter()
+        .map(|part| part.as_ref().trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn snapshot(pairs: &[(&str, &str)]) -> Snapshot {
+        Snapshot {
+            version: VERSION,
+            target_language: "ja".to_string(),
+            settings: None,
+            segments: pairs
+                .iter()
+                .map(|(source, translation)| SnapshotSegment {
+                    source: (*source).to_string(),
+                    translation: (*translation).to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn kept(planned: &[PlannedSegment<'_>]) -> Vec<Option<String>> {
+        planned
+            .iter()
+            .map(|s| s.previous.as_ref().map(|p| p.current.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_without_snapshot_translates_everything() {
+        let plan = plan("A\n\nB\n", None, None);
+        assert_eq!(kept(&plan.segments), [None, None]);
+    }
+
+    #[test]
+    fn test_plan_keeps_unchanged_paragraphs_with_edits() {
+        let snapshot = snapshot(&[("A", "あ"), ("B", "い"), ("C", "う")]);
+        let output = "あ（編集済み）\n\nい\n\nう\n";
+        let plan = plan("A\n\nB2\n\nC\n\nD\n", Some(&snapshot), Some(output));
+        assert_eq!(
+            kept(&plan.segments),
+            [
+                Some("あ（編集済み）".to_string()),
+                None,
+                Some("う".to_string()),
+                None
+            ]
+        );
+        let previous = plan.segments[0].previous.as_ref().unwrap();
+        assert_eq!(previous.machine, "あ");
+        assert!(previous.is_edited());
+        assert!(!plan.segments[2].previous.as_ref().unwrap().is_edited());
+        assert!(plan.superseded.is_empty());
+    }
+
+    #[test]
+    fn test_plan_aligns_added_split_and_merged_paragraphs() {
+        let snapshot = snapshot(&[("A", "あ\n\nあ2"), ("B", "い"), ("C", "う")]);
+        // A translation spanning two paragraphs lines up as a whole
+        let plan_a = plan(
+            "A\n\nB\n\nC",
+            Some(&snapshot),
+            Some("あ\n\nあ2\n\nい*\n\nう\n"),
+        );
+        assert_eq!(kept(&plan_a.segments)[1], Some("い*".to_string()));
+
+        // Split, added, and merged paragraphs stay with their segment
+        let split = "あ\n\nあ2\n\nい（前）\n\nい（後）\n\n注\n\nう\n";
+        assert_eq!(
+            kept(&plan("A\n\nB\n\nC", Some(&snapshot), Some(split)).segments),
+            [
+                Some("あ\n\nあ2".to_string()),
+                Some("い（前）\n\nい（後）\n\n注".to_string()),
+                Some("う".to_string())
+            ]
+        );
+        let merged = "あ\n\nあ2\n\nいう\n";
+        assert_eq!(
+            kept(&plan("A\n\nB\n\nC", Some(&snapshot), Some(merged)).segments),
+            [
+                Some("あ\n\nあ2".to_string()),
+                Some("いう".to_string()),
+                Some(String::new())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_reports_superseded_edits() {
+        let snapshot = snapshot(&[("A", "あ"), ("B", "い"), ("C", "う")]);
+        let output = "あ\n\nい（直し）\n\nう（直し）\n";
+        let plan = plan("A\n\nB, revised\n", Some(&snapshot), Some(output));
+        assert_eq!(
+            plan.superseded,
+            [
+                SnapshotSegment {
+                    source: "B".to_string(),
+                    translation: "い（直し）".to_string(),
+                },
+                SnapshotSegment {
+                    source: "C".to_string(),
+                    translation: "う（直し）".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_discard_machine_translations_keeps_edits() {
+        let snapshot = snapshot(&[("A", "あ"), ("B", "い"), ("C", "う")]);
+        let output = "あ\n\nい（直し）\n\nう\n";
+        let mut plan = plan("A\n\nB\n\nC\n", Some(&snapshot), Some(output));
+        plan.discard_machine_translations();
+        assert_eq!(
+            kept(&plan.segments),
+            [None, Some("い（直し）".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn test_lock_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("doc.ja.md.tl-lock");
+        let path = path.to_str().unwrap();
+        assert!(Snapshot::load(path).unwrap().is_none());
+
+        let snapshot = snapshot(&[("A", "あ")]);
+        snapshot.save(path).unwrap();
+        assert_eq!(Snapshot::load(path).unwrap(), Some(snapshot));
+
+        fs::write(path, "{}").unwrap();
+        let err = Snapshot::load(path).unwrap_err();
+        assert!(err.to_string().contains("Invalid lock file"));
+        assert_eq!(assemble(&["あ\n", "", "い"]), "あ\n\nい\n");
+    }
+}
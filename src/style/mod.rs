@@ -3,9 +3,14 @@
 //! Provides preset and custom translation styles to control the tone
 //! and style of translations.
 
+mod pack;
+
 use std::collections::HashMap;
 
 use crate::config::CustomStyle;
+use crate::translation::{StyleExample, validate_language};
+
+pub use pack::{ImportPlan, StylePack};
 
 /// A preset translation style (hardcoded, not modifiable by users).
 #[derive(Debug, Clone)]
@@ -16,6 +21,8 @@ pub struct PresetStyle {
     pub description: &'static str,
     /// Prompt text appended to the system prompt.
     pub prompt: &'static str,
+    /// What the style controls; two styles controlling the same aspect conflict.
+    pub aspect: &'static str,
 }
 
 /// All available preset styles.
@@ -24,51 +31,79 @@ pub const PRESETS: &[PresetStyle] = &[
         key: "casual",
         description: "Casual, conversational tone",
         prompt: "Use a casual, friendly, conversational tone.",
+        aspect: "tone",
     },
     PresetStyle {
         key: "formal",
         description: "Formal, business-appropriate",
         prompt: "Use a formal, polite, business-appropriate tone.",
+        aspect: "tone",
     },
     PresetStyle {
         key: "literal",
         description: "Literal, close to source",
         prompt: "Translate as literally as possible while remaining grammatical.",
+        aspect: "wording",
     },
     PresetStyle {
         key: "natural",
         description: "Natural, idiomatic",
         prompt: "Translate naturally, prioritizing idiomatic expressions over literal accuracy.",
+        aspect: "wording",
     },
 ];
 
 /// Resolved style information.
+///
+/// A style may combine several styles (`formal,technical`), and custom
+/// styles may extend others. The prompt is the concatenation of every
+/// style involved, parents before the styles extending them and otherwise
+/// in the order given.
 #[derive(Debug, Clone)]
-pub enum ResolvedStyle {
-    /// A preset style.
-    Preset(&'static PresetStyle),
-    /// A custom user-defined style.
-    Custom { key: String, prompt: String },
+pub struct ResolvedStyle {
+    key: String,
+    parts: Vec<String>,
+    prompt: String,
+    template: Option<String>,
+    examples: Vec<StyleExample>,
 }
 
 impl ResolvedStyle {
     /// Returns the prompt text for this style.
     pub fn prompt(&self) -> &str {
-        match self {
-            Self::Preset(preset) => preset.prompt,
-            Self::Custom { prompt, .. } => prompt,
-        }
+        &self.prompt
     }
 
-    /// Returns the key for this style.
+    /// Returns the key for this style (comma-separated when combined).
     pub fn key(&self) -> &str {
-        match self {
-            Self::Preset(preset) => preset.key,
-            Self::Custom { key, .. } => key,
-        }
+        &self.key
+    }
+
+    /// Returns every style involved, in prompt order.
+    pub fn parts(&self) -> &[String] {
+        &self.parts
+    }
+
+    /// Returns the custom system prompt template for this style, if any.
+    pub fn template(&self) -> Option<&str> {
+        self.template.as_deref()
+    }
+
+    /// Returns the few-shot examples of every style involved, in prompt order.
+    pub fn examples(&self) -> &[StyleExample] {
+        &self.examples
     }
 }
 
+/// A single style reached while expanding a style key.
+struct StylePart<'a> {
+    key: &'a str,
+    prompt: &'a str,
+    aspect: Option<&'static str>,
+    template: Option<&'a str>,
+    examples: &'a [StyleExample],
+}
+
 /// Looks up a preset style by key.
 pub fn get_preset(key: &str) -> Option<&'static PresetStyle> {
     PRESETS.iter().find(|p| p.key == key)
@@ -87,36 +122,174 @@ pub fn sorted_custom_keys(styles: &HashMap<String, CustomStyle>) -> Vec<&String>
     keys
 }
 
+/// Returns presets followed by custom styles as a JSON object (`{"styles": [...]}`).
+#[allow(clippy::implicit_hasher)]
+pub fn styles_json(styles: &HashMap<String, CustomStyle>) -> serde_json::Value {
+    let presets = PRESETS.iter().map(|preset| {
+        serde_json::json!({
+            "name": preset.key,
+            "kind": "preset",
+            "description": preset.description,
+            "prompt": preset.prompt,
+        })
+    });
+    let custom = sorted_custom_keys(styles).into_iter().map(|key| {
+        let style = &styles[key];
+        serde_json::json!({
+            "name": key,
+            "kind": "custom",
+            "description": style.description,
+            "prompt": style.prompt,
+            "extends": style.extends,
+            "examples": style.examples,
+        })
+    });
+    let styles: Vec<_> = presets.chain(custom).collect();
+    serde_json::json!({ "styles": styles })
+}
+
+/// Splits a comma-separated style key into its components.
+fn split_keys(key: &str) -> impl Iterator<Item = &str> {
+    key.split(',').map(str::trim).filter(|k| !k.is_empty())
+}
+
 /// Resolves a style key to a `ResolvedStyle`.
 ///
-/// First checks presets, then custom styles.
-/// Returns an error if the style is not found.
+/// The key may name several styles separated by commas. Each is looked up
+/// in presets first, then custom styles, whose `extends` are expanded
+/// recursively. A style reached more than once is included once.
+///
+/// # Errors
+///
+/// Returns an error if a style is not found, styles extend each other in
+/// a cycle, or two styles control the same aspect (e.g. `casual` and
+/// `formal`) or set different prompt templates.
 #[allow(clippy::implicit_hasher)]
 pub fn resolve_style(
     key: &str,
     custom_styles: &HashMap<String, CustomStyle>,
 ) -> Result<ResolvedStyle, StyleError> {
+    let mut parts = Vec::new();
+    let mut chain = Vec::new();
+    for component in split_keys(key) {
+        expand_style(component, custom_styles, &mut chain, &mut parts)?;
+    }
+    if parts.is_empty() {
+        return Err(not_found(key, custom_styles));
+    }
+
+    for (index, part) in parts.iter().enumerate() {
+        for other in &parts[index + 1..] {
+            let conflict = |aspect: &str| StyleError::Conflict {
+                first: part.key.to_string(),
+                second: other.key.to_string(),
+                aspect: aspect.to_string(),
+            };
+            if let (Some(aspect), Some(other_aspect)) = (part.aspect, other.aspect)
+                && aspect == other_aspect
+            {
+                return Err(conflict(aspect));
+            }
+            if let (Some(template), Some(other_template)) = (part.template, other.template)
+                && template != other_template
+            {
+                return Err(conflict("prompt template"));
+            }
+        }
+    }
+
+    Ok(ResolvedStyle {
+        key: split_keys(key).collect::<Vec<_>>().join(","),
+        parts: parts.iter().map(|part| part.key.to_string()).collect(),
+        prompt: parts
+            .iter()
+            .map(|part| part.prompt)
+            .collect::<Vec<_>>()
+            .join(" "),
+        template: parts
+            .iter()
+            .find_map(|part| part.template)
+            .map(str::to_string),
+        examples: parts
+            .iter()
+            .flat_map(|part| part.examples.iter().cloned())
+            .collect(),
+    })
+}
+
+/// Appends `key` and the styles it extends to `parts`.
+///
+/// `chain` holds the custom styles currently being expanded, to detect cycles.
+fn expand_style<'a>(
+    key: &'a str,
+    custom_styles: &'a HashMap<String, CustomStyle>,
+    chain: &mut Vec<&'a str>,
+    parts: &mut Vec<StylePart<'a>>,
+) -> Result<(), StyleError> {
+    if parts.iter().any(|part| part.key == key) {
+        return Ok(());
+    }
+
     // Check presets first
     if let Some(preset) = get_preset(key) {
-        return Ok(ResolvedStyle::Preset(preset));
+        parts.push(StylePart {
+            key: preset.key,
+            prompt: preset.prompt,
+            aspect: Some(preset.aspect),
+            template: None,
+            examples: &[],
+        });
+        return Ok(());
     }
 
     // Check custom styles
-    if let Some(custom) = custom_styles.get(key) {
-        return Ok(ResolvedStyle::Custom {
-            key: key.to_string(),
-            prompt: custom.prompt.clone(),
+    let Some((key, custom)) = custom_styles.get_key_value(key) else {
+        return Err(not_found(key, custom_styles));
+    };
+    if let Some(start) = chain.iter().position(|k| k == key) {
+        let mut cycle: Vec<String> = chain[start..].iter().map(ToString::to_string).collect();
+        cycle.push(key.clone());
+        return Err(StyleError::Cycle(cycle));
+    }
+
+    chain.push(key);
+    for parent in split_keys(custom.extends.as_deref().unwrap_or_default()) {
+        expand_style(parent, custom_styles, chain, parts)?;
+    }
+    chain.pop();
+
+    let invalid = custom
+        .examples
+        .iter()
+        .flat_map(|example| [&example.source_language, &example.target_language])
+        .find(|language| validate_language(language).is_err());
+    if let Some(language) = invalid {
+        return Err(StyleError::InvalidExample {
+            key: key.clone(),
+            language: language.clone(),
         });
     }
 
+    parts.push(StylePart {
+        key,
+        prompt: &custom.prompt,
+        aspect: None,
+        template: custom.prompt_template.as_deref(),
+        examples: &custom.examples,
+    });
+    Ok(())
+}
+
+/// Builds a `NotFound` error listing the available custom styles.
+fn not_found(key: &str, custom_styles: &HashMap<String, CustomStyle>) -> StyleError {
     let custom_keys: Vec<String> = sorted_custom_keys(custom_styles)
         .into_iter()
         .cloned()
         .collect();
-    Err(StyleError::NotFound {
+    StyleError::NotFound {
         key: key.to_string(),
         custom_keys,
-    })
+    }
 }
 
 /// Style-related errors.
@@ -133,6 +306,16 @@ pub enum StyleError {
     AlreadyExists(String),
     /// Invalid style key format.
     InvalidKey(String),
+    /// Two combined styles control the same aspect.
+    Conflict {
+        first: String,
+        second: String,
+        aspect: String,
+    },
+    /// Custom styles extend each other in a cycle (the chain of keys).
+    Cycle(Vec<String>),
+    /// An example of a custom style has an invalid language tag.
+    InvalidExample { key: String, language: String },
 }
 
 impl std::fmt::Display for StyleError {
@@ -160,6 +343,30 @@ impl std::fmt::Display for StyleError {
                     "Invalid style key '{key}': must start with a letter and contain only alphanumeric characters and underscores"
                 )
             }
+            Self::Conflict {
+                first,
+                second,
+                aspect,
+            } => {
+                write!(
+                    f,
+                    "Styles '{first}' and '{second}' conflict: both set the {aspect}"
+                )
+            }
+            Self::Cycle(chain) => {
+                write!(
+                    f,
+                    "Style '{}' extends itself: {}",
+                    chain[0],
+                    chain.join(" -> ")
+                )
+            }
+            Self::InvalidExample { key, language } => {
+                write!(
+                    f,
+                    "Style '{key}' has an example with an invalid language '{language}'"
+                )
+            }
         }
     }
 }
@@ -195,6 +402,7 @@ pub fn validate_custom_key(key: &str) -> Result<(), StyleError> {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
@@ -230,6 +438,9 @@ mod tests {
             CustomStyle {
                 description: "z desc".to_string(),
                 prompt: "z prompt".to_string(),
+                prompt_template: None,
+                extends: None,
+                examples: Vec::new(),
             },
         );
         styles.insert(
@@ -237,6 +448,9 @@ mod tests {
             CustomStyle {
                 description: "a desc".to_string(),
                 prompt: "a prompt".to_string(),
+                prompt_template: None,
+                extends: None,
+                examples: Vec::new(),
             },
         );
         styles.insert(
@@ -244,6 +458,9 @@ mod tests {
             CustomStyle {
                 description: "b desc".to_string(),
                 prompt: "b prompt".to_string(),
+                prompt_template: None,
+                extends: None,
+                examples: Vec::new(),
             },
         );
 
@@ -277,6 +494,9 @@ mod tests {
             CustomStyle {
                 description: "My description".to_string(),
                 prompt: "My custom prompt".to_string(),
+                prompt_template: None,
+                extends: None,
+                examples: Vec::new(),
             },
         );
 
@@ -292,6 +512,95 @@ mod tests {
         );
     }
 
+    fn custom_style(prompt: &str, extends: Option<&str>) -> CustomStyle {
+        CustomStyle {
+            description: "desc".to_string(),
+            prompt: prompt.to_string(),
+            prompt_template: None,
+            extends: extends.map(str::to_string),
+            examples: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_style_combined() {
+        let mut custom = HashMap::new();
+        custom.insert("technical".to_string(), custom_style("Keep terms.", None));
+
+        let resolved = resolve_style("formal, technical", &custom).unwrap();
+        assert_eq!(resolved.key(), "formal,technical");
+        assert_eq!(resolved.parts(), ["formal", "technical"]);
+        assert_eq!(
+            resolved.prompt(),
+            "Use a formal, polite, business-appropriate tone. Keep terms."
+        );
+    }
+
+    #[test]
+    fn test_resolve_style_extends_puts_parents_first_once() {
+        let mut custom = HashMap::new();
+        custom.insert("technical".to_string(), custom_style("Keep terms.", None));
+        custom.insert(
+            "docs".to_string(),
+            custom_style("Keep headings.", Some("formal,technical")),
+        );
+
+        let resolved = resolve_style("technical,docs", &custom).unwrap();
+        assert_eq!(resolved.parts(), ["technical", "formal", "docs"]);
+        assert!(resolved.prompt().ends_with("Keep headings."));
+    }
+
+    #[test]
+    fn test_resolve_style_conflicts() {
+        let mut custom = HashMap::new();
+        custom.insert("chatty".to_string(), custom_style("Chat.", Some("casual")));
+
+        let err = resolve_style("formal,chatty", &custom).unwrap_err();
+        assert!(matches!(err, StyleError::Conflict { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Styles 'formal' and 'casual' conflict: both set the tone"
+        );
+        assert!(resolve_style("literal,natural", &custom).is_err());
+
+        let mut templated = custom_style("A.", None);
+        templated.prompt_template = Some("A {target_language}".to_string());
+        custom.insert("a".to_string(), templated);
+        let mut templated = custom_style("B.", None);
+        templated.prompt_template = Some("B {target_language}".to_string());
+        custom.insert("b".to_string(), templated);
+        assert!(matches!(
+            resolve_style("a,b", &custom),
+            Err(StyleError::Conflict { aspect, .. }) if aspect == "prompt template"
+        ));
+        assert_eq!(
+            resolve_style("a,formal", &custom).unwrap().template(),
+            Some("A {target_language}")
+        );
+    }
+
+    #[test]
+    fn test_resolve_style_cycle() {
+        let mut custom = HashMap::new();
+        custom.insert("a".to_string(), custom_style("A.", Some("b")));
+        custom.insert("b".to_string(), custom_style("B.", Some("c")));
+        custom.insert("c".to_string(), custom_style("C.", Some("b")));
+
+        let err = resolve_style("a", &custom).unwrap_err();
+        assert_eq!(err.to_string(), "Style 'b' extends itself: b -> c -> b");
+    }
+
+    #[test]
+    fn test_resolve_style_unknown_parent() {
+        let mut custom = HashMap::new();
+        custom.insert("a".to_string(), custom_style("A.", Some("missing")));
+        assert!(matches!(
+            resolve_style("a", &custom),
+            Err(StyleError::NotFound { key, .. }) if key == "missing"
+        ));
+        assert!(resolve_style(" , ", &custom).is_err());
+    }
+
     #[test]
     fn test_resolve_style_not_found() {
         let custom: HashMap<String, CustomStyle> = HashMap::new();
@@ -394,6 +703,9 @@ mod tests {
             CustomStyle {
                 description: "desc".to_string(),
                 prompt: "prompt".to_string(),
+                prompt_template: None,
+                extends: None,
+                examples: Vec::new(),
             },
         );
 
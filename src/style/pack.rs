@@ -0,0 +1,186 @@
+//! Style packs: custom styles shared as a TOML file.
+//!
+//! A pack has the same `[styles.<name>]` tables as the config file, so
+//! its contents can also be pasted into `config.toml` directly.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::{StyleError, get_preset, is_preset, not_found, split_keys, validate_custom_key};
+use crate::config::CustomStyle;
+
+/// A set of custom styles for `tl styles export` and `tl styles import`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StylePack {
+    /// Styles keyed by name (sorted, so exports are stable).
+    #[serde(default)]
+    pub styles: BTreeMap<String, CustomStyle>,
+}
+
+/// How importing a pack changes the existing custom styles.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportPlan {
+    /// Styles that do not exist yet.
+    pub added: Vec<String>,
+    /// Existing styles whose definition differs from the pack.
+    pub conflicts: Vec<String>,
+    /// Existing styles identical to the pack.
+    pub unchanged: Vec<String>,
+}
+
+impl StylePack {
+    /// Builds a pack from the named custom styles.
+    ///
+    /// Custom styles they extend are included too, so the pack is
+    /// self-contained. With no names, all custom styles are exported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a name is a preset or does not exist.
+    #[allow(clippy::implicit_hasher)]
+    pub fn export(
+        names: &[String],
+        custom_styles: &HashMap<String, CustomStyle>,
+    ) -> Result<Self, StyleError> {
+        let mut pack = Self::default();
+        if names.is_empty() {
+            pack.styles
+                .extend(custom_styles.iter().map(|(k, v)| (k.clone(), v.clone())));
+            return Ok(pack);
+        }
+
+        let mut pending: Vec<String> = names
+            .iter()
+            .flat_map(|name| split_keys(name))
+            .map(str::to_string)
+            .collect();
+        for name in &pending {
+            if is_preset(name) {
+                return Err(StyleError::PresetImmutable(name.clone()));
+            }
+        }
+        while let Some(name) = pending.pop() {
+            if pack.styles.contains_key(&name) || get_preset(&name).is_some() {
+                continue;
+            }
+            let style = custom_styles
+                .get(&name)
+                .ok_or_else(|| not_found(&name, custom_styles))?;
+            if let Some(ref extends) = style.extends {
+                pending.extend(split_keys(extends).map(str::to_string));
+            }
+            pack.styles.insert(name, style.clone());
+        }
+        Ok(pack)
+    }
+
+    /// Checks that every style key follows the custom style key rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StyleError::InvalidKey` (or `PresetImmutable` for preset
+    /// names) for the first invalid key.
+    pub fn validate(&self) -> Result<(), StyleError> {
+        self.styles
+            .keys()
+            .try_for_each(|key| validate_custom_key(key))
+    }
+
+    /// Compares the pack with the existing custom styles.
+    #[allow(clippy::implicit_hasher)]
+    pub fn plan(&self, custom_styles: &HashMap<String, CustomStyle>) -> ImportPlan {
+        let mut plan = ImportPlan::default();
+        for (key, style) in &self.styles {
+            let list = match custom_styles.get(key) {
+                None => &mut plan.added,
+                Some(existing) if existing == style => &mut plan.unchanged,
+                Some(_) => &mut plan.conflicts,
+            };
+            list.push(key.clone());
+        }
+        plan
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn style(prompt: &str, extends: Option<&str>) -> CustomStyle {
+        CustomStyle {
+            description: "desc".to_string(),
+            prompt: prompt.to_string(),
+            prompt_template: None,
+            extends: extends.map(str::to_string),
+            examples: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_export_includes_extended_custom_styles() {
+        let mut styles = HashMap::new();
+        styles.insert("technical".to_string(), style("T.", None));
+        styles.insert("docs".to_string(), style("D.", Some("formal,technical")));
+        styles.insert("other".to_string(), style("O.", None));
+
+        let pack = StylePack::export(&["docs".to_string()], &styles).unwrap();
+        assert_eq!(
+            pack.styles.keys().collect::<Vec<_>>(),
+            ["docs", "technical"]
+        );
+
+        let all = StylePack::export(&[], &styles).unwrap();
+        assert_eq!(all.styles.len(), 3);
+    }
+
+    #[test]
+    fn test_export_rejects_presets_and_unknown_styles() {
+        let styles = HashMap::new();
+        assert!(matches!(
+            StylePack::export(&["casual".to_string()], &styles),
+            Err(StyleError::PresetImmutable(_))
+        ));
+        assert!(matches!(
+            StylePack::export(&["missing".to_string()], &styles),
+            Err(StyleError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_pack_round_trips_through_toml() {
+        let mut styles = HashMap::new();
+        styles.insert("docs".to_string(), style("D.", Some("formal")));
+        let pack = StylePack::export(&[], &styles).unwrap();
+
+        let toml = toml::to_string_pretty(&pack).unwrap();
+        assert!(toml.contains("[styles.docs]"));
+        assert_eq!(toml::from_str::<StylePack>(&toml).unwrap(), pack);
+    }
+
+    #[test]
+    fn test_validate_and_plan() {
+        let mut pack = StylePack::default();
+        pack.styles
+            .insert("new_style".to_string(), style("N.", None));
+        pack.styles.insert("same".to_string(), style("S.", None));
+        pack.styles
+            .insert("changed".to_string(), style("C2.", None));
+        assert!(pack.validate().is_ok());
+
+        let mut existing = HashMap::new();
+        existing.insert("same".to_string(), style("S.", None));
+        existing.insert("changed".to_string(), style("C1.", None));
+        assert_eq!(
+            pack.plan(&existing),
+            ImportPlan {
+                added: vec!["new_style".to_string()],
+                conflicts: vec!["changed".to_string()],
+                unchanged: vec!["same".to_string()],
+            }
+        );
+
+        pack.styles.insert("bad-key".to_string(), style("B.", None));
+        assert!(matches!(pack.validate(), Err(StyleError::InvalidKey(_))));
+    }
+}
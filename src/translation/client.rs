@@ -1,14 +1,19 @@
 use anyhow::{Context, Result};
 use bytes::Bytes;
 use futures_util::Stream;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::borrow::Cow;
 use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
 
-use super::prompt::{SYSTEM_PROMPT_TEMPLATE, build_system_prompt_with_style};
-use super::sse_parser::sse_to_text_stream;
+use super::language::same_language;
+use super::prompt::{
+    PromptOptions, SOURCE_LANGUAGE_TEMPLATE, SYSTEM_PROMPT_TEMPLATE, build_system_prompt_with_style,
+};
+use super::sse_parser::{StreamEvent, Usage, sse_to_event_stream};
+use crate::error::TlError;
 
 /// A request to translate text.
 ///
@@ -20,29 +25,39 @@ pub struct TranslationRequest {
     pub source_text: String,
     /// The target language (ISO 639-1 code, e.g., "ja", "en").
     pub target_language: String,
+    /// The source language, if given or detected (`None` lets the model infer it).
+    pub source_language: Option<String>,
+    /// Whether `source_language` was detected rather than given. A detected
+    /// language only guides the prompt; it never skips the translation.
+    pub source_language_detected: bool,
     /// The model to use for translation.
     pub model: String,
     /// The API endpoint URL.
     pub endpoint: String,
     /// The translation style prompt (if specified).
     pub style: Option<String>,
+    /// Configurable prompt settings (language names, addenda, template, and examples).
+    pub prompt: PromptOptions,
 }
 
 impl TranslationRequest {
     /// Computes a unique cache key for this request.
     ///
-    /// The key is a SHA-256 hash of the source text, target language,
-    /// model, endpoint, style, and prompt template hash.
+    /// The key is a SHA-256 hash of the source text, source and target
+    /// languages, model, endpoint, style, prompt options, and prompt
+    /// template hash.
     pub fn cache_key(&self) -> String {
-        let prompt_hash = Self::prompt_hash();
+        let prompt_hash = self.prompt_hash();
 
         let cache_input = serde_json::json!({
             "source_text": self.source_text,
             "target_language": self.target_language,
+            "source_language": self.source_language,
             "model": self.model,
             "endpoint": self.endpoint,
             "prompt_hash": prompt_hash,
-            "style": self.style
+            "style": self.style,
+            "prompt": self.prompt
         });
 
         let mut hasher = Sha256::new();
@@ -50,12 +65,71 @@ impl TranslationRequest {
         hex::encode(hasher.finalize())
     }
 
-    /// Computes a hash of the system prompt template.
+    /// Computes the key of this request in the translation memory.
     ///
-    /// Used to invalidate cache when the prompt changes.
-    pub fn prompt_hash() -> String {
+    /// Preferred translations (corrections made by hand) apply whatever
+    /// the model or endpoint, but only with the same style and prompt, so
+    /// those are hashed along with the source text and target language.
+    pub fn memory_key(&self) -> String {
+        let memory_input = serde_json::json!({
+            "source_text": self.source_text,
+            "target_language": self.target_language,
+            "prompt_hash": self.prompt_hash(),
+            "style": self.style,
+            "prompt": self.prompt
+        });
+
+        let mut hasher = Sha256::new();
+        hasher.update(memory_input.to_string().as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Computes a hash of the settings this request is translated with.
+    ///
+    /// Like [`cache_key`](Self::cache_key) without the source text and
+    /// endpoint: a different model, style, or prompt gives a different
+    /// hash, so translations made with other settings can be told apart.
+    pub fn settings_hash(&self) -> String {
+        let settings_input = serde_json::json!({
+            "target_language": self.target_language,
+            "source_language": self.source_language,
+            "model": self.model,
+            "prompt_hash": self.prompt_hash(),
+            "style": self.style,
+            "prompt": self.prompt
+        });
+
+        let mut hasher = Sha256::new();
+        hasher.update(settings_input.to_string().as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Returns `true` if the source text is given as already being in the
+    /// target language.
+    ///
+    /// Such requests need no API call; the source text is the translation.
+    /// Detected source languages never count, since detection can be wrong
+    /// for mixed-language text.
+    pub fn is_same_language(&self) -> bool {
+        !self.source_language_detected
+            && self
+                .source_language
+                .as_deref()
+                .is_some_and(|source| same_language(source, &self.target_language))
+    }
+
+    /// Computes a hash of the effective system prompt template.
+    ///
+    /// This is the custom template if one is configured, otherwise the
+    /// built-in one. Used to invalidate cache when the prompt changes.
+    pub fn prompt_hash(&self) -> String {
         let mut hasher = Sha256::new();
-        hasher.update(SYSTEM_PROMPT_TEMPLATE.as_bytes());
+        if let Some(ref template) = self.prompt.template {
+            hasher.update(template.as_bytes());
+        } else {
+            hasher.update(SYSTEM_PROMPT_TEMPLATE.as_bytes());
+            hasher.update(SOURCE_LANGUAGE_TEMPLATE.as_bytes());
+        }
         hex::encode(hasher.finalize())
     }
 }
@@ -66,24 +140,72 @@ struct ChatCompletionRequest<'a> {
     model: &'a str,
     messages: Vec<Message<'a>>,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+}
+
+/// Streaming options (asks the API to report token usage at the end).
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    include_usage: bool,
 }
 
 impl<'a> ChatCompletionRequest<'a> {
     /// Builds a chat completion request for translation.
-    fn for_translation(model: &'a str, system_prompt: &'a str, source_text: &'a str) -> Self {
+    ///
+    /// Few-shot examples and conversation context become user/assistant
+    /// turns between the system prompt and the text to translate.
+    fn for_translation(
+        model: &'a str,
+        system_prompt: &'a str,
+        options: &'a PromptOptions,
+        source_text: &'a str,
+    ) -> Self {
+        let mut messages = vec![Message {
+            role: "system",
+            content: Cow::Borrowed(system_prompt),
+        }];
+        for example in options.prior_turns() {
+            messages.push(Message {
+                role: "user",
+                content: Cow::Borrowed(&example.source),
+            });
+            messages.push(Message {
+                role: "assistant",
+                content: Cow::Borrowed(&example.target),
+            });
+        }
+        messages.push(Message {
+            role: "user",
+            content: Cow::Borrowed(source_text),
+        });
+
+        Self::streaming(model, messages)
+    }
+
+    /// Builds a chat completion request with a single user message.
+    fn for_prompt(model: &'a str, system_prompt: &'a str, input: &'a str) -> Self {
+        let messages = vec![
+            Message {
+                role: "system",
+                content: Cow::Borrowed(system_prompt),
+            },
+            Message {
+                role: "user",
+                content: Cow::Borrowed(input),
+            },
+        ];
+        Self::streaming(model, messages)
+    }
+
+    const fn streaming(model: &'a str, messages: Vec<Message<'a>>) -> Self {
         Self {
             model,
-            messages: vec![
-                Message {
-                    role: "system",
-                    content: Cow::Borrowed(system_prompt),
-                },
-                Message {
-                    role: "user",
-                    content: Cow::Borrowed(source_text),
-                },
-            ],
+            messages,
             stream: true,
+            stream_options: Some(StreamOptions {
+                include_usage: true,
+            }),
         }
     }
 }
@@ -94,6 +216,41 @@ struct Message<'a> {
     content: Cow<'a, str>,
 }
 
+/// A stream of translated text chunks.
+///
+/// Yields text as it arrives. Token usage reported by the API is captured
+/// along the way and available from [`TranslationStream::usage`] once the
+/// stream is exhausted.
+pub struct TranslationStream {
+    inner: Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>,
+    usage: Option<Usage>,
+}
+
+impl TranslationStream {
+    /// Returns the token usage, if the API reported it.
+    pub const fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
+}
+
+impl Stream for TranslationStream {
+    type Item = Result<String>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(StreamEvent::Usage(usage)))) => self.usage = Some(usage),
+                Poll::Ready(Some(Ok(StreamEvent::Text(text)))) => {
+                    return Poll::Ready(Some(Ok(text)));
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
 /// Client for translating text using OpenAI-compatible APIs.
 ///
 /// Supports streaming responses for real-time output.
@@ -101,7 +258,7 @@ struct Message<'a> {
 /// # Example
 ///
 /// ```no_run
-/// use tl_cli::translation::{TranslationClient, TranslationRequest};
+/// use tl_cli::translation::{PromptOptions, TranslationClient, TranslationRequest};
 /// use futures_util::StreamExt;
 ///
 /// # async fn example() -> anyhow::Result<()> {
@@ -113,9 +270,12 @@ struct Message<'a> {
 /// let request = TranslationRequest {
 ///     source_text: "Hello, world!".to_string(),
 ///     target_language: "ja".to_string(),
+///     source_language: None,
+///     source_language_detected: false,
 ///     model: "gemma3:12b".to_string(),
 ///     endpoint: "http://localhost:11434".to_string(),
 ///     style: None,
+///     prompt: PromptOptions::default(),
 /// };
 ///
 /// let mut stream = client.translate_stream(&request).await?;
@@ -148,58 +308,104 @@ impl TranslationClient {
     pub async fn translate_stream(
         &self,
         request: &TranslationRequest,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<String>> + Send>>> {
-        let byte_stream = self
-            .send_chat_completion(
-                &request.model,
-                &request.target_language,
-                &request.source_text,
-                request.style.as_deref(),
-            )
-            .await?;
+    ) -> Result<TranslationStream> {
+        let byte_stream = self.send_chat_completion(request).await?;
 
-        Ok(Box::pin(sse_to_text_stream(byte_stream)))
+        Ok(TranslationStream {
+            inner: Box::pin(sse_to_event_stream(byte_stream)),
+            usage: None,
+        })
+    }
+
+    /// Sends `input` with a custom system prompt and returns a stream of
+    /// the response.
+    ///
+    /// Used for tasks other than translation, such as `tl review`.
+    pub async fn complete_stream(
+        &self,
+        model: &str,
+        system_prompt: &str,
+        input: &str,
+    ) -> Result<TranslationStream> {
+        let chat_request = ChatCompletionRequest::for_prompt(model, system_prompt, input);
+        let response = self.send_chat(chat_request).await?;
+
+        Ok(TranslationStream {
+            inner: Box::pin(sse_to_event_stream(response.bytes_stream())),
+            usage: None,
+        })
     }
 
     /// Sends a chat completion request and returns the raw byte stream.
     async fn send_chat_completion(
         &self,
-        model: &str,
-        target_language: &str,
-        source_text: &str,
-        style: Option<&str>,
+        request: &TranslationRequest,
     ) -> Result<impl Stream<Item = reqwest::Result<Bytes>> + Send + 'static> {
-        let url = self.build_url();
-        let system_prompt = build_system_prompt_with_style(target_language, style);
-        let chat_request =
-            ChatCompletionRequest::for_translation(model, &system_prompt, source_text);
+        let system_prompt = build_system_prompt_with_style(
+            &request.target_language,
+            request.source_language.as_deref(),
+            request.style.as_deref(),
+            &request.prompt,
+        );
+        let chat_request = ChatCompletionRequest::for_translation(
+            &request.model,
+            &system_prompt,
+            &request.prompt,
+            &request.source_text,
+        );
 
-        let response = self.send_request(&url, &chat_request).await?;
+        let response = self.send_chat(chat_request).await?;
 
         Ok(response.bytes_stream())
     }
 
-    /// Sends an HTTP POST request with optional authorization.
-    async fn send_request<T: Serialize + Sync>(
+    /// Sends a chat completion request.
+    ///
+    /// Not every OpenAI-compatible server accepts `stream_options`; if the
+    /// request is rejected with 400 Bad Request because of it, it is sent
+    /// once more without it (and without token usage). Any other 400 is
+    /// returned as is.
+    async fn send_chat(
         &self,
-        url: &str,
-        body: &T,
+        mut chat_request: ChatCompletionRequest<'_>,
     ) -> Result<reqwest::Response> {
+        let url = self.build_url();
+        let response = self.post(&url, &chat_request).await?;
+        if response.status() != StatusCode::BAD_REQUEST || chat_request.stream_options.is_none() {
+            return Self::check_status(response).await;
+        }
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !rejects_stream_options(&body) {
+            return Err(TlError::from_status(status, &body).into());
+        }
+
+        chat_request.stream_options = None;
+        let response = self.post(&url, &chat_request).await?;
+        Self::check_status(response).await
+    }
+
+    /// Sends an HTTP POST request with optional authorization.
+    async fn post<T: Serialize + Sync>(&self, url: &str, body: &T) -> Result<reqwest::Response> {
         let mut request = self.client.post(url).json(body);
 
         if let Some(api_key) = &self.api_key {
             request = request.header("Authorization", format!("Bearer {api_key}"));
         }
 
-        let response = request
-            .send()
-            .await
-            .with_context(|| format!("Failed to connect to API endpoint: {url}"))?;
+        request.send().await.with_context(|| TlError::Network {
+            message: format!("Failed to connect to API endpoint: {url}"),
+            status: None,
+        })
+    }
 
+    /// Turns an unsuccessful response into an error.
+    async fn check_status(response: reqwest::Response) -> Result<reqwest::Response> {
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("API request failed with status {status}: {body}");
+            return Err(TlError::from_status(status, &body).into());
         }
 
         Ok(response)
@@ -214,17 +420,35 @@ impl TranslationClient {
     }
 }
 
+/// Returns true if a 400 response body blames `stream_options` or an
+/// unknown parameter.
+fn rejects_stream_options(body: &str) -> bool {
+    let body = body.to_lowercase();
+    let unknown = ["unknown", "unrecognized", "unsupported", "unexpected"]
+        .iter()
+        .any(|word| body.contains(word));
+    let parameter = ["parameter", "field", "argument", "property"]
+        .iter()
+        .any(|word| body.contains(word));
+    body.contains("stream_options") || (unknown && parameter)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::find_tl_error;
+    use crate::translation::FewShotExample;
 
     fn create_test_request() -> TranslationRequest {
         TranslationRequest {
             source_text: "Hello, world!".to_string(),
             target_language: "ja".to_string(),
+            source_language: None,
+            source_language_detected: false,
             model: "gemma3:12b".to_string(),
             endpoint: "http://localhost:11434".to_string(),
             style: None,
+            prompt: PromptOptions::default(),
         }
     }
 
@@ -236,6 +460,60 @@ mod tests {
         assert_eq!(key1, key2);
     }
 
+    #[test]
+    fn test_settings_hash_ignores_source_text() {
+        let request = create_test_request();
+        let mut other_text = create_test_request();
+        other_text.source_text = "Goodbye".to_string();
+        assert_eq!(request.settings_hash(), other_text.settings_hash());
+
+        let mut other_style = create_test_request();
+        other_style.style = Some("casual".to_string());
+        assert_ne!(request.settings_hash(), other_style.settings_hash());
+        let mut other_model = create_test_request();
+        other_model.model = "other".to_string();
+        assert_ne!(request.settings_hash(), other_model.settings_hash());
+    }
+
+    #[test]
+    fn test_cache_key_includes_source_language() {
+        let request = create_test_request();
+        let mut with_source = create_test_request();
+        with_source.source_language = Some("en".to_string());
+        assert_ne!(request.cache_key(), with_source.cache_key());
+    }
+
+    #[test]
+    fn test_is_same_language() {
+        let mut request = create_test_request();
+        assert!(!request.is_same_language());
+        request.source_language = Some("en".to_string());
+        assert!(!request.is_same_language());
+        request.source_language = Some("ja".to_string());
+        assert!(request.is_same_language());
+        request.source_language = Some("pt-BR".to_string());
+        request.target_language = "pt".to_string();
+        assert!(request.is_same_language());
+        request.target_language = "pt-PT".to_string();
+        assert!(!request.is_same_language());
+        request.source_language_detected = true;
+        request.target_language = "pt-BR".to_string();
+        assert!(!request.is_same_language());
+    }
+
+    #[test]
+    fn test_memory_key_ignores_model_but_not_style() {
+        let request = create_test_request();
+        let mut other_model = create_test_request();
+        other_model.model = "gpt-4o".to_string();
+        other_model.endpoint = "https://api.openai.com".to_string();
+        assert_eq!(request.memory_key(), other_model.memory_key());
+
+        let mut styled = create_test_request();
+        styled.style = Some("Use a casual tone.".to_string());
+        assert_ne!(request.memory_key(), styled.memory_key());
+    }
+
     #[test]
     fn test_cache_key_is_hex_string() {
         let request = create_test_request();
@@ -277,16 +555,70 @@ mod tests {
         assert_ne!(request1.cache_key(), request2.cache_key());
     }
 
+    #[test]
+    fn test_cache_key_differs_for_examples() {
+        let request1 = create_test_request();
+        let mut request2 = create_test_request();
+        request2.prompt.examples = vec![FewShotExample {
+            source: "Hi".to_string(),
+            target: "やあ".to_string(),
+        }];
+        assert_ne!(request1.cache_key(), request2.cache_key());
+    }
+
+    #[test]
+    fn test_for_translation_puts_examples_and_context_before_input() {
+        let pair = |source: &str, target: &str| FewShotExample {
+            source: source.to_string(),
+            target: target.to_string(),
+        };
+        let options = PromptOptions {
+            examples: vec![pair("Hi", "やあ")],
+            context: vec![pair("I met Ann.", "アンに会った。")],
+            ..PromptOptions::default()
+        };
+        let request =
+            ChatCompletionRequest::for_translation("m", "system", &options, "She smiled.");
+        let roles: Vec<_> = request.messages.iter().map(|m| m.role).collect();
+        assert_eq!(
+            roles,
+            ["system", "user", "assistant", "user", "assistant", "user"]
+        );
+        assert_eq!(request.messages[2].content, "やあ");
+        assert_eq!(request.messages[4].content, "アンに会った。");
+        assert_eq!(request.messages[5].content, "She smiled.");
+    }
+
+    #[test]
+    fn test_cache_key_differs_for_context() {
+        let request1 = create_test_request();
+        let mut request2 = create_test_request();
+        request2.prompt.context = vec![FewShotExample {
+            source: "I met Ann.".to_string(),
+            target: "アンに会った。".to_string(),
+        }];
+        assert_ne!(request1.cache_key(), request2.cache_key());
+    }
+
     #[test]
     fn test_prompt_hash_is_consistent() {
-        let hash1 = TranslationRequest::prompt_hash();
-        let hash2 = TranslationRequest::prompt_hash();
+        let hash1 = create_test_request().prompt_hash();
+        let hash2 = create_test_request().prompt_hash();
         assert_eq!(hash1, hash2);
     }
 
+    #[test]
+    fn test_prompt_hash_covers_custom_template() {
+        let request1 = create_test_request();
+        let mut request2 = create_test_request();
+        request2.prompt.template = Some("Translate into {target_language}.".to_string());
+        assert_ne!(request1.prompt_hash(), request2.prompt_hash());
+        assert_ne!(request1.cache_key(), request2.cache_key());
+    }
+
     #[test]
     fn test_prompt_hash_is_hex_string() {
-        let hash = TranslationRequest::prompt_hash();
+        let hash = create_test_request().prompt_hash();
         // SHA-256 produces 64 hex characters
         assert_eq!(hash.len(), 64);
         assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
@@ -318,6 +650,20 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_connection_failure_is_network_error() {
+        // Port 9 (discard) is not expected to accept connections in test environments
+        let client = TranslationClient::new("http://127.0.0.1:9".to_string(), None);
+        let Err(err) = client.translate_stream(&create_test_request()).await else {
+            panic!("expected a connection error");
+        };
+        assert!(matches!(
+            find_tl_error(&err),
+            Some(TlError::Network { status: None, .. })
+        ));
+        assert!(err.to_string().contains("Failed to connect"));
+    }
+
     #[test]
     fn test_build_url_with_trailing_slash() {
         let client = TranslationClient::new("http://localhost:11434/".to_string(), None);
@@ -326,4 +672,15 @@ mod tests {
             "http://localhost:11434/v1/chat/completions"
         );
     }
+
+    #[test]
+    fn test_rejects_stream_options() {
+        assert!(rejects_stream_options("Unrecognized field: stream_options"));
+        assert!(rejects_stream_options(
+            r#"{"error":{"message":"Unknown parameter: 'stream_options.include_usage'"}}"#
+        ));
+        assert!(rejects_stream_options("unsupported parameter"));
+        assert!(!rejects_stream_options("model 'foo' not found"));
+        assert!(!rejects_stream_options("context length exceeded"));
+    }
 }
@@ -0,0 +1,361 @@
+//! Local source-language detection.
+//!
+//! A cheap heuristic that avoids an extra API call: the writing system
+//! identifies most languages outright, and Latin-script text is scored
+//! against a few very common words per language. Detection returns `None`
+//! when the evidence is weak, in which case the model is left to infer the
+//! source language itself.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use super::language::canonicalize_language;
+
+/// Value of `--from` (and `/set from`) that requests automatic detection.
+pub const AUTO_DETECT: &str = "auto";
+
+/// Minimum number of letters needed before attempting detection.
+const MIN_LETTERS: usize = 3;
+
+/// Minimum number of common-word hits for Latin-script languages.
+const MIN_WORD_HITS: usize = 2;
+
+/// Very common, fairly distinctive words for Latin-script languages.
+const COMMON_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "of", "to", "this", "that", "with", "you", "it", "was",
+            "for", "have", "not",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "une", "des", "du", "que", "pas", "pour", "dans",
+            "vous", "nous", "je",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "ich", "mit", "zu", "sie",
+            "auf", "für", "wir",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "y", "es", "una", "que", "por", "para", "con", "está", "del",
+            "pero", "muy", "yo",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "gli", "e", "è", "che", "di", "una", "per", "non", "sono", "con", "della",
+            "questo", "io", "anche",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "as", "e", "é", "que", "não", "uma", "para", "com", "do", "da", "em",
+            "você", "eu",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "niet", "van", "dat", "ik", "je", "met", "zijn",
+            "voor", "op", "wij",
+        ],
+    ),
+];
+
+/// Writing systems distinguished by the detector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Kana,
+    Han,
+    Hangul,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Thai,
+    Devanagari,
+}
+
+impl Script {
+    const fn of(c: char) -> Option<Self> {
+        match c {
+            'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' => Some(Self::Latin),
+            '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9F}' => {
+                Some(Self::Kana)
+            }
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => Some(Self::Han),
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' => {
+                Some(Self::Hangul)
+            }
+            '\u{0400}'..='\u{04FF}' => Some(Self::Cyrillic),
+            '\u{0370}'..='\u{03FF}' => Some(Self::Greek),
+            '\u{0600}'..='\u{06FF}' => Some(Self::Arabic),
+            '\u{0590}'..='\u{05FF}' => Some(Self::Hebrew),
+            '\u{0E00}'..='\u{0E7F}' => Some(Self::Thai),
+            '\u{0900}'..='\u{097F}' => Some(Self::Devanagari),
+            _ => None,
+        }
+    }
+}
+
+/// Detects the language of `text`.
+///
+/// Returns an ISO 639-1 code from the supported list, or `None` if the
+/// language cannot be determined with reasonable confidence.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    const SCRIPTS: [Script; 10] = [
+        Script::Latin,
+        Script::Kana,
+        Script::Han,
+        Script::Hangul,
+        Script::Cyrillic,
+        Script::Greek,
+        Script::Arabic,
+        Script::Hebrew,
+        Script::Thai,
+        Script::Devanagari,
+    ];
+
+    let mut counts = [0usize; SCRIPTS.len()];
+    for script in text.chars().filter_map(Script::of) {
+        if let Some(index) = SCRIPTS.iter().position(|s| *s == script) {
+            counts[index] += 1;
+        }
+    }
+    let count = |script: Script| {
+        SCRIPTS
+            .iter()
+            .position(|s| *s == script)
+            .map_or(0, |index| counts[index])
+    };
+
+    let total: usize = counts.iter().sum();
+    if total < MIN_LETTERS {
+        return None;
+    }
+
+    // Japanese mixes kana with kanji (and often Latin-script names); when
+    // those make up most of the text, any meaningful share of kana decides it
+    let kana = count(Script::Kana);
+    let cjk = kana + count(Script::Han);
+    if cjk * 2 >= total && kana * 10 >= cjk {
+        return Some("ja");
+    }
+
+    let (dominant, dominant_count) = SCRIPTS
+        .iter()
+        .zip(counts)
+        .max_by_key(|(_, n)| *n)
+        .map(|(s, n)| (*s, n))?;
+    if dominant_count * 2 < total {
+        return None;
+    }
+
+    match dominant {
+        Script::Latin => detect_latin(text),
+        Script::Kana => Some("ja"),
+        Script::Han => Some("zh"),
+        Script::Hangul => Some("ko"),
+        Script::Cyrillic => Some(detect_cyrillic(text)),
+        Script::Greek => Some("el"),
+        Script::Arabic => Some(detect_arabic(text)),
+        Script::Hebrew => Some("he"),
+        Script::Thai => Some("th"),
+        Script::Devanagari => Some("hi"),
+    }
+}
+
+/// Resolves the source language of `text`.
+///
+/// An explicit language code is used as-is; `None` or [`AUTO_DETECT`]
+/// runs [`detect_language`].
+pub fn resolve_source_language(from: Option<&str>, text: &str) -> Option<String> {
+    match from {
+        Some(lang) if lang != AUTO_DETECT => Some(lang.to_string()),
+        _ => detect_language(text).map(str::to_string),
+    }
+}
+
+/// Returns `true` if [`resolve_source_language`] detects the source
+/// language for `from` rather than taking it as given.
+pub fn is_detected(from: Option<&str>) -> bool {
+    from.is_none_or(|lang| lang == AUTO_DETECT)
+}
+
+/// Canonicalizes a `--from` value through `aliases`.
+///
+/// Returns `None` for [`AUTO_DETECT`] (or no value), meaning the source
+/// language should be detected.
+///
+/// # Errors
+///
+/// Returns a usage error if the language is not a valid tag.
+pub fn canonicalize_source_language<S: BuildHasher>(
+    from: Option<&str>,
+    aliases: &HashMap<String, String, S>,
+) -> Result<Option<String>> {
+    from.filter(|lang| *lang != AUTO_DETECT)
+        .map(|lang| canonicalize_language(lang, aliases))
+        .transpose()
+}
+
+/// Scores Latin-script text against common words of each language.
+fn detect_latin(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut scores: Vec<(&'static str, usize)> = COMMON_WORDS
+        .iter()
+        .map(|(code, common)| {
+            let hits = words
+                .iter()
+                .filter(|w| common.contains(&w.as_str()))
+                .count();
+            (*code, hits)
+        })
+        .collect();
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+
+    match scores.as_slice() {
+        [(code, best), (_, second), ..] if *best >= MIN_WORD_HITS && best > second => Some(code),
+        _ => None,
+    }
+}
+
+/// Distinguishes Ukrainian from Russian by letters unique to Ukrainian.
+fn detect_cyrillic(text: &str) -> &'static str {
+    if text
+        .chars()
+        .any(|c| matches!(c, 'і' | 'ї' | 'є' | 'ґ' | 'І' | 'Ї' | 'Є' | 'Ґ'))
+    {
+        "uk"
+    } else {
+        "ru"
+    }
+}
+
+/// Distinguishes Persian from Arabic by letters unique to Persian.
+fn detect_arabic(text: &str) -> &'static str {
+    if text.chars().any(|c| matches!(c, 'پ' | 'چ' | 'ژ' | 'گ')) {
+        "fa"
+    } else {
+        "ar"
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_by_script() {
+        assert_eq!(detect_language("こんにちは、世界"), Some("ja"));
+        assert_eq!(detect_language("東京は日本の首都です"), Some("ja"));
+        assert_eq!(detect_language("你好，世界"), Some("zh"));
+        assert_eq!(detect_language("안녕하세요 세계"), Some("ko"));
+        assert_eq!(detect_language("Привет, мир"), Some("ru"));
+        assert_eq!(detect_language("Привіт, світе"), Some("uk"));
+        assert_eq!(detect_language("Γεια σου κόσμε"), Some("el"));
+        assert_eq!(detect_language("مرحبا بالعالم"), Some("ar"));
+        assert_eq!(detect_language("שלום עולם"), Some("he"));
+        assert_eq!(detect_language("สวัสดีชาวโลก"), Some("th"));
+        assert_eq!(detect_language("नमस्ते दुनिया"), Some("hi"));
+    }
+
+    #[test]
+    fn test_detect_latin_by_common_words() {
+        assert_eq!(
+            detect_language("The quick brown fox jumps over the lazy dog and it is fast."),
+            Some("en")
+        );
+        assert_eq!(
+            detect_language("Le chat est sur la table et il dort dans le salon."),
+            Some("fr")
+        );
+        assert_eq!(
+            detect_language("Der Hund ist nicht im Haus und die Katze schläft."),
+            Some("de")
+        );
+        assert_eq!(
+            detect_language("El perro está en la casa y los gatos duermen por la noche."),
+            Some("es")
+        );
+    }
+
+    #[test]
+    fn test_detect_returns_none_when_unsure() {
+        assert_eq!(detect_language(""), None);
+        assert_eq!(detect_language("12345 !!"), None);
+        assert_eq!(detect_language("Hi"), None);
+        assert_eq!(detect_language("Kubernetes Docker Terraform"), None);
+    }
+
+    #[test]
+    fn test_resolve_source_language() {
+        assert_eq!(
+            resolve_source_language(Some("fr"), "こんにちは"),
+            Some("fr".to_string())
+        );
+        assert_eq!(
+            resolve_source_language(Some(AUTO_DETECT), "こんにちは"),
+            Some("ja".to_string())
+        );
+        assert_eq!(
+            resolve_source_language(None, "こんにちは"),
+            Some("ja".to_string())
+        );
+        assert_eq!(resolve_source_language(None, "42"), None);
+    }
+
+    #[test]
+    fn test_canonicalize_source_language() {
+        let aliases = HashMap::from([("br".to_string(), "pt-BR".to_string())]);
+        assert_eq!(canonicalize_source_language(None, &aliases).unwrap(), None);
+        assert_eq!(
+            canonicalize_source_language(Some(AUTO_DETECT), &aliases).unwrap(),
+            None
+        );
+        assert_eq!(
+            canonicalize_source_language(Some("br"), &aliases).unwrap(),
+            Some("pt-BR".to_string())
+        );
+        assert!(canonicalize_source_language(Some("xx"), &aliases).is_err());
+    }
+
+    #[test]
+    fn test_detect_ignores_embedded_latin_in_japanese() {
+        assert_eq!(
+            detect_language("このAPIはJSONを返します。詳しくはREADMEを参照してください。"),
+            Some("ja")
+        );
+    }
+
+    #[test]
+    fn test_detect_ignores_embedded_japanese_in_english() {
+        assert_eq!(
+            detect_language("In Japanese, ありがとうございます means thank you."),
+            None
+        );
+        assert_eq!(
+            detect_language("The word ありがとう is used to say thank you to a friend."),
+            Some("en")
+        );
+    }
+}
@@ -1,10 +1,20 @@
 //! Language code validation and supported languages.
+//!
+//! Languages are BCP 47 tags limited to a primary language, an optional
+//! script and an optional region (`ja`, `zh-Hant`, `pt-BR`, `es-419`).
 
 use anyhow::Result;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
 
+use crate::error::TlError;
+use crate::output;
 use crate::ui::Style;
 
 /// Supported language codes (ISO 639-1) and their names.
+///
+/// Entries with a subtag name common regional or script variants; other
+/// combinations of a listed language with a script or region are valid too.
 pub const SUPPORTED_LANGUAGES: &[(&str, &str)] = &[
     ("af", "Afrikaans"),
     ("am", "Amharic"),
@@ -22,6 +32,7 @@ pub const SUPPORTED_LANGUAGES: &[(&str, &str)] = &[
     ("el", "Greek"),
     ("en", "English"),
     ("es", "Spanish"),
+    ("es-419", "Spanish (Latin America)"),
     ("et", "Estonian"),
     ("eu", "Basque"),
     ("fa", "Persian"),
@@ -63,6 +74,8 @@ pub const SUPPORTED_LANGUAGES: &[(&str, &str)] = &[
     ("pl", "Polish"),
     ("ps", "Pashto"),
     ("pt", "Portuguese"),
+    ("pt-BR", "Portuguese (Brazil)"),
+    ("pt-PT", "Portuguese (Portugal)"),
     ("ro", "Romanian"),
     ("ru", "Russian"),
     ("si", "Sinhala"),
@@ -82,35 +95,471 @@ pub const SUPPORTED_LANGUAGES: &[(&str, &str)] = &[
     ("uz", "Uzbek"),
     ("vi", "Vietnamese"),
     ("zh", "Chinese (Simplified)"),
+    ("zh-Hans", "Chinese (Simplified)"),
+    ("zh-Hant", "Chinese (Traditional)"),
     ("zh-TW", "Chinese (Traditional)"),
 ];
 
+/// Native names (endonyms) of common languages, keyed by primary subtag.
+const NATIVE_NAMES: &[(&str, &str)] = &[
+    ("ar", "العربية"),
+    ("bg", "Български"),
+    ("cs", "Čeština"),
+    ("da", "Dansk"),
+    ("de", "Deutsch"),
+    ("el", "Ελληνικά"),
+    ("en", "English"),
+    ("es", "Español"),
+    ("fa", "فارسی"),
+    ("fi", "Suomi"),
+    ("fr", "Français"),
+    ("he", "עברית"),
+    ("hi", "हिन्दी"),
+    ("hu", "Magyar"),
+    ("id", "Bahasa Indonesia"),
+    ("it", "Italiano"),
+    ("ja", "日本語"),
+    ("ko", "한국어"),
+    ("ms", "Bahasa Melayu"),
+    ("nl", "Nederlands"),
+    ("no", "Norsk"),
+    ("pl", "Polski"),
+    ("pt", "Português"),
+    ("ro", "Română"),
+    ("ru", "Русский"),
+    ("sv", "Svenska"),
+    ("th", "ไทย"),
+    ("tr", "Türkçe"),
+    ("uk", "Українська"),
+    ("vi", "Tiếng Việt"),
+    ("zh", "中文"),
+];
+
+/// Script subtags (ISO 15924) with display names.
+const SCRIPTS: &[(&str, &str)] = &[
+    ("Arab", "Arabic script"),
+    ("Cyrl", "Cyrillic"),
+    ("Deva", "Devanagari"),
+    ("Grek", "Greek script"),
+    ("Hans", "Simplified"),
+    ("Hant", "Traditional"),
+    ("Hebr", "Hebrew script"),
+    ("Jpan", "Japanese script"),
+    ("Kore", "Korean script"),
+    ("Latn", "Latin"),
+    ("Thai", "Thai script"),
+];
+
+/// Scripts implied by tags that do not name one, most specific first.
+///
+/// Only languages written in more than one common script are listed, so
+/// that `zh-TW` is known to be Traditional Chinese and plain `zh`
+/// Simplified.
+const LIKELY_SCRIPTS: &[(&str, &str)] = &[
+    ("zh-CN", "Hans"),
+    ("zh-SG", "Hans"),
+    ("zh-HK", "Hant"),
+    ("zh-MO", "Hant"),
+    ("zh-TW", "Hant"),
+    ("zh", "Hans"),
+];
+
+/// Region subtags (ISO 3166-1 and UN M.49) with display names.
+const REGIONS: &[(&str, &str)] = &[
+    ("419", "Latin America"),
+    ("AR", "Argentina"),
+    ("AT", "Austria"),
+    ("AU", "Australia"),
+    ("BE", "Belgium"),
+    ("BR", "Brazil"),
+    ("CA", "Canada"),
+    ("CH", "Switzerland"),
+    ("CN", "China"),
+    ("CO", "Colombia"),
+    ("DE", "Germany"),
+    ("ES", "Spain"),
+    ("FR", "France"),
+    ("GB", "United Kingdom"),
+    ("HK", "Hong Kong"),
+    ("IE", "Ireland"),
+    ("IN", "India"),
+    ("MO", "Macau"),
+    ("MX", "Mexico"),
+    ("NZ", "New Zealand"),
+    ("PT", "Portugal"),
+    ("SG", "Singapore"),
+    ("TW", "Taiwan"),
+    ("US", "United States"),
+];
+
+/// A language tag: primary language with optional script and region.
+///
+/// Parsing normalizes case (`zh-hant-tw` becomes `zh-Hant-TW`), so the
+/// [`Display`](std::fmt::Display) form is canonical and suitable for cache
+/// keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageTag {
+    /// Primary language subtag (ISO 639-1, or 639-2 where listed).
+    pub language: String,
+    /// Script subtag (ISO 15924), e.g. `Hant`.
+    pub script: Option<String>,
+    /// Region subtag (ISO 3166-1 alpha-2 or UN M.49), e.g. `BR` or `419`.
+    pub region: Option<String>,
+}
+
+impl LanguageTag {
+    /// Parses and validates a language tag.
+    ///
+    /// `_` is accepted as a separator. The primary language must be one
+    /// of [`SUPPORTED_LANGUAGES`]; script and region only need to be
+    /// well-formed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a usage error if the tag is malformed or the language is
+    /// not supported.
+    pub fn parse(tag: &str) -> Result<Self> {
+        Self::parse_subtags(tag).ok_or_else(|| {
+            TlError::Usage(format!(
+                "Invalid language code: '{tag}'\n\n\
+                 Use an ISO 639-1 code (ja, en, zh, ...), optionally with a script\n\
+                 or region (zh-Hant, pt-BR, es-419).\n\
+                 Run 'tl languages' to see all supported codes."
+            ))
+            .into()
+        })
+    }
+
+    fn parse_subtags(tag: &str) -> Option<Self> {
+        let mut subtags = tag.split(['-', '_']).peekable();
+
+        let language = subtags.next()?.to_ascii_lowercase();
+        let is_base = |code: &&str| !code.contains('-') && *code == language;
+        if !SUPPORTED_LANGUAGES
+            .iter()
+            .map(|(code, _)| code)
+            .any(is_base)
+        {
+            return None;
+        }
+
+        let script = subtags
+            .next_if(|s| s.len() == 4 && s.chars().all(|c| c.is_ascii_alphabetic()))
+            .map(|s| {
+                let (first, rest) = s.split_at(1);
+                first.to_ascii_uppercase() + &rest.to_ascii_lowercase()
+            });
+        let region = subtags
+            .next_if(|s| {
+                (s.len() == 2 && s.chars().all(|c| c.is_ascii_alphabetic()))
+                    || (s.len() == 3 && s.chars().all(|c| c.is_ascii_digit()))
+            })
+            .map(str::to_ascii_uppercase);
+
+        // Variants and extensions are not supported
+        if subtags.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            language,
+            script,
+            region,
+        })
+    }
+
+    /// Returns `true` if both tags name the same language.
+    ///
+    /// The primary languages must be equal; scripts (see
+    /// [`likely_script`](Self::likely_script)) and regions only have to
+    /// agree where both tags give one, so `pt-BR` matches `pt` but not
+    /// `pt-PT`, and `zh` matches `zh-Hans` but not `zh-TW`.
+    pub fn matches(&self, other: &Self) -> bool {
+        fn agree(a: Option<&str>, b: Option<&str>) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+        }
+        self.language == other.language
+            && agree(self.likely_script(), other.likely_script())
+            && agree(self.region.as_deref(), other.region.as_deref())
+    }
+
+    /// Returns the script subtag, or the script the tag implies.
+    ///
+    /// `zh` and `zh-CN` imply `Hans`; `zh-TW`, `zh-HK` and `zh-MO` imply
+    /// `Hant`.
+    pub fn likely_script(&self) -> Option<&str> {
+        if let Some(ref script) = self.script {
+            return Some(script);
+        }
+        let regional = self
+            .region
+            .as_ref()
+            .map(|region| format!("{}-{region}", self.language));
+        LIKELY_SCRIPTS
+            .iter()
+            .find(|(tag, _)| regional.as_deref() == Some(*tag))
+            .or_else(|| LIKELY_SCRIPTS.iter().find(|(tag, _)| *tag == self.language))
+            .map(|(_, script)| *script)
+    }
+
+    /// Returns the native name of the language, if known (e.g. `日本語`).
+    pub fn native_name(&self) -> Option<&'static str> {
+        NATIVE_NAMES
+            .iter()
+            .find(|(code, _)| *code == self.language)
+            .map(|(_, name)| *name)
+    }
+
+    /// Returns `true` if the tag has a script or region subtag.
+    pub const fn is_variant(&self) -> bool {
+        self.script.is_some() || self.region.is_some()
+    }
+
+    /// Returns a human-readable name, e.g. `Portuguese (Brazil)`.
+    ///
+    /// Listed tags use their name from [`SUPPORTED_LANGUAGES`]; otherwise
+    /// the name of the longest listed prefix is qualified with the names
+    /// of the remaining subtags (or the subtags themselves if unknown).
+    pub fn name(&self) -> String {
+        let lookup = |code: &str| {
+            SUPPORTED_LANGUAGES
+                .iter()
+                .find(|(c, _)| *c == code)
+                .map(|(_, name)| *name)
+        };
+        if let Some(name) = lookup(&self.to_string()) {
+            return name.to_string();
+        }
+
+        let with_script = self
+            .script
+            .as_ref()
+            .and_then(|script| lookup(&format!("{}-{script}", self.language)));
+        let (base, script) = with_script.map_or_else(
+            || {
+                (
+                    lookup(&self.language).unwrap_or(&self.language),
+                    self.script.as_deref(),
+                )
+            },
+            |name| (name, None),
+        );
+
+        let subtag_name = |table: &[(&str, &'static str)], code: &str| {
+            table
+                .iter()
+                .find(|(c, _)| *c == code)
+                .map_or_else(|| code.to_string(), |(_, name)| (*name).to_string())
+        };
+        let qualifiers: Vec<String> = script
+            .map(|s| subtag_name(SCRIPTS, s))
+            .into_iter()
+            .chain(self.region.as_deref().map(|r| subtag_name(REGIONS, r)))
+            .collect();
+        if qualifiers.is_empty() {
+            return base.to_string();
+        }
+
+        // Merge into an existing parenthetical: "Chinese (Simplified, Singapore)"
+        let qualifiers = qualifiers.join(", ");
+        base.strip_suffix(')').map_or_else(
+            || format!("{base} ({qualifiers})"),
+            |open| format!("{open}, {qualifiers})"),
+        )
+    }
+}
+
+impl std::fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.language)?;
+        if let Some(ref script) = self.script {
+            write!(f, "-{script}")?;
+        }
+        if let Some(ref region) = self.region {
+            write!(f, "-{region}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves a user-supplied language into a canonical tag.
+///
+/// `aliases` (the `[aliases]` table in config) is consulted first, so
+/// `br = "pt-BR"` lets `--to br` mean Brazilian Portuguese.
+///
+/// # Errors
+///
+/// Returns a usage error if the language (or the alias target) is not a
+/// valid tag.
+pub fn canonicalize_language<S: BuildHasher>(
+    lang: &str,
+    aliases: &HashMap<String, String, S>,
+) -> Result<String> {
+    let tag = aliases.get(lang).map_or(lang, String::as_str);
+    Ok(LanguageTag::parse(tag)?.to_string())
+}
+
+/// Returns how a language is referred to in the system prompt.
+///
+/// Codes are resolved to their English names (`ja` becomes `Japanese`,
+/// `pt-BR` becomes `Portuguese (Brazil)`), since small models sometimes
+/// misread bare codes. With `native`, the native name is added when it
+/// differs: `Japanese (日本語)`.
+pub fn prompt_language_name(lang: &str, native: bool) -> String {
+    let Ok(tag) = LanguageTag::parse(lang) else {
+        return lang.to_string();
+    };
+    let name = tag.name();
+    match tag.native_name() {
+        Some(native_name) if native && native_name != name => format!("{name} ({native_name})"),
+        _ => name,
+    }
+}
+
+/// Returns `true` if two language tags name the same language (see
+/// [`LanguageTag::matches`]). Tags that do not parse must be equal.
+pub fn same_language(a: &str, b: &str) -> bool {
+    match (LanguageTag::parse(a), LanguageTag::parse(b)) {
+        (Ok(a), Ok(b)) => a.matches(&b),
+        _ => a == b,
+    }
+}
+
+/// Returns the human-readable name of a language tag, if it is valid.
+pub fn language_name(lang: &str) -> Option<String> {
+    LanguageTag::parse(lang).ok().map(|tag| tag.name())
+}
+
+/// Returns all supported languages as a JSON object (`{"languages": [...]}`).
+pub fn languages_json() -> serde_json::Value {
+    let languages: Vec<_> = SUPPORTED_LANGUAGES
+        .iter()
+        .map(|(code, name)| serde_json::json!({ "code": code, "name": name }))
+        .collect();
+    serde_json::json!({ "languages": languages })
+}
+
 /// Prints all supported language codes to stdout.
-pub fn print_languages() {
+pub fn print_languages() -> Result<()> {
+    if output::is_json() {
+        return output::print_json(&languages_json());
+    }
+
     println!("{}", Style::header("Supported language codes (ISO 639-1)"));
     for (code, name) in SUPPORTED_LANGUAGES {
-        println!("  {:5} {}", Style::code(code), Style::secondary(name));
+        println!("  {:7} {}", Style::code(code), Style::secondary(name));
     }
+    println!();
+    println!(
+        "{}",
+        Style::hint(
+            "Any language above can take a script or region subtag (BCP 47), e.g. sr-Latn, en-GB."
+        )
+    );
+    Ok(())
 }
 
 /// Validates that the given language code is supported.
 ///
 /// # Errors
 ///
-/// Returns an error if the language code is not in the supported list.
+/// Returns an error if the language code is not a valid tag for a
+/// supported language.
 pub fn validate_language(lang: &str) -> Result<()> {
-    if SUPPORTED_LANGUAGES.iter().any(|(code, _)| *code == lang) {
-        Ok(())
-    } else {
-        anyhow::bail!(
-            "Invalid language code: '{lang}'\n\n\
-             Valid language codes (ISO 639-1): ja, en, zh, ko, fr, de, es, ...\n\
-             Run 'tl languages' to see all supported codes."
-        )
+    LanguageTag::parse(lang).map(|_| ())
+}
+
+/// A bidirectional language pair for automatic target selection.
+///
+/// Input detected as the primary language is translated into the
+/// secondary language; everything else (including undetected input) is
+/// translated into the primary language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguagePair {
+    /// The "home" language, used as the default target.
+    pub primary: String,
+    /// The language that primary-language input is translated into.
+    pub secondary: String,
+}
+
+impl LanguagePair {
+    /// Parses a pair from a comma-separated string such as `ja,en`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a usage error if the pair is malformed or uses an
+    /// unsupported language code.
+    pub fn parse<S: BuildHasher>(
+        value: &str,
+        aliases: &HashMap<String, String, S>,
+    ) -> Result<Self> {
+        let codes: Vec<String> = value.split(',').map(|s| s.trim().to_string()).collect();
+        Self::from_codes(&codes, aliases)
+    }
+
+    /// Builds a pair from exactly two language codes (or aliases).
+    ///
+    /// # Errors
+    ///
+    /// Returns a usage error unless there are exactly two distinct,
+    /// supported language codes.
+    pub fn from_codes<S: BuildHasher>(
+        codes: &[String],
+        aliases: &HashMap<String, String, S>,
+    ) -> Result<Self> {
+        let [primary, secondary] = codes else {
+            return Err(TlError::Usage(format!(
+                "Invalid language pair: '{}'\n\n\
+                 A pair needs exactly two language codes, e.g. ja,en",
+                codes.join(",")
+            ))
+            .into());
+        };
+        let primary = canonicalize_language(primary, aliases)?;
+        let secondary = canonicalize_language(secondary, aliases)?;
+        if primary == secondary {
+            return Err(TlError::Usage(format!(
+                "Invalid language pair: '{primary},{secondary}'\n\n\
+                 The two languages must differ"
+            ))
+            .into());
+        }
+        Ok(Self { primary, secondary })
+    }
+
+    /// Returns the target language for input in `source`.
+    ///
+    /// Tags are compared with [`same_language`], so a detected `pt` matches
+    /// a `pt-BR` pair. If `source` matches both languages, an exact match
+    /// decides; otherwise the input is translated into the primary language.
+    pub fn target_for(&self, source: Option<&str>) -> &str {
+        let Some(source) = source else {
+            return &self.primary;
+        };
+        let is_primary = if source == self.secondary {
+            false
+        } else {
+            source == self.primary
+                || (same_language(source, &self.primary) && !same_language(source, &self.secondary))
+        };
+        if is_primary {
+            &self.secondary
+        } else {
+            &self.primary
+        }
+    }
+}
+
+impl std::fmt::Display for LanguagePair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.primary, self.secondary)
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
@@ -127,4 +576,111 @@ mod tests {
         assert!(validate_language("").is_err());
         assert!(validate_language("JP").is_err()); // Case sensitive
     }
+
+    #[test]
+    fn test_language_pair_parse() {
+        let pair = LanguagePair::parse("ja, en", &HashMap::new()).unwrap();
+        assert_eq!(pair.primary, "ja");
+        assert_eq!(pair.secondary, "en");
+        assert_eq!(pair.to_string(), "ja,en");
+
+        assert!(LanguagePair::parse("ja", &HashMap::new()).is_err());
+        assert!(LanguagePair::parse("ja,en,zh", &HashMap::new()).is_err());
+        assert!(LanguagePair::parse("ja,ja", &HashMap::new()).is_err());
+        assert!(LanguagePair::parse("ja,xx", &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_language_pair_target_for() {
+        let pair = LanguagePair::parse("ja,en", &HashMap::new()).unwrap();
+        assert_eq!(pair.target_for(Some("ja")), "en");
+        assert_eq!(pair.target_for(Some("en")), "ja");
+        assert_eq!(pair.target_for(Some("fr")), "ja");
+        assert_eq!(pair.target_for(None), "ja");
+    }
+
+    #[test]
+    fn test_language_pair_target_for_regional_and_script_tags() {
+        let pair = LanguagePair::parse("pt-BR,en", &HashMap::new()).unwrap();
+        assert_eq!(pair.target_for(Some("pt")), "en");
+        assert_eq!(pair.target_for(Some("pt-BR")), "en");
+        assert_eq!(pair.target_for(Some("en")), "pt-BR");
+        assert_eq!(pair.target_for(Some("pt-PT")), "pt-BR");
+
+        let pair = LanguagePair::parse("zh-Hans,zh-Hant", &HashMap::new()).unwrap();
+        assert_eq!(pair.target_for(Some("zh-Hans")), "zh-Hant");
+        assert_eq!(pair.target_for(Some("zh-Hant-TW")), "zh-Hans");
+        assert_eq!(pair.target_for(Some("zh")), "zh-Hant");
+        assert_eq!(pair.target_for(Some("zh-TW")), "zh-Hans");
+    }
+
+    #[test]
+    fn test_same_language() {
+        assert!(same_language("pt-BR", "pt"));
+        assert!(same_language("zh", "zh-Hans-CN"));
+        assert!(same_language("zh-TW", "zh-Hant"));
+        assert!(same_language("zh-HK", "zh-Hant-HK"));
+        assert!(!same_language("zh", "zh-Hant-TW"));
+        assert!(!same_language("zh-Hans", "zh-TW"));
+        assert!(!same_language("zh-CN", "zh-HK"));
+        assert!(!same_language("pt-BR", "pt-PT"));
+        assert!(!same_language("zh-Hans", "zh-Hant"));
+        assert!(!same_language("en", "ja"));
+    }
+
+    #[test]
+    fn test_language_tag_parse_normalizes_case() {
+        let tag = LanguageTag::parse("zh_hant_tw").unwrap();
+        assert_eq!(tag.language, "zh");
+        assert_eq!(tag.script.as_deref(), Some("Hant"));
+        assert_eq!(tag.region.as_deref(), Some("TW"));
+        assert_eq!(tag.to_string(), "zh-Hant-TW");
+        assert_eq!(LanguageTag::parse("PT-br").unwrap().to_string(), "pt-BR");
+        assert_eq!(LanguageTag::parse("es-419").unwrap().to_string(), "es-419");
+    }
+
+    #[test]
+    fn test_language_tag_parse_rejects_malformed() {
+        assert!(LanguageTag::parse("xx-BR").is_err()); // unknown language
+        assert!(LanguageTag::parse("pt-BRA").is_err()); // bad region
+        assert!(LanguageTag::parse("pt-BR-x").is_err()); // trailing subtag
+        assert!(LanguageTag::parse("en-US-Latn").is_err()); // script after region
+        assert!(LanguageTag::parse("zh-TW-").is_err());
+    }
+
+    #[test]
+    fn test_language_tag_name() {
+        let name = |tag: &str| LanguageTag::parse(tag).unwrap().name();
+        assert_eq!(name("ja"), "Japanese");
+        assert_eq!(name("pt-BR"), "Portuguese (Brazil)");
+        assert_eq!(name("es-419"), "Spanish (Latin America)");
+        assert_eq!(name("zh-Hant"), "Chinese (Traditional)");
+        assert_eq!(name("zh-Hant-HK"), "Chinese (Traditional, Hong Kong)");
+        assert_eq!(name("sr-Latn"), "Serbian (Latin)");
+        assert_eq!(name("en-GB"), "English (United Kingdom)");
+        assert_eq!(name("en-ZA"), "English (ZA)");
+    }
+
+    #[test]
+    fn test_canonicalize_language_with_aliases() {
+        let aliases = HashMap::from([
+            ("br".to_string(), "pt-BR".to_string()),
+            ("bad".to_string(), "nope".to_string()),
+        ]);
+        assert_eq!(canonicalize_language("br", &aliases).unwrap(), "pt-BR");
+        assert_eq!(
+            canonicalize_language("zh-hant", &aliases).unwrap(),
+            "zh-Hant"
+        );
+        assert!(canonicalize_language("bad", &aliases).is_err());
+    }
+
+    #[test]
+    fn test_prompt_language_name() {
+        assert_eq!(prompt_language_name("ja", false), "Japanese");
+        assert_eq!(prompt_language_name("pt-BR", false), "Portuguese (Brazil)");
+        assert_eq!(prompt_language_name("ja", true), "Japanese (日本語)");
+        assert_eq!(prompt_language_name("en", true), "English");
+        assert_eq!(prompt_language_name("am", true), "Amharic");
+    }
 }
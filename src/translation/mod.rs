@@ -1,7 +1,20 @@
 mod client;
+mod detect;
 mod language;
 mod prompt;
 mod sse_parser;
 
-pub use client::{TranslationClient, TranslationRequest};
-pub use language::{SUPPORTED_LANGUAGES, print_languages, validate_language};
+pub use client::{TranslationClient, TranslationRequest, TranslationStream};
+pub use detect::{
+    AUTO_DETECT, canonicalize_source_language, detect_language, is_detected,
+    resolve_source_language,
+};
+pub use language::{
+    LanguagePair, LanguageTag, SUPPORTED_LANGUAGES, canonicalize_language, language_name,
+    languages_json, print_languages, prompt_language_name, same_language, validate_language,
+};
+pub use prompt::{
+    FewShotExample, PromptOptions, StyleExample, TEMPLATE_PLACEHOLDERS, validate_template,
+};
+pub use sse_parser::Usage;
+pub(crate) use sse_parser::{StreamEvent, parse_sse_events};
@@ -1,36 +1,300 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::language::{prompt_language_name, same_language};
+use crate::error::TlError;
+
 pub const SYSTEM_PROMPT_TEMPLATE: &str = "You are a translator. Translate the following text to {target_language}. \
      Output only the translated text without any explanations. \
      Preserve the original formatting including blank lines and whitespace.";
 
-/// Builds the system prompt with optional style instructions.
+/// Sentence added to the system prompt when the source language is known.
+pub const SOURCE_LANGUAGE_TEMPLATE: &str = "The source text is in {source_language}.";
+
+/// Placeholders that may appear in a custom prompt template.
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &["target_language", "source_language", "context"];
+
+/// Sentence added to the system prompt when earlier exchanges are included.
+pub const CONTEXT_NOTE: &str = "Earlier messages of this conversation are included for context; \
+     keep names and terminology consistent with them.";
+
+/// Text substituted for `{source_language}` when the source language is unknown.
+const UNKNOWN_SOURCE_LANGUAGE: &str = "the source language";
+
+/// An example translation shown to the model before the actual input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FewShotExample {
+    /// Example source text.
+    pub source: String,
+    /// The desired translation of `source`.
+    pub target: String,
+}
+
+/// An example translation of a custom style, for one language pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StyleExample {
+    /// The language of `source`.
+    pub source_language: String,
+    /// The language of `target`.
+    pub target_language: String,
+    /// Example source text.
+    pub source: String,
+    /// The desired translation of `source`.
+    pub target: String,
+}
+
+impl StyleExample {
+    /// Returns `true` if the example fits a translation into `target`
+    /// from `source` (any source language when it is unknown).
+    pub fn applies_to(&self, source: Option<&str>, target: &str) -> bool {
+        same_language(&self.target_language, target)
+            && source.is_none_or(|source| same_language(&self.source_language, source))
+    }
+
+    /// Returns the source and translation of the example.
+    pub fn pair(&self) -> FewShotExample {
+        FewShotExample {
+            source: self.source.clone(),
+            target: self.target.clone(),
+        }
+    }
+}
+
+/// Configurable parts of the system prompt.
+///
+/// These are part of the cache key, so changing them re-translates.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PromptOptions {
+    /// Add native language names, e.g. "Japanese (日本語)".
+    pub native_names: bool,
+    /// Extra instructions for the target language (from `[language_prompts]`).
+    pub language_prompt: Option<String>,
+    /// Custom template replacing [`SYSTEM_PROMPT_TEMPLATE`].
+    ///
+    /// Covered by the request's prompt hash rather than serialized here.
+    #[serde(skip)]
+    pub template: Option<String>,
+    /// Example translations sent as prior user/assistant turns.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<FewShotExample>,
+    /// Earlier source/translation pairs of a conversation, sent after the examples.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<FewShotExample>,
+}
+
+impl PromptOptions {
+    /// Returns the examples followed by the context, in message order.
+    pub fn prior_turns(&self) -> impl Iterator<Item = &FewShotExample> {
+        self.examples.iter().chain(&self.context)
+    }
+}
+
+/// Checks that a prompt template only uses known placeholders.
+///
+/// Braces that do not enclose an identifier (e.g. JSON examples) are
+/// left alone. `location` names where the template was configured, e.g.
+/// `[tl]` or `style 'docs'`.
+///
+/// # Errors
+///
+/// Returns a configuration error naming the first unknown placeholder.
+pub fn validate_template(template: &str, location: &str) -> Result<()> {
+    for name in template_placeholders(template) {
+        if !TEMPLATE_PLACEHOLDERS.contains(&name) {
+            let available: Vec<String> = TEMPLATE_PLACEHOLDERS
+                .iter()
+                .map(|name| format!("{{{name}}}"))
+                .collect();
+            return Err(TlError::Config(format!(
+                "Invalid 'prompt_template' in {location}: \
+                 Unknown placeholder '{{{name}}}' in prompt template\n\n\
+                 Available placeholders: {}",
+                available.join(", ")
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the names of `{identifier}` placeholders in `template`.
+fn template_placeholders(template: &str) -> impl Iterator<Item = &str> {
+    template.split('{').skip(1).filter_map(|rest| {
+        let name = &rest[..rest.find('}')?];
+        let is_identifier =
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        is_identifier.then_some(name)
+    })
+}
+
+/// Builds the system prompt with optional source language and style instructions.
+///
+/// Languages are referred to by name rather than code; a per-language
+/// addendum from `options` comes before the style instructions. A custom
+/// template in `options` replaces the built-in one, including the source
+/// language sentence; `{context}` expands to a note about earlier messages
+/// when there are any.
 #[allow(clippy::literal_string_with_formatting_args)]
-pub fn build_system_prompt_with_style(target_language: &str, style: Option<&str>) -> String {
+pub fn build_system_prompt_with_style(
+    target_language: &str,
+    source_language: Option<&str>,
+    style: Option<&str>,
+    options: &PromptOptions,
+) -> String {
+    let name = |lang| prompt_language_name(lang, options.native_names);
+    let context_note = if options.context.is_empty() {
+        ""
+    } else {
+        CONTEXT_NOTE
+    };
+
     // {target_language} is a placeholder for string replacement, not a format argument
-    let base = SYSTEM_PROMPT_TEMPLATE.replace("{target_language}", target_language);
-    match style {
-        Some(style_prompt) => format!("{base} {style_prompt}"),
-        None => base,
+    let mut prompt = options.template.as_ref().map_or_else(
+        || {
+            let mut prompt =
+                SYSTEM_PROMPT_TEMPLATE.replace("{target_language}", &name(target_language));
+            if let Some(source) = source_language {
+                prompt.push(' ');
+                prompt.push_str(
+                    &SOURCE_LANGUAGE_TEMPLATE.replace("{source_language}", &name(source)),
+                );
+            }
+            prompt
+        },
+        |template| {
+            let source = source_language.map_or_else(|| UNKNOWN_SOURCE_LANGUAGE.to_string(), name);
+            template
+                .replace("{target_language}", &name(target_language))
+                .replace("{source_language}", &source)
+                .replace("{context}", context_note)
+                .trim_end()
+                .to_string()
+        },
+    );
+    if let Some(ref language_prompt) = options.language_prompt {
+        prompt.push(' ');
+        prompt.push_str(language_prompt);
+    }
+    if let Some(style_prompt) = style {
+        prompt.push(' ');
+        prompt.push_str(style_prompt);
     }
+    prompt
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_build_system_prompt_with_style_no_style() {
-        let prompt = build_system_prompt_with_style("Japanese", None);
+        let prompt = build_system_prompt_with_style("ja", None, None, &PromptOptions::default());
         assert!(prompt.contains("Japanese"));
         assert!(prompt.contains("Translate the following text"));
     }
 
     #[test]
     fn test_build_system_prompt_with_style_casual() {
-        let prompt = build_system_prompt_with_style("Japanese", Some("Use a casual tone."));
+        let prompt = build_system_prompt_with_style(
+            "ja",
+            None,
+            Some("Use a casual tone."),
+            &PromptOptions::default(),
+        );
         assert!(prompt.contains("Japanese"));
         assert!(prompt.contains("Use a casual tone."));
     }
 
+    #[test]
+    fn test_build_system_prompt_with_source_language() {
+        let prompt = build_system_prompt_with_style(
+            "ja",
+            Some("en"),
+            Some("Use a casual tone."),
+            &PromptOptions::default(),
+        );
+        assert!(prompt.contains("The source text is in English."));
+        assert!(prompt.ends_with("Use a casual tone."));
+
+        let prompt = build_system_prompt_with_style("ja", None, None, &PromptOptions::default());
+        assert!(!prompt.contains("source text is in"));
+    }
+
+    #[test]
+    fn test_build_system_prompt_spells_out_regional_variants() {
+        let prompt = build_system_prompt_with_style(
+            "pt-BR",
+            Some("zh-Hant"),
+            None,
+            &PromptOptions::default(),
+        );
+        assert!(prompt.contains("to Portuguese (Brazil)."));
+        assert!(prompt.contains("The source text is in Chinese (Traditional)."));
+    }
+
+    #[test]
+    fn test_build_system_prompt_with_options() {
+        let options = PromptOptions {
+            native_names: true,
+            language_prompt: Some("Use polite keigo.".to_string()),
+            template: None,
+            examples: Vec::new(),
+            context: Vec::new(),
+        };
+        let prompt = build_system_prompt_with_style("ja", None, Some("Be brief."), &options);
+        assert!(prompt.contains("to Japanese (日本語)."));
+        assert!(prompt.ends_with("Use polite keigo. Be brief."));
+    }
+
+    #[test]
+    fn test_build_system_prompt_with_template() {
+        let options = PromptOptions {
+            template: Some(
+                "Translate from {source_language} into {target_language}.\n{context}".to_string(),
+            ),
+            ..PromptOptions::default()
+        };
+        let prompt = build_system_prompt_with_style("ja", Some("en"), Some("Be brief."), &options);
+        assert_eq!(prompt, "Translate from English into Japanese. Be brief.");
+
+        let prompt = build_system_prompt_with_style("ja", None, None, &options);
+        assert_eq!(prompt, "Translate from the source language into Japanese.");
+    }
+
+    #[test]
+    fn test_validate_template() {
+        assert!(validate_template("To {target_language}: {\"keep\": 1} { }", "[tl]").is_ok());
+        assert!(validate_template(SYSTEM_PROMPT_TEMPLATE, "[tl]").is_ok());
+
+        let err = validate_template("To {target_lang}.", "style 'docs'").unwrap_err();
+        assert!(matches!(
+            crate::error::find_tl_error(&err),
+            Some(TlError::Config(_))
+        ));
+        assert!(err.to_string().contains("'{target_lang}'"));
+        assert!(err.to_string().contains("style 'docs'"));
+
+        // No glossary exists yet, so its placeholder would silently vanish
+        assert!(validate_template("To {target_language}.{glossary}", "[tl]").is_err());
+    }
+
+    #[test]
+    fn test_style_example_applies_to_matching_languages() {
+        let example = StyleExample {
+            source_language: "en".to_string(),
+            target_language: "pt-BR".to_string(),
+            source: "Hi".to_string(),
+            target: "Oi".to_string(),
+        };
+        assert!(example.applies_to(Some("en"), "pt-BR"));
+        assert!(example.applies_to(Some("en-GB"), "pt"));
+        assert!(example.applies_to(None, "pt-BR"));
+        assert!(!example.applies_to(Some("en"), "de"));
+        assert!(!example.applies_to(Some("en"), "pt-PT"));
+        assert!(!example.applies_to(Some("fr"), "pt-BR"));
+    }
+
     #[test]
     fn test_system_prompt_template_has_placeholder() {
         assert!(SYSTEM_PROMPT_TEMPLATE.contains("{target_language}"));
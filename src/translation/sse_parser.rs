@@ -5,12 +5,50 @@
 use anyhow::Result;
 use bytes::Bytes;
 use futures_util::Stream;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::error::TlError;
+
+/// Token usage reported by the API at the end of a stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Tokens in the prompt (system prompt and source text).
+    pub prompt_tokens: u32,
+    /// Tokens in the generated translation.
+    pub completion_tokens: u32,
+    /// Total tokens billed for the request.
+    pub total_tokens: u32,
+}
+
+impl std::ops::Add for Usage {
+    type Output = Self;
+
+    /// Adds up the usage of several requests.
+    fn add(self, other: Self) -> Self {
+        Self {
+            prompt_tokens: self.prompt_tokens + other.prompt_tokens,
+            completion_tokens: self.completion_tokens + other.completion_tokens,
+            total_tokens: self.total_tokens + other.total_tokens,
+        }
+    }
+}
+
+/// An event extracted from the SSE stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamEvent {
+    /// A chunk of translated text.
+    Text(String),
+    /// Token usage for the whole request.
+    Usage(Usage),
+}
 
 /// Response structure for streaming chat completions.
 #[derive(Debug, Deserialize)]
 struct StreamResponse {
+    #[serde(default)]
     choices: Vec<StreamChoice>,
+    #[serde(default)]
+    usage: Option<Usage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,7 +61,7 @@ struct Delta {
     content: Option<String>,
 }
 
-/// Converts a raw SSE byte stream into a stream of text chunks.
+/// Converts a raw SSE byte stream into a stream of events.
 ///
 /// Handles buffering, line parsing, and SSE protocol details.
 ///
@@ -33,10 +71,10 @@ struct Delta {
 ///
 /// # Returns
 ///
-/// A stream that yields extracted text content from each SSE data event.
-pub fn sse_to_text_stream(
+/// A stream that yields extracted text content and token usage from SSE data events.
+pub fn sse_to_event_stream(
     byte_stream: impl Stream<Item = reqwest::Result<Bytes>> + Send + 'static,
-) -> impl Stream<Item = Result<String>> + Send {
+) -> impl Stream<Item = Result<StreamEvent>> + Send {
     async_stream::stream! {
         use futures_util::StreamExt;
 
@@ -47,7 +85,11 @@ pub fn sse_to_text_stream(
             let chunk = match chunk_result {
                 Ok(c) => c,
                 Err(e) => {
-                    yield Err(anyhow::anyhow!("Stream error: {e}"));
+                    yield Err(TlError::Network {
+                        message: format!("Stream error: {e}"),
+                        status: None,
+                    }
+                    .into());
                     continue;
                 }
             };
@@ -57,37 +99,30 @@ pub fn sse_to_text_stream(
             while let Some(line_end) = buffer.find('\n') {
                 let line: String = buffer.drain(..=line_end).collect();
 
-                if let Some(content) = parse_sse_line(line.trim()) {
-                    yield Ok(content);
-                } else if line.trim() == "data: [DONE]" {
+                let line = line.trim();
+                if line == "data: [DONE]" {
                     return;
                 }
+
+                for event in parse_sse_events(line) {
+                    yield Ok(event);
+                }
             }
         }
     }
 }
 
-/// Parses a single SSE line and extracts the text content.
-///
-/// # Arguments
-///
-/// * `line` - A trimmed SSE line (e.g., `data: {"choices":[...]}`)
-///
-/// # Returns
+/// Parses a single SSE line into its text content and usage events.
 ///
-/// * `Some(content)` - The extracted text content if the line contains valid data
-/// * `None` - For non-data lines, empty content, or parse errors
-///
-/// # Example
-///
-/// ```ignore
-/// let line = r#"data: {"choices":[{"delta":{"content":"Hello"}}]}"#;
-/// assert_eq!(parse_sse_line(line), Some("Hello".to_string()));
-/// ```
-fn parse_sse_line(line: &str) -> Option<String> {
-    let json_str = line.strip_prefix("data: ")?;
+/// Returns an empty list for non-data lines, empty content, or parse errors.
+pub fn parse_sse_events(line: &str) -> Vec<StreamEvent> {
+    let Some(json_str) = line.strip_prefix("data: ") else {
+        return Vec::new();
+    };
 
-    let response = serde_json::from_str::<StreamResponse>(json_str).ok()?;
+    let Ok(response) = serde_json::from_str::<StreamResponse>(json_str) else {
+        return Vec::new();
+    };
 
     let content: String = response
         .choices
@@ -96,74 +131,112 @@ fn parse_sse_line(line: &str) -> Option<String> {
         .filter(|c| !c.is_empty())
         .collect();
 
-    if content.is_empty() {
-        None
-    } else {
-        Some(content)
+    let mut events = Vec::new();
+    if !content.is_empty() {
+        events.push(StreamEvent::Text(content));
     }
+    if let Some(usage) = response.usage {
+        events.push(StreamEvent::Usage(usage));
+    }
+    events
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn text(content: &str) -> Vec<StreamEvent> {
+        vec![StreamEvent::Text(content.to_string())]
+    }
+
     #[test]
-    fn test_parse_sse_line_with_content() {
+    fn test_parse_sse_events_with_content() {
         let line = r#"data: {"choices":[{"delta":{"content":"Hello"}}]}"#;
-        assert_eq!(parse_sse_line(line), Some("Hello".to_string()));
+        assert_eq!(parse_sse_events(line), text("Hello"));
     }
 
     #[test]
-    fn test_parse_sse_line_with_empty_content() {
+    fn test_parse_sse_events_with_empty_content() {
         let line = r#"data: {"choices":[{"delta":{"content":""}}]}"#;
-        assert_eq!(parse_sse_line(line), None);
+        assert!(parse_sse_events(line).is_empty());
     }
 
     #[test]
-    fn test_parse_sse_line_with_null_content() {
+    fn test_parse_sse_events_with_null_content() {
         let line = r#"data: {"choices":[{"delta":{}}]}"#;
-        assert_eq!(parse_sse_line(line), None);
+        assert!(parse_sse_events(line).is_empty());
     }
 
     #[test]
-    fn test_parse_sse_line_multiple_choices() {
+    fn test_parse_sse_events_multiple_choices() {
         let line =
             r#"data: {"choices":[{"delta":{"content":"Hello"}},{"delta":{"content":" World"}}]}"#;
-        assert_eq!(parse_sse_line(line), Some("Hello World".to_string()));
+        assert_eq!(parse_sse_events(line), text("Hello World"));
     }
 
     #[test]
-    fn test_parse_sse_line_no_data_prefix() {
+    fn test_parse_sse_events_no_data_prefix() {
         let line = r#"{"choices":[{"delta":{"content":"Hello"}}]}"#;
-        assert_eq!(parse_sse_line(line), None);
+        assert!(parse_sse_events(line).is_empty());
     }
 
     #[test]
-    fn test_parse_sse_line_invalid_json() {
+    fn test_parse_sse_events_invalid_json() {
         let line = "data: not json";
-        assert_eq!(parse_sse_line(line), None);
+        assert!(parse_sse_events(line).is_empty());
     }
 
     #[test]
-    fn test_parse_sse_line_done_marker() {
+    fn test_parse_sse_events_done_marker() {
         let line = "data: [DONE]";
-        assert_eq!(parse_sse_line(line), None);
+        assert!(parse_sse_events(line).is_empty());
     }
 
     #[test]
-    fn test_parse_sse_line_empty_line() {
-        assert_eq!(parse_sse_line(""), None);
+    fn test_parse_sse_events_empty_line() {
+        assert!(parse_sse_events("").is_empty());
     }
 
     #[test]
-    fn test_parse_sse_line_comment() {
+    fn test_parse_sse_events_comment() {
         let line = ": this is a comment";
-        assert_eq!(parse_sse_line(line), None);
+        assert!(parse_sse_events(line).is_empty());
     }
 
     #[test]
-    fn test_parse_sse_line_unicode_content() {
+    fn test_parse_sse_events_unicode_content() {
         let line = r#"data: {"choices":[{"delta":{"content":"こんにちは"}}]}"#;
-        assert_eq!(parse_sse_line(line), Some("こんにちは".to_string()));
+        assert_eq!(parse_sse_events(line), text("こんにちは"));
+    }
+
+    #[test]
+    fn test_parse_sse_events_usage_chunk() {
+        let line = r#"data: {"choices":[],"usage":{"prompt_tokens":12,"completion_tokens":5,"total_tokens":17}}"#;
+        assert_eq!(
+            parse_sse_events(line),
+            vec![StreamEvent::Usage(Usage {
+                prompt_tokens: 12,
+                completion_tokens: 5,
+                total_tokens: 17,
+            })]
+        );
+    }
+
+    #[test]
+    fn test_parse_sse_events_content_and_usage() {
+        let line = r#"data: {"choices":[{"delta":{"content":"Hi"}}],"usage":{"prompt_tokens":1,"completion_tokens":1,"total_tokens":2}}"#;
+        let events = parse_sse_events(line);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], StreamEvent::Text("Hi".to_string()));
+        assert!(matches!(events[1], StreamEvent::Usage(_)));
+    }
+
+    #[test]
+    fn test_parse_sse_events_null_usage() {
+        let line = r#"data: {"choices":[{"delta":{"content":"Hi"}}],"usage":null}"#;
+        assert_eq!(
+            parse_sse_events(line),
+            vec![StreamEvent::Text("Hi".to_string())]
+        );
     }
 }
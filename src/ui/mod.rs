@@ -4,7 +4,7 @@ use inquire::InquireError;
 mod spinner;
 mod theme;
 
-pub use spinner::Spinner;
+pub use spinner::{MultiSpinner, Spinner};
 pub use theme::Style;
 
 /// Check if the inquire error is a user cancellation/interruption.
@@ -1,6 +1,9 @@
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use std::time::Duration;
 
+/// Spinner tick characters shared by all spinners.
+const TICK_STRINGS: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
 /// A terminal spinner for indicating progress.
 ///
 /// Automatically clears itself when dropped (RAII pattern).
@@ -16,7 +19,7 @@ impl Spinner {
         // unwrap is safe: template string is a compile-time constant
         progress_bar.set_style(
             ProgressStyle::default_spinner()
-                .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
+                .tick_strings(TICK_STRINGS)
                 .template("{spinner} {msg}")
                 .unwrap(),
         );
@@ -37,3 +40,64 @@ impl Drop for Spinner {
         self.progress_bar.finish_and_clear();
     }
 }
+
+/// A group of spinners, one line per concurrent task.
+///
+/// Each line is labeled and updated independently; all lines are cleared
+/// when the group is dropped.
+pub struct MultiSpinner {
+    _multi: MultiProgress,
+    lines: Vec<(String, ProgressBar)>,
+}
+
+impl MultiSpinner {
+    /// Creates and starts one spinner line per label.
+    #[allow(clippy::unwrap_used)]
+    pub fn new(labels: &[String], message: &str) -> Self {
+        let multi = MultiProgress::new();
+        // unwrap is safe: template string is a compile-time constant
+        let style = ProgressStyle::default_spinner()
+            .tick_strings(TICK_STRINGS)
+            .template("{spinner} {prefix} {msg}")
+            .unwrap();
+
+        let lines = labels
+            .iter()
+            .map(|label| {
+                let bar = multi.add(ProgressBar::new_spinner());
+                bar.set_style(style.clone());
+                bar.set_prefix(label.clone());
+                bar.set_message(message.to_string());
+                bar.enable_steady_tick(Duration::from_millis(80));
+                (label.clone(), bar)
+            })
+            .collect();
+
+        Self {
+            _multi: multi,
+            lines,
+        }
+    }
+
+    /// Updates the message of the line with the given label.
+    pub fn set_message(&self, label: &str, message: String) {
+        if let Some((_, bar)) = self.lines.iter().find(|(l, _)| l == label) {
+            bar.set_message(message);
+        }
+    }
+
+    /// Stops the line with the given label, leaving a final message.
+    pub fn finish(&self, label: &str, message: String) {
+        if let Some((_, bar)) = self.lines.iter().find(|(l, _)| l == label) {
+            bar.finish_with_message(message);
+        }
+    }
+}
+
+impl Drop for MultiSpinner {
+    fn drop(&mut self) {
+        for (_, bar) in &self.lines {
+            bar.finish_and_clear();
+        }
+    }
+}
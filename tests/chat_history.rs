@@ -0,0 +1,34 @@
+#![allow(clippy::unwrap_used)]
+//! `tl chat --resume` tests.
+
+mod common;
+
+use common::tl_with_endpoint;
+use predicates::prelude::*;
+
+const ENDPOINT: &str = "http://127.0.0.1:9";
+
+#[test]
+fn test_resume_without_saved_session() {
+    let (mut cmd, _temp_dir) = tl_with_endpoint(ENDPOINT);
+
+    cmd.args(["chat", "--resume"])
+        .assert()
+        .failure()
+        .code(exitcode::USAGE)
+        .stderr(predicate::str::contains("No chat session to resume"));
+}
+
+#[test]
+fn test_resume_rejects_invalid_history() {
+    let (mut cmd, temp_dir) = tl_with_endpoint(ENDPOINT);
+    let history_dir = temp_dir.path().join("cache").join("tl").join("chat");
+    std::fs::create_dir_all(&history_dir).unwrap();
+    std::fs::write(history_dir.join("session-1.json"), "not json").unwrap();
+
+    cmd.args(["chat", "--resume"])
+        .assert()
+        .failure()
+        .code(exitcode::NOINPUT)
+        .stderr(predicate::str::contains("Invalid chat history"));
+}
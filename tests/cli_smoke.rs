@@ -125,7 +125,8 @@ fn test_chat_help() {
         .stdout(predicate::str::contains("--to"))
         .stdout(predicate::str::contains("--style"))
         .stdout(predicate::str::contains("--provider"))
-        .stdout(predicate::str::contains("--model"));
+        .stdout(predicate::str::contains("--model"))
+        .stdout(predicate::str::contains("--resume"));
 }
 
 #[test]
@@ -195,3 +196,108 @@ fn test_exit_code_file_not_found() {
         .assert()
         .code(exitcode::NOINPUT);
 }
+
+#[test]
+fn test_exit_code_missing_config() {
+    // No provider configured should return exit code 78 (CONFIG - sysexits.h)
+    let temp_dir = tempfile::tempdir().unwrap();
+    tl().env("XDG_CONFIG_HOME", temp_dir.path())
+        .write_stdin("hello")
+        .assert()
+        .code(exitcode::CONFIG);
+}
+
+#[test]
+fn test_exit_code_write_without_file() {
+    // --write without a file should return exit code 64 (USAGE - sysexits.h)
+    let (mut cmd, _temp_dir) = tl_with_config();
+    cmd.arg("--write")
+        .write_stdin("hello")
+        .assert()
+        .code(exitcode::USAGE);
+}
+
+#[test]
+fn test_exit_code_empty_input() {
+    // Empty input should return exit code 66 (NOINPUT - sysexits.h)
+    let (mut cmd, _temp_dir) = tl_with_config();
+    cmd.write_stdin("").assert().code(exitcode::NOINPUT);
+}
+
+#[test]
+fn test_exit_code_unknown_cli_style() {
+    // Unknown --style should return exit code 64 (USAGE - sysexits.h)
+    let (mut cmd, _temp_dir) = tl_with_config();
+    cmd.args(["--style", "nonexistent_style_xyz"])
+        .write_stdin("hello")
+        .assert()
+        .code(exitcode::USAGE);
+}
+
+#[test]
+fn test_output_json_languages() {
+    let output = tl()
+        .args(["--output", "json", "languages"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let languages = json["languages"].as_array().unwrap();
+    assert!(
+        languages
+            .iter()
+            .any(|l| l["code"] == "ja" && l["name"] == "Japanese")
+    );
+}
+
+#[test]
+fn test_output_json_styles() {
+    let output = tl().args(["--output", "json", "styles"]).output().unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let styles = json["styles"].as_array().unwrap();
+    assert!(
+        styles
+            .iter()
+            .any(|s| s["name"] == "casual" && s["kind"] == "preset")
+    );
+}
+
+#[test]
+fn test_output_json_providers() {
+    let (mut cmd, _temp_dir) = tl_with_config();
+    let output = cmd
+        .args(["--output", "json", "providers"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let providers = json["providers"].as_array().unwrap();
+    assert_eq!(providers.len(), 1);
+    assert_eq!(providers[0]["name"], "test");
+    assert_eq!(providers[0]["default"], true);
+}
+
+#[test]
+fn test_output_json_error_on_stderr() {
+    let output = tl()
+        .args(["--output", "json", "--to", "invalid_xyz"])
+        .write_stdin("test")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(exitcode::USAGE));
+    assert!(output.stdout.is_empty());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(json["error"]["code"], "usage");
+    assert_eq!(json["error"]["exit_code"], exitcode::USAGE);
+    assert!(
+        json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Invalid language code")
+    );
+}
@@ -0,0 +1,171 @@
+//! Shared helpers for integration tests.
+
+#![allow(dead_code, clippy::unwrap_used)]
+
+use assert_cmd::Command;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Creates a `tl` command with isolated config and cache directories.
+///
+/// The config has a single provider `test` pointing at `endpoint`, with
+/// `test-model` and target language `ja` as defaults.
+#[allow(deprecated)]
+pub fn tl_with_endpoint(endpoint: &str) -> (Command, tempfile::TempDir) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config_dir = temp_dir.path().join("config").join("tl");
+    std::fs::create_dir_all(&config_dir).unwrap();
+
+    std::fs::write(
+        config_dir.join("config.toml"),
+        format!(
+            r#"
+[tl]
+provider = "test"
+model = "test-model"
+to = "ja"
+
+[providers.test]
+endpoint = "{endpoint}"
+models = ["test-model"]
+"#
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("tl").unwrap();
+    cmd.env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_CACHE_HOME", temp_dir.path().join("cache"));
+    (cmd, temp_dir)
+}
+
+/// A minimal OpenAI-compatible upstream that streams canned responses.
+///
+/// Each request to `/v1/chat/completions` is answered with an SSE stream
+/// containing `chunks` as content deltas, a usage event, and `[DONE]`.
+/// Request bodies are recorded for assertions.
+pub struct MockUpstream {
+    pub endpoint: String,
+    requests: Arc<Mutex<Vec<serde_json::Value>>>,
+}
+
+impl MockUpstream {
+    /// Starts a mock upstream on a random local port.
+    pub fn start(chunks: &[&str]) -> Self {
+        let chunks: Vec<String> = chunks.iter().map(|s| (*s).to_string()).collect();
+        Self::start_with(move |_| MockResponse::Stream(chunks.clone()))
+    }
+
+    /// Starts a mock upstream that answers every request with the given HTTP status.
+    pub fn start_with_status(status: u16, body: &str) -> Self {
+        let body = body.to_string();
+        Self::start_with(move |_| MockResponse::Status(status, body.clone()))
+    }
+
+    /// Starts a mock upstream whose response depends on the request body.
+    pub fn start_with<F>(respond: F) -> Self
+    where
+        F: Fn(&serde_json::Value) -> MockResponse + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&requests);
+        let respond = Arc::new(respond);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let recorded = Arc::clone(&recorded);
+                let respond = Arc::clone(&respond);
+                thread::spawn(move || handle(stream, &recorded, respond.as_ref()));
+            }
+        });
+
+        Self { endpoint, requests }
+    }
+
+    /// Returns the JSON bodies of all requests received so far.
+    pub fn requests(&self) -> Vec<serde_json::Value> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// How the mock upstream answers a request.
+pub enum MockResponse {
+    /// Stream these content chunks as SSE.
+    Stream(Vec<String>),
+    /// Reply with a non-streaming JSON body.
+    Json(serde_json::Value),
+    /// Reply with an HTTP error status and body.
+    Status(u16, String),
+}
+
+fn handle(
+    mut stream: TcpStream,
+    recorded: &Mutex<Vec<serde_json::Value>>,
+    respond: &(dyn Fn(&serde_json::Value) -> MockResponse + Send + Sync),
+) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            return;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    let request: serde_json::Value = serde_json::from_slice(&body).unwrap_or_default();
+    recorded.lock().unwrap().push(request.clone());
+
+    let response = match respond(&request) {
+        MockResponse::Stream(chunks) => {
+            let mut body = String::new();
+            for chunk in &chunks {
+                let event = serde_json::json!({ "choices": [{ "delta": { "content": chunk } }] });
+                let _ = write!(body, "data: {event}\n\n");
+            }
+            let usage = serde_json::json!({
+                "choices": [],
+                "usage": {
+                    "prompt_tokens": 10,
+                    "completion_tokens": chunks.len(),
+                    "total_tokens": 10 + chunks.len(),
+                }
+            });
+            let _ = write!(body, "data: {usage}\n\ndata: [DONE]\n\n");
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        }
+        MockResponse::Json(body) => {
+            let body = body.to_string();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        }
+        MockResponse::Status(status, body) => format!(
+            "HTTP/1.1 {status} Error\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        ),
+    };
+
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+}
@@ -0,0 +1,141 @@
+#![allow(clippy::unwrap_used)]
+//! `tl compare` tests.
+
+mod common;
+
+use common::{MockResponse, MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+
+/// Starts an upstream that answers `<model> says hello`, failing for `fail`.
+fn upstream_failing_for(fail: Option<&'static str>) -> MockUpstream {
+    MockUpstream::start_with(move |request| {
+        let model = request["model"].as_str().unwrap().to_string();
+        if Some(model.as_str()) == fail {
+            MockResponse::Status(500, r#"{"error":"boom"}"#.to_string())
+        } else {
+            MockResponse::Stream(vec![format!("{model} says hello")])
+        }
+    })
+}
+
+#[test]
+fn test_compare_sends_same_request_to_each_model() {
+    let upstream = upstream_failing_for(None);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let output = cmd
+        .args([
+            "compare",
+            "-m",
+            "test-model,other-model",
+            "--output",
+            "json",
+        ])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = report["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["model"], "test-model");
+    assert_eq!(results[0]["translation"], "test-model says hello");
+    assert_eq!(results[1]["provider"], "test");
+    assert_eq!(results[1]["translation"], "other-model says hello");
+    assert_eq!(results[1]["usage"]["total_tokens"], 11);
+
+    // Only the model differs between the requests
+    let mut requests = upstream.requests();
+    assert_eq!(requests.len(), 2);
+    for request in &mut requests {
+        request.as_object_mut().unwrap().remove("model");
+    }
+    assert_eq!(requests[0], requests[1]);
+}
+
+#[test]
+fn test_compare_follows_configured_pair() {
+    let upstream = upstream_failing_for(None);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let path = temp_dir.path().join("config/tl/config.toml");
+    let config = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(
+        &path,
+        config.replace("to = \"ja\"", "pair = [\"ja\", \"en\"]"),
+    )
+    .unwrap();
+
+    // Japanese input goes to the other language of the pair
+    let output = cmd
+        .args([
+            "compare",
+            "-m",
+            "test-model,other-model",
+            "--output",
+            "json",
+        ])
+        .write_stdin("これは日本語の文章です。")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["source_language"], "ja");
+    assert_eq!(report["target_language"], "en");
+    for request in upstream.requests() {
+        let system = request["messages"][0]["content"].as_str().unwrap();
+        assert!(system.contains("to English"), "{system}");
+    }
+}
+
+#[test]
+fn test_compare_prints_side_by_side_and_diff() {
+    let upstream = upstream_failing_for(None);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.args(["--no-color", "compare", "-m", "test-model,other-model"])
+        .write_stdin("Hello")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?m)^test/test-model +│ test/other-model$").unwrap());
+
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.args([
+        "--no-color",
+        "compare",
+        "-m",
+        "test-model,other-model",
+        "--diff",
+    ])
+    .write_stdin("Hello")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("=== test/other-model ("))
+    .stdout(predicate::str::contains(
+        "[-test-]{+other+}-model says hello",
+    ));
+}
+
+#[test]
+fn test_compare_reports_failures_after_printing_results() {
+    let upstream = upstream_failing_for(Some("other-model"));
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.args(["--no-color", "compare", "-m", "test-model,other-model"])
+        .write_stdin("Hello")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("test-model says hello"))
+        .stderr(predicate::str::contains("1 of 2 translations failed"));
+}
+
+#[test]
+fn test_compare_needs_two_models() {
+    let upstream = upstream_failing_for(None);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.args(["compare", "-m", "test-model"])
+        .write_stdin("Hello")
+        .assert()
+        .code(64)
+        .stderr(predicate::str::contains("at least two"));
+    assert!(upstream.requests().is_empty());
+}
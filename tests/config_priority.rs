@@ -21,6 +21,7 @@ fn make_config_with_defaults() -> ConfigFile {
             api_key: Some("test_key".to_string()),
             api_key_env: None,
             models: vec!["test_model".to_string()],
+            prompt_template: None,
         },
     );
 
@@ -30,6 +31,9 @@ fn make_config_with_defaults() -> ConfigFile {
         CustomStyle {
             description: "Test custom style".to_string(),
             prompt: "Test prompt".to_string(),
+            prompt_template: None,
+            extends: None,
+            examples: Vec::new(),
         },
     );
 
@@ -39,9 +43,17 @@ fn make_config_with_defaults() -> ConfigFile {
             model: Some("config_model".to_string()),
             to: Some("ja".to_string()),
             style: Some("formal".to_string()),
+            pair: None,
+            native_names: None,
+            prompt_template: None,
+            chat_history: None,
+            review_provider: None,
+            review_model: None,
         },
         providers,
         styles,
+        aliases: HashMap::new(),
+        language_prompts: HashMap::new(),
     }
 }
 
@@ -53,6 +65,7 @@ fn test_cli_style_overrides_config_style() {
         provider: None,
         model: None,
         style: Some("casual".to_string()), // CLI specifies casual
+        pair: None,
     };
 
     let resolved = resolve_config(&options, &config).unwrap();
@@ -71,6 +84,7 @@ fn test_cli_style_can_use_custom_style() {
         provider: None,
         model: None,
         style: Some("custom_style".to_string()), // CLI specifies custom style
+        pair: None,
     };
 
     let resolved = resolve_config(&options, &config).unwrap();
@@ -87,6 +101,7 @@ fn test_config_style_used_when_cli_not_specified() {
         provider: None,
         model: None,
         style: None, // CLI doesn't specify style
+        pair: None,
     };
 
     let resolved = resolve_config(&options, &config).unwrap();
@@ -104,6 +119,7 @@ fn test_cli_to_overrides_config_to() {
         provider: None,
         model: None,
         style: None,
+        pair: None,
     };
 
     let resolved = resolve_config(&options, &config).unwrap();
@@ -120,6 +136,7 @@ fn test_cli_model_overrides_config_model() {
         provider: None,
         model: Some("cli_model".to_string()), // CLI specifies model
         style: None,
+        pair: None,
     };
 
     let resolved = resolve_config(&options, &config).unwrap();
@@ -138,6 +155,7 @@ fn test_cli_provider_overrides_config_provider() {
             api_key: Some("other_key".to_string()),
             api_key_env: None,
             models: vec!["other_model".to_string()],
+            prompt_template: None,
         },
     );
 
@@ -146,6 +164,7 @@ fn test_cli_provider_overrides_config_provider() {
         provider: Some("other_provider".to_string()), // CLI specifies different provider
         model: None,
         style: None,
+        pair: None,
     };
 
     let resolved = resolve_config(&options, &config).unwrap();
@@ -163,6 +182,7 @@ fn test_invalid_style_returns_error() {
         provider: None,
         model: None,
         style: Some("nonexistent_style".to_string()),
+        pair: None,
     };
 
     let result = resolve_config(&options, &config);
@@ -179,6 +199,7 @@ fn test_all_cli_options_override_config() {
             api_key: Some("cli_key".to_string()),
             api_key_env: None,
             models: vec!["cli_model".to_string()],
+            prompt_template: None,
         },
     );
 
@@ -187,6 +208,7 @@ fn test_all_cli_options_override_config() {
         provider: Some("cli_provider".to_string()),
         model: Some("cli_specified_model".to_string()),
         style: Some("literal".to_string()),
+        pair: None,
     };
 
     let resolved = resolve_config(&options, &config).unwrap();
@@ -0,0 +1,324 @@
+#![allow(clippy::unwrap_used)]
+//! Incremental translation (`--incremental`) tests.
+
+mod common;
+
+use assert_cmd::Command;
+use common::{MockResponse, MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+use std::path::Path;
+
+/// Starts an upstream that "translates" by prefixing the input with `[ja]`.
+fn upstream() -> MockUpstream {
+    MockUpstream::start_with(|request| {
+        let messages = request["messages"].as_array().unwrap();
+        let input = messages.last().unwrap()["content"].as_str().unwrap();
+        MockResponse::Stream(vec![format!("[ja] {input}")])
+    })
+}
+
+/// Returns a `tl` command sharing the config and cache of `temp_dir`.
+fn tl_in(endpoint: &str, temp_dir: &Path) -> Command {
+    let (mut cmd, _unused) = tl_with_endpoint(endpoint);
+    cmd.env("XDG_CONFIG_HOME", temp_dir.join("config"))
+        .env("XDG_CACHE_HOME", temp_dir.join("cache"));
+    cmd
+}
+
+#[test]
+fn test_incremental_translates_only_changed_paragraphs() {
+    let upstream = upstream();
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let source = temp_dir.path().join("doc.md");
+    let output = temp_dir.path().join("doc.ja.md");
+    std::fs::write(&source, "# Title\n\nFirst.\n\nSecond.\n").unwrap();
+
+    cmd.args(["--no-color", "--incremental"])
+        .arg(&source)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("(3 of 3 paragraphs translated)"));
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "[ja] # Title\n\n[ja] First.\n\n[ja] Second.\n"
+    );
+    assert!(temp_dir.path().join("doc.ja.md.tl-lock").exists());
+    assert_eq!(upstream.requests().len(), 3);
+
+    // A reviewer fixes one paragraph; the source changes another and grows
+    std::fs::write(&output, "[ja] # Title\n\n最初（手直し）\n\n[ja] Second.\n").unwrap();
+    std::fs::write(&source, "# Title\n\nFirst.\n\nSecond, revised.\n\nThird.\n").unwrap();
+
+    let out = tl_in(&upstream.endpoint, temp_dir.path())
+        .args(["--incremental", "--output", "json"])
+        .arg(&source)
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["files"][0]["target_language"], "ja");
+    assert_eq!(report["files"][0]["paragraphs"], 4);
+    assert_eq!(report["files"][0]["translated"], 2);
+    assert_eq!(report["files"][0]["kept"], 2);
+
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "[ja] # Title\n\n最初（手直し）\n\n[ja] Second, revised.\n\n[ja] Third.\n"
+    );
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 5);
+    let mut sent: Vec<_> = requests[3..]
+        .iter()
+        .map(|request| request["messages"][1]["content"].as_str().unwrap())
+        .collect();
+    sent.sort_unstable();
+    assert_eq!(sent, ["Second, revised.", "Third."]);
+}
+
+#[test]
+fn test_incremental_up_to_date_makes_no_requests() {
+    let upstream = upstream();
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let source = temp_dir.path().join("doc.md");
+    std::fs::write(&source, "One.\n\nTwo.\n").unwrap();
+    cmd.args(["--incremental", "-t", "ja,fr"])
+        .arg(&source)
+        .assert()
+        .success();
+    assert_eq!(upstream.requests().len(), 4);
+    assert!(temp_dir.path().join("doc.fr.md").exists());
+
+    tl_in(&upstream.endpoint, temp_dir.path())
+        .args(["--no-color", "--incremental", "-t", "ja,fr"])
+        .arg(&source)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("doc.ja.md is up to date"));
+    assert_eq!(upstream.requests().len(), 4);
+}
+
+#[test]
+fn test_incremental_usage_errors() {
+    let upstream = upstream();
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.arg("--incremental")
+        .write_stdin("Hello")
+        .assert()
+        .code(exitcode::USAGE)
+        .stderr(predicate::str::contains("requires a file"));
+
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let source = temp_dir.path().join("doc.md");
+    std::fs::write(&source, "Hello").unwrap();
+    cmd.args(["--incremental", "--verify"])
+        .arg(&source)
+        .assert()
+        .code(exitcode::USAGE)
+        .stderr(predicate::str::contains("cannot be combined with --verify"));
+}
+
+#[test]
+fn test_incremental_records_edits_as_preferred_translations() {
+    let upstream = upstream();
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let source = temp_dir.path().join("doc.md");
+    let output = temp_dir.path().join("doc.ja.md");
+    std::fs::write(&source, "One.\n\nTwo.\n").unwrap();
+    cmd.arg("--incremental").arg(&source).assert().success();
+    assert_eq!(upstream.requests().len(), 2);
+
+    std::fs::write(&output, "[ja] One.\n\n二（手直し）\n").unwrap();
+    std::fs::write(&source, "One, revised.\n\nTwo.\n").unwrap();
+    tl_in(&upstream.endpoint, temp_dir.path())
+        .args(["--no-color", "--incremental"])
+        .arg(&source)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "(1 of 2 paragraphs translated, 1 edited paragraph kept)",
+        ));
+    assert_eq!(upstream.requests().len(), 3);
+
+    // Regenerating from scratch reuses the edit instead of translating again
+    std::fs::remove_file(&output).unwrap();
+    std::fs::remove_file(temp_dir.path().join("doc.ja.md.tl-lock")).unwrap();
+    tl_in(&upstream.endpoint, temp_dir.path())
+        .arg("--incremental")
+        .arg(&source)
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "[ja] One, revised.\n\n二（手直し）\n"
+    );
+    assert_eq!(upstream.requests().len(), 3);
+
+    // Edits are only preferred with the same style
+    std::fs::remove_file(&output).unwrap();
+    std::fs::remove_file(temp_dir.path().join("doc.ja.md.tl-lock")).unwrap();
+    tl_in(&upstream.endpoint, temp_dir.path())
+        .args(["--incremental", "-s", "casual"])
+        .arg(&source)
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "[ja] One, revised.\n\n[ja] Two.\n"
+    );
+    assert_eq!(upstream.requests().len(), 5);
+}
+
+#[test]
+fn test_incremental_refuses_to_overwrite_file_without_lock() {
+    let upstream = upstream();
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let source = temp_dir.path().join("doc.md");
+    let output = temp_dir.path().join("doc.ja.md");
+    std::fs::write(&source, "One.\n").unwrap();
+    std::fs::write(&output, "手で訳した。\n").unwrap();
+
+    cmd.arg("--incremental")
+        .arg(&source)
+        .assert()
+        .code(exitcode::NOINPUT)
+        .stderr(predicate::str::contains(
+            "already exists but has no lock file",
+        ));
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "手で訳した。\n");
+
+    // A lock file for another language does not count
+    std::fs::write(
+        temp_dir.path().join("doc.ja.md.tl-lock"),
+        r#"{"version": 1, "target_language": "en", "segments": []}"#,
+    )
+    .unwrap();
+    tl_in(&upstream.endpoint, temp_dir.path())
+        .arg("--incremental")
+        .arg(&source)
+        .assert()
+        .code(exitcode::NOINPUT)
+        .stderr(predicate::str::contains(
+            "already exists but has a lock file for en rather than ja",
+        ));
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), "手で訳した。\n");
+    assert!(upstream.requests().is_empty());
+}
+
+#[test]
+fn test_incremental_retranslates_when_settings_change() {
+    let upstream = upstream();
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let source = temp_dir.path().join("doc.md");
+    let output = temp_dir.path().join("doc.ja.md");
+    std::fs::write(&source, "One.\n\nTwo.\n").unwrap();
+    cmd.arg("--incremental").arg(&source).assert().success();
+    assert_eq!(upstream.requests().len(), 2);
+
+    // Another style re-translates everything except the hand edit
+    std::fs::write(&output, "[ja] One.\n\n二（手直し）\n").unwrap();
+    tl_in(&upstream.endpoint, temp_dir.path())
+        .args(["--no-color", "--incremental", "-s", "casual"])
+        .arg(&source)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("changed since"));
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[2]["messages"][1]["content"], "One.");
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "[ja] One.\n\n二（手直し）\n"
+    );
+
+    // The new settings are recorded, so the next run is up to date
+    tl_in(&upstream.endpoint, temp_dir.path())
+        .args(["--incremental", "-s", "casual"])
+        .arg(&source)
+        .assert()
+        .success();
+    assert_eq!(upstream.requests().len(), 3);
+}
+
+#[test]
+fn test_incremental_keeps_paragraphs_split_by_hand() {
+    let upstream = upstream();
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let source = temp_dir.path().join("doc.md");
+    let output = temp_dir.path().join("doc.ja.md");
+    std::fs::write(&source, "One.\n\nTwo.\n\nThree.\n").unwrap();
+    cmd.arg("--incremental").arg(&source).assert().success();
+    assert_eq!(upstream.requests().len(), 3);
+
+    // The reviewer splits one paragraph in two and fixes another
+    let reviewed = "[ja] One.\n\n二の前半。\n\n二の後半。\n\n三（手直し）\n";
+    std::fs::write(&output, reviewed).unwrap();
+    std::fs::write(&source, "One, revised.\n\nTwo.\n\nThree.\n").unwrap();
+    let out = tl_in(&upstream.endpoint, temp_dir.path())
+        .args(["--incremental", "--output", "json"])
+        .arg(&source)
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["files"][0]["translated"], 1);
+    assert_eq!(report["files"][0]["edited"], 2);
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "[ja] One, revised.\n\n二の前半。\n\n二の後半。\n\n三（手直し）\n"
+    );
+
+    // Both edits were recorded, including the split paragraph
+    std::fs::remove_file(&output).unwrap();
+    std::fs::remove_file(temp_dir.path().join("doc.ja.md.tl-lock")).unwrap();
+    tl_in(&upstream.endpoint, temp_dir.path())
+        .arg("--incremental")
+        .arg(&source)
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "[ja] One, revised.\n\n二の前半。\n\n二の後半。\n\n三（手直し）\n"
+    );
+    assert_eq!(upstream.requests().len(), 4);
+}
+
+#[test]
+fn test_incremental_fix_placeholders_retries_broken_paragraphs() {
+    let upstream = MockUpstream::start_with(|request| {
+        let input = request["messages"][1]["content"].as_str().unwrap();
+        let prompt = request["messages"][0]["content"].as_str().unwrap();
+        let text = match input {
+            "Hello, {name}" if prompt.contains("broke placeholders") => "こんにちは、{name}",
+            "Hello, {name}" => "こんにちは",
+            _ => "タイトル",
+        };
+        MockResponse::Stream(vec![text.to_string()])
+    });
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let source = temp_dir.path().join("doc.md");
+    std::fs::write(&source, "Title\n\nHello, {name}\n").unwrap();
+
+    cmd.args(["--incremental", "--fix-placeholders", "--strict"])
+        .arg(&source)
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("doc.ja.md")).unwrap(),
+        "タイトル\n\nこんにちは、{name}\n"
+    );
+
+    // Only the broken paragraph is sent again, with the corrective prompt
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 3);
+    let retries: Vec<_> = requests
+        .iter()
+        .filter(|request| {
+            request["messages"][0]["content"]
+                .as_str()
+                .unwrap()
+                .contains("(missing {name})")
+        })
+        .collect();
+    assert_eq!(retries.len(), 1);
+    assert_eq!(retries[0]["messages"][1]["content"], "Hello, {name}");
+}
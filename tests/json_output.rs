@@ -0,0 +1,136 @@
+#![allow(clippy::unwrap_used)]
+//! `--output json` contract tests for the translate path.
+//!
+//! These tests run the binary against a mock OpenAI-compatible upstream
+//! and check the shape of the JSON printed on stdout and stderr.
+
+mod common;
+
+use common::{MockResponse, MockUpstream, tl_with_endpoint};
+
+#[test]
+fn test_translate_json_output() {
+    let upstream = MockUpstream::start(&["こんにちは", "、世界"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let output = cmd
+        .args(["--output", "json", "--style", "casual"])
+        .write_stdin("Hello, world")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["translation"], "こんにちは、世界");
+    assert_eq!(json["target_language"], "ja");
+    assert_eq!(json["provider"], "test");
+    assert_eq!(json["model"], "test-model");
+    assert_eq!(json["style"], "casual");
+    assert_eq!(json["cached"], false);
+    assert!(json["duration_ms"].is_u64());
+    assert_eq!(json["usage"]["completion_tokens"], 2);
+}
+
+#[test]
+fn test_translate_json_output_cached() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut first, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    first
+        .args(["--output", "json"])
+        .write_stdin("Hello")
+        .assert()
+        .success();
+
+    // Reuse the same config and cache directories for the second run
+    let (mut second, _unused) = tl_with_endpoint(&upstream.endpoint);
+    second
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_CACHE_HOME", temp_dir.path().join("cache"));
+    let output = second
+        .args(["--output", "json"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["translation"], "こんにちは");
+    assert_eq!(json["cached"], true);
+    assert!(json["usage"].is_null());
+    assert_eq!(upstream.requests().len(), 1);
+}
+
+#[test]
+fn test_translate_json_error_from_upstream() {
+    let upstream = MockUpstream::start_with_status(429, r#"{"error":"rate limited"}"#);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let output = cmd
+        .args(["--output", "json"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(exitcode::TEMPFAIL));
+    assert!(output.stdout.is_empty());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(json["error"]["code"], "rate_limit");
+    assert_eq!(json["error"]["status"], 429);
+}
+
+#[test]
+fn test_translate_requests_usage() {
+    let upstream = MockUpstream::start(&["ok"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.write_stdin("Hello").assert().success();
+
+    let requests = upstream.requests();
+    assert_eq!(requests[0]["stream_options"]["include_usage"], true);
+}
+
+#[test]
+fn test_translate_retries_without_usage_when_rejected() {
+    let upstream = MockUpstream::start_with(|request| {
+        if request.get("stream_options").is_some() {
+            MockResponse::Status(400, "Unrecognized field: stream_options".to_string())
+        } else {
+            MockResponse::Stream(vec!["こんにちは".to_string()])
+        }
+    });
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let output = cmd
+        .args(["--output", "json"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["translation"], "こんにちは");
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].get("stream_options").is_none());
+}
+
+#[test]
+fn test_translate_does_not_retry_unrelated_bad_request() {
+    let upstream = MockUpstream::start_with(|_| {
+        MockResponse::Status(400, "model 'test-model' not found".to_string())
+    });
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let output = cmd
+        .args(["--output", "json"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert!(
+        json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("not found")
+    );
+    assert_eq!(upstream.requests().len(), 1);
+}
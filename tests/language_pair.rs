@@ -0,0 +1,128 @@
+#![allow(clippy::unwrap_used)]
+//! Bidirectional language pair (`--pair` and `pair` in config) tests.
+
+mod common;
+
+use common::{MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+
+/// Replaces the default target language in the test config with a pair.
+fn use_config_pair(temp_dir: &tempfile::TempDir, pair: &str) {
+    let path = temp_dir
+        .path()
+        .join("config")
+        .join("tl")
+        .join("config.toml");
+    let config = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(
+        &path,
+        config.replace("to = \"ja\"", &format!("pair = {pair}")),
+    )
+    .unwrap();
+}
+
+fn translate_json(cmd: &mut assert_cmd::Command, input: &str) -> serde_json::Value {
+    let output = cmd
+        .args(["--output", "json"])
+        .write_stdin(input)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn test_pair_swaps_primary_language_to_secondary() {
+    let upstream = MockUpstream::start(&["Hello"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let json = translate_json(cmd.args(["--pair", "ja,en"]), "こんにちは、元気ですか？");
+    assert_eq!(json["source_language"], "ja");
+    assert_eq!(json["target_language"], "en");
+    assert_eq!(json["translation"], "Hello");
+}
+
+#[test]
+fn test_pair_translates_other_languages_to_primary() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let json = translate_json(
+        cmd.args(["--pair", "ja,en"]),
+        "Le chat est sur la table et il dort dans le salon.",
+    );
+    assert_eq!(json["source_language"], "fr");
+    assert_eq!(json["target_language"], "ja");
+}
+
+#[test]
+fn test_config_pair_is_used_without_flags() {
+    let upstream = MockUpstream::start(&["Good morning"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    use_config_pair(&temp_dir, r#"["ja", "en"]"#);
+
+    let json = translate_json(&mut cmd, "おはようございます");
+    assert_eq!(json["target_language"], "en");
+}
+
+#[test]
+fn test_to_overrides_config_pair() {
+    let upstream = MockUpstream::start(&["Bonjour"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    use_config_pair(&temp_dir, r#"["ja", "en"]"#);
+
+    let json = translate_json(cmd.args(["--to", "fr"]), "おはようございます");
+    assert_eq!(json["target_language"], "fr");
+}
+
+#[test]
+fn test_invalid_pair_is_usage_error() {
+    let upstream = MockUpstream::start(&["unused"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["--pair", "ja"])
+        .write_stdin("Hello")
+        .assert()
+        .code(exitcode::USAGE)
+        .stderr(predicate::str::contains("Invalid language pair"));
+}
+
+#[test]
+fn test_invalid_config_pair_is_config_error() {
+    let upstream = MockUpstream::start(&["unused"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    use_config_pair(&temp_dir, r#"["ja", "ja"]"#);
+
+    cmd.write_stdin("Hello")
+        .assert()
+        .code(exitcode::CONFIG)
+        .stderr(predicate::str::contains("Invalid 'pair' in config file"));
+}
+
+#[test]
+fn test_pair_with_regional_tag_matches_detected_language() {
+    let upstream = MockUpstream::start(&["I don't know"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    // Detection only yields "pt", which still matches pt-BR
+    let json = translate_json(
+        cmd.args(["--pair", "pt-BR,en"]),
+        "Eu não sei o que você quer para o jantar.",
+    );
+    assert_eq!(json["source_language"], "pt");
+    assert_eq!(json["target_language"], "en");
+}
+
+#[test]
+fn test_explicit_regional_source_in_target_language_is_echoed() {
+    let upstream = MockUpstream::start(&["unused"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["--from", "pt-BR", "-t", "pt"])
+        .write_stdin("Olá")
+        .assert()
+        .success()
+        .stdout("Olá")
+        .stderr(predicate::str::contains("skipping translation"));
+    assert!(upstream.requests().is_empty());
+}
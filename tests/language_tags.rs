@@ -0,0 +1,101 @@
+#![allow(clippy::unwrap_used)]
+//! BCP 47 language tag and alias tests.
+
+mod common;
+
+use common::{MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+
+fn add_aliases(temp_dir: &tempfile::TempDir, aliases: &str) {
+    let path = temp_dir
+        .path()
+        .join("config")
+        .join("tl")
+        .join("config.toml");
+    let mut config = std::fs::read_to_string(&path).unwrap();
+    config.push_str("\n[aliases]\n");
+    config.push_str(aliases);
+    std::fs::write(&path, config).unwrap();
+}
+
+#[test]
+fn test_regional_tag_is_canonicalized_and_named_in_prompt() {
+    let upstream = MockUpstream::start(&["Olá"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let output = cmd
+        .args(["--output", "json", "--to", "pt-br"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["target_language"], "pt-BR");
+    let prompt = upstream.requests()[0]["messages"][0]["content"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(prompt.contains("to Portuguese (Brazil)."), "{prompt}");
+}
+
+#[test]
+fn test_config_alias_resolves_to_tag() {
+    let upstream = MockUpstream::start(&["繁體"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    add_aliases(&temp_dir, "tw = \"zh-Hant-TW\"\n");
+
+    let output = cmd
+        .args(["--output", "json", "--to", "tw"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["target_language"], "zh-Hant-TW");
+}
+
+#[test]
+fn test_malformed_tag_is_usage_error() {
+    let upstream = MockUpstream::start(&["unused"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["--to", "pt-Brazil"])
+        .write_stdin("Hello")
+        .assert()
+        .code(exitcode::USAGE)
+        .stderr(predicate::str::contains(
+            "Invalid language code: 'pt-Brazil'",
+        ));
+    assert!(upstream.requests().is_empty());
+}
+
+#[test]
+fn test_native_names_and_language_prompts_from_config() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let path = temp_dir
+        .path()
+        .join("config")
+        .join("tl")
+        .join("config.toml");
+    let config = std::fs::read_to_string(&path)
+        .unwrap()
+        .replace("to = \"ja\"", "to = \"ja\"\nnative_names = true");
+    std::fs::write(
+        &path,
+        format!("{config}\n[language_prompts]\nja = \"Use polite keigo.\"\nde = \"Use du.\"\n"),
+    )
+    .unwrap();
+
+    cmd.write_stdin("Hello").assert().success();
+
+    let prompt = upstream.requests()[0]["messages"][0]["content"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(prompt.contains("to Japanese (日本語)."), "{prompt}");
+    assert!(prompt.ends_with("Use polite keigo."), "{prompt}");
+    assert!(!prompt.contains("Use du."));
+}
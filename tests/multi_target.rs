@@ -0,0 +1,144 @@
+#![allow(clippy::unwrap_used)]
+//! Multi-target translation (`-t ja,zh,ko`) tests.
+
+mod common;
+
+use common::{MockResponse, MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+
+const TARGETS: &[(&str, &str)] = &[
+    ("ja", "Japanese"),
+    ("zh", "Chinese (Simplified)"),
+    ("ko", "Korean"),
+];
+
+/// Returns the target language named in a request's system prompt.
+fn target_of(request: &serde_json::Value) -> &'static str {
+    let prompt = request["messages"][0]["content"].as_str().unwrap();
+    TARGETS
+        .iter()
+        .find(|(_, name)| prompt.contains(&format!("to {name}.")))
+        .map(|(code, _)| *code)
+        .unwrap()
+}
+
+/// Starts an upstream that answers `translated-<lang>`, failing for `fail`.
+fn upstream_failing_for(fail: Option<&'static str>) -> MockUpstream {
+    MockUpstream::start_with(move |request| {
+        let target = target_of(request);
+        if Some(target) == fail {
+            MockResponse::Status(500, r#"{"error":"boom"}"#.to_string())
+        } else {
+            MockResponse::Stream(vec![format!("translated-{target}")])
+        }
+    })
+}
+
+#[test]
+fn test_multi_target_prints_labeled_sections_in_order() {
+    let upstream = upstream_failing_for(None);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["-t", "ja,zh,ko"])
+        .write_stdin("Hello")
+        .assert()
+        .success()
+        .stdout(
+            "=== ja ===\ntranslated-ja\n\n=== zh ===\ntranslated-zh\n\n=== ko ===\ntranslated-ko\n",
+        );
+
+    assert_eq!(upstream.requests().len(), 3);
+}
+
+#[test]
+fn test_multi_target_writes_localized_files() {
+    let upstream = upstream_failing_for(None);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let file = temp_dir.path().join("notes.md");
+    std::fs::write(&file, "Hello").unwrap();
+
+    cmd.args(["-t", "ja,zh", "-w", file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("");
+
+    let read = |name: &str| std::fs::read_to_string(temp_dir.path().join(name)).unwrap();
+    assert_eq!(read("notes.md"), "Hello");
+    assert_eq!(read("notes.ja.md"), "translated-ja");
+    assert_eq!(read("notes.zh.md"), "translated-zh");
+}
+
+#[test]
+fn test_multi_target_reuses_cache_per_language() {
+    let upstream = upstream_failing_for(None);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.args(["-t", "ja"])
+        .write_stdin("Hello")
+        .assert()
+        .success();
+
+    let (mut second, _unused) = tl_with_endpoint(&upstream.endpoint);
+    second
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_CACHE_HOME", temp_dir.path().join("cache"));
+    let output = second
+        .args(["--output", "json", "-t", "ja,zh"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let translations = json["translations"].as_array().unwrap();
+    assert_eq!(translations.len(), 2);
+    assert_eq!(translations[0]["target_language"], "ja");
+    assert_eq!(translations[0]["cached"], true);
+    assert_eq!(translations[1]["target_language"], "zh");
+    assert_eq!(translations[1]["cached"], false);
+
+    // Only zh needed a second request
+    assert_eq!(upstream.requests().len(), 2);
+}
+
+#[test]
+fn test_multi_target_partial_failure() {
+    let upstream = upstream_failing_for(Some("zh"));
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["-t", "ja,zh,ko"])
+        .write_stdin("Hello")
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("translated-ja")
+                .and(predicate::str::contains("translated-ko")),
+        )
+        .stderr(
+            predicate::str::contains("Translation to zh failed")
+                .and(predicate::str::contains("1 of 3 translations failed")),
+        );
+}
+
+#[test]
+fn test_multi_target_rejects_ndjson() {
+    let upstream = upstream_failing_for(None);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["-t", "ja,zh", "--stream-format", "ndjson"])
+        .write_stdin("Hello")
+        .assert()
+        .code(exitcode::USAGE);
+    assert!(upstream.requests().is_empty());
+}
+
+#[test]
+fn test_multi_target_validates_every_language() {
+    let upstream = upstream_failing_for(None);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["-t", "ja,xx"])
+        .write_stdin("Hello")
+        .assert()
+        .code(exitcode::USAGE)
+        .stderr(predicate::str::contains("Invalid language code: 'xx'"));
+}
@@ -0,0 +1,108 @@
+#![allow(clippy::unwrap_used)]
+//! `--stream-format ndjson` protocol tests.
+//!
+//! Editor integrations rely on every stdout line being a single JSON event,
+//! so these tests parse stdout line by line and check the event sequence.
+
+mod common;
+
+use common::{MockUpstream, tl_with_endpoint};
+use serde_json::Value;
+
+fn events(stdout: &[u8]) -> Vec<Value> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn kinds(events: &[Value]) -> Vec<&str> {
+    events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_ndjson_stream_events() {
+    let upstream = MockUpstream::start(&["こんにちは", "、世界"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let output = cmd
+        .args(["--stream-format", "ndjson"])
+        .write_stdin("Hello, world")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let events = events(&output.stdout);
+    assert_eq!(kinds(&events), ["start", "delta", "delta", "done"]);
+
+    assert_eq!(events[0]["target_language"], "ja");
+    assert_eq!(events[0]["provider"], "test");
+    assert_eq!(events[0]["model"], "test-model");
+    assert_eq!(events[1]["text"], "こんにちは");
+    assert_eq!(events[2]["text"], "、世界");
+    assert_eq!(events[3]["cached"], false);
+    assert!(events[3]["duration_ms"].is_u64());
+    assert_eq!(events[3]["usage"]["completion_tokens"], 2);
+}
+
+#[test]
+fn test_ndjson_stream_cached() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut first, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    first.write_stdin("Hello").assert().success();
+
+    let (mut second, _unused) = tl_with_endpoint(&upstream.endpoint);
+    second
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_CACHE_HOME", temp_dir.path().join("cache"));
+    let output = second
+        .args(["--stream-format", "ndjson"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let events = events(&output.stdout);
+    assert_eq!(kinds(&events), ["start", "delta", "done"]);
+    assert_eq!(events[1]["text"], "こんにちは");
+    assert_eq!(events[2]["cached"], true);
+    assert!(events[2]["usage"].is_null());
+    assert_eq!(upstream.requests().len(), 1);
+}
+
+#[test]
+fn test_ndjson_stream_error_event() {
+    let upstream = MockUpstream::start_with_status(401, r#"{"error":"bad key"}"#);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let output = cmd
+        .args(["--stream-format", "ndjson"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(exitcode::NOPERM));
+
+    let events = events(&output.stdout);
+    assert_eq!(kinds(&events), ["start", "error"]);
+    assert_eq!(events[1]["error"]["code"], "auth");
+    assert_eq!(events[1]["error"]["status"], 401);
+}
+
+#[test]
+fn test_ndjson_stream_error_before_start() {
+    let (mut cmd, _temp_dir) = tl_with_endpoint("http://127.0.0.1:9");
+
+    let output = cmd
+        .args(["--stream-format", "ndjson"])
+        .write_stdin("")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(exitcode::NOINPUT));
+
+    let events = events(&output.stdout);
+    assert_eq!(kinds(&events), ["error"]);
+    assert_eq!(events[0]["error"]["code"], "input");
+}
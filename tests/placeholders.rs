@@ -0,0 +1,156 @@
+#![allow(clippy::unwrap_used)]
+//! Placeholder and markup validation tests.
+
+mod common;
+
+use common::{MockResponse, MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+
+/// Starts an upstream that drops `{name}` unless told to keep placeholders.
+fn upstream() -> MockUpstream {
+    MockUpstream::start_with(|request| {
+        let prompt = request["messages"][0]["content"].as_str().unwrap();
+        if prompt.contains("broke placeholders") {
+            MockResponse::Stream(vec!["こんにちは、".to_string(), "{name}".to_string()])
+        } else {
+            MockResponse::Stream(vec!["こんにちは".to_string()])
+        }
+    })
+}
+
+#[test]
+fn test_changed_placeholders_are_reported() {
+    let upstream = upstream();
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.args(["--no-color"])
+        .write_stdin("Hello, {name}")
+        .assert()
+        .success()
+        .stdout("こんにちは\n")
+        .stderr(predicate::str::contains(
+            "The translation changed placeholders or markup: missing {name}",
+        ));
+
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let output = cmd
+        .args(["--output", "json"])
+        .write_stdin("Hello, {name}")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        report["placeholders"],
+        serde_json::json!([{"kind": "missing", "placeholder": "{name}"}])
+    );
+}
+
+#[test]
+fn test_strict_fails_before_writing() {
+    let upstream = upstream();
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let file = temp_dir.path().join("messages.txt");
+    std::fs::write(&file, "Hello, {name}").unwrap();
+
+    cmd.args(["--strict", "-w"])
+        .arg(&file)
+        .assert()
+        .code(exitcode::DATAERR)
+        .stderr(predicate::str::contains("missing {name}"));
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "Hello, {name}");
+}
+
+#[test]
+fn test_fix_placeholders_retries_and_caches_the_fix() {
+    let upstream = upstream();
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let file = temp_dir.path().join("messages.txt");
+    std::fs::write(&file, "Hello, {name}").unwrap();
+
+    cmd.args(["--no-color", "--fix-placeholders", "--strict"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout("こんにちは、{name}\n")
+        .stderr(predicate::str::contains("retrying"));
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 2);
+    let retry_prompt = requests[1]["messages"][0]["content"].as_str().unwrap();
+    assert!(retry_prompt.contains("(missing {name})"));
+
+    // The corrected translation replaces the cached one
+    let (mut second, _unused) = tl_with_endpoint(&upstream.endpoint);
+    second
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_CACHE_HOME", temp_dir.path().join("cache"))
+        .args(["--strict"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout("こんにちは、{name}");
+    assert_eq!(upstream.requests().len(), 2);
+}
+
+#[test]
+fn test_fix_placeholders_ndjson_emits_retry_event() {
+    let upstream = upstream();
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let output = cmd
+        .args(["--fix-placeholders", "--stream-format", "ndjson"])
+        .write_stdin("Hello, {name}")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let events: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let kinds: Vec<&str> = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["start", "delta", "retry", "delta", "done"]);
+    assert_eq!(events[2]["placeholders"][0]["placeholder"], "{name}");
+    assert_eq!(events[3]["text"], "こんにちは、{name}");
+    assert_eq!(events[4]["usage"]["total_tokens"], 23);
+    assert!(events[4].get("placeholders").is_none());
+}
+
+#[test]
+fn test_strict_fails_each_target() {
+    let upstream = upstream();
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.args(["--strict", "-t", "ja,zh"])
+        .write_stdin("Hello, {name}")
+        .assert()
+        .code(exitcode::DATAERR)
+        .stderr(predicate::str::contains("2 of 2 translations failed"));
+}
+
+#[test]
+fn test_fix_placeholders_retries_only_broken_paragraphs() {
+    let upstream = MockUpstream::start_with(|request| {
+        let input = request["messages"][1]["content"].as_str().unwrap();
+        let prompt = request["messages"][0]["content"].as_str().unwrap();
+        let text = if prompt.contains("broke placeholders") {
+            "こんにちは、{name}"
+        } else if input.starts_with("# Title") {
+            "# タイトル\n\nこんにちは\n\nさようなら"
+        } else {
+            "unexpected"
+        };
+        MockResponse::Stream(vec![text.to_string()])
+    });
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.args(["--no-color", "--fix-placeholders", "--strict"])
+        .write_stdin("# Title\n\nHello, {name}\n\nBye")
+        .assert()
+        .success()
+        .stdout("# タイトル\n\nこんにちは、{name}\n\nさようなら\n");
+
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1]["messages"][1]["content"], "Hello, {name}");
+}
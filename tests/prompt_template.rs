@@ -0,0 +1,141 @@
+#![allow(clippy::unwrap_used)]
+//! Custom system prompt template tests.
+
+mod common;
+
+use common::{MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+
+fn config_path(temp_dir: &tempfile::TempDir) -> std::path::PathBuf {
+    temp_dir
+        .path()
+        .join("config")
+        .join("tl")
+        .join("config.toml")
+}
+
+/// Applies `edit` to the config file written by `tl_with_endpoint`.
+fn edit_config(temp_dir: &tempfile::TempDir, edit: impl FnOnce(String) -> String) {
+    let path = config_path(temp_dir);
+    let config = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, edit(config)).unwrap();
+}
+
+fn set_global_template(temp_dir: &tempfile::TempDir, template: &str) {
+    edit_config(temp_dir, |config| {
+        config.replace(
+            "to = \"ja\"",
+            &format!("to = \"ja\"\nprompt_template = \"{template}\""),
+        )
+    });
+}
+
+fn system_prompts(upstream: &MockUpstream) -> Vec<String> {
+    upstream
+        .requests()
+        .iter()
+        .map(|request| {
+            request["messages"][0]["content"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn test_global_template_replaces_built_in_prompt() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    set_global_template(
+        &temp_dir,
+        "Render {source_language} text as {target_language}.",
+    );
+
+    cmd.args(["--from", "en"])
+        .write_stdin("Hello")
+        .assert()
+        .success();
+
+    assert_eq!(
+        system_prompts(&upstream),
+        ["Render English text as Japanese."]
+    );
+}
+
+#[test]
+fn test_provider_and_style_templates_take_precedence() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    set_global_template(&temp_dir, "Global {target_language}.");
+    edit_config(&temp_dir, |config| {
+        format!(
+            "{}\n{}",
+            config.replace(
+                "models = [\"test-model\"]",
+                "models = [\"test-model\"]\nprompt_template = \"Provider {target_language}.\"",
+            ),
+            "[styles.terse]\ndescription = \"Terse\"\nprompt = \"Be terse.\"\n\
+             prompt_template = \"Style {target_language}.\"\n"
+        )
+    });
+
+    cmd.write_stdin("Hello").assert().success();
+
+    let mut cmd = tl_with_endpoint(&upstream.endpoint).0;
+    cmd.env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_CACHE_HOME", temp_dir.path().join("cache"))
+        .args(["--style", "terse"])
+        .write_stdin("Hello")
+        .assert()
+        .success();
+
+    assert_eq!(
+        system_prompts(&upstream),
+        ["Provider Japanese.", "Style Japanese. Be terse."]
+    );
+}
+
+#[test]
+fn test_changing_template_invalidates_cache() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    set_global_template(&temp_dir, "First {target_language}.");
+    cmd.write_stdin("Hello").assert().success();
+
+    let rerun = || {
+        let mut cmd = tl_with_endpoint(&upstream.endpoint).0;
+        cmd.env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+            .env("XDG_CACHE_HOME", temp_dir.path().join("cache"))
+            .write_stdin("Hello")
+            .assert()
+            .success();
+    };
+
+    // Same template: served from the cache
+    rerun();
+    assert_eq!(upstream.requests().len(), 1);
+
+    edit_config(&temp_dir, |config| config.replace("First", "Second"));
+    rerun();
+    assert_eq!(
+        system_prompts(&upstream),
+        ["First Japanese.", "Second Japanese."]
+    );
+}
+
+#[test]
+fn test_unknown_placeholder_is_config_error() {
+    let upstream = MockUpstream::start(&["unused"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    set_global_template(&temp_dir, "Translate to {target}.");
+
+    cmd.write_stdin("Hello")
+        .assert()
+        .failure()
+        .code(exitcode::CONFIG)
+        .stderr(predicate::str::contains("Unknown placeholder '{target}'"))
+        .stderr(predicate::str::contains("[tl]"));
+
+    assert!(upstream.requests().is_empty());
+}
@@ -0,0 +1,199 @@
+#![allow(clippy::unwrap_used)]
+//! Tests for the OpenAI-compatible caching proxy (`tl proxy`).
+//!
+//! The proxy runs in-process on a random port in front of mock upstreams.
+
+mod common;
+
+use common::{MockResponse, MockUpstream};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tl_cli::cache::CacheManager;
+use tl_cli::config::ConfigFile;
+use tl_cli::server::{self, proxy};
+
+fn config(default_endpoint: &str, other_endpoint: &str) -> ConfigFile {
+    toml::from_str(&format!(
+        r#"
+[tl]
+provider = "default"
+
+[providers.default]
+endpoint = "{default_endpoint}"
+models = ["test-model"]
+
+[providers.other]
+endpoint = "{other_endpoint}"
+models = ["other-model"]
+"#
+    ))
+    .unwrap()
+}
+
+/// Starts the proxy with a fresh cache and returns its base URL.
+async fn start_proxy(config: ConfigFile, cache_dir: &tempfile::TempDir) -> String {
+    let cache = CacheManager::with_path(&cache_dir.path().join("translations.db")).unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let state = proxy::ProxyState::new(config, None, Some(cache));
+    tokio::spawn(server::serve(listener, proxy::router(Arc::new(state))));
+    base
+}
+
+fn chat_request(stream: bool) -> Value {
+    json!({
+        "model": "test-model",
+        "messages": [{ "role": "user", "content": "Say hi" }],
+        "stream": stream,
+    })
+}
+
+/// Concatenates the content deltas of an SSE chat completion stream.
+fn sse_content(body: &str) -> String {
+    assert!(body.trim_end().ends_with("data: [DONE]"), "{body}");
+    body.lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .filter(|data| *data != "[DONE]")
+        .filter_map(|data| {
+            let chunk: Value = serde_json::from_str(data).unwrap();
+            chunk["choices"][0]["delta"]["content"]
+                .as_str()
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+async fn post(base: &str, body: &Value) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("{base}/v1/chat/completions"))
+        .json(body)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_streamed_response_is_forwarded_and_replayed_from_cache() {
+    let upstream = MockUpstream::start(&["Hi", " there"]);
+    let cache_dir = tempfile::tempdir().unwrap();
+    let base = start_proxy(config(&upstream.endpoint, "http://127.0.0.1:9"), &cache_dir).await;
+
+    let first = post(&base, &chat_request(true)).await;
+    assert_eq!(first.status(), 200);
+    assert_eq!(first.headers()["x-tl-cache"], "miss");
+    assert_eq!(sse_content(&first.text().await.unwrap()), "Hi there");
+
+    let second = post(&base, &chat_request(true)).await;
+    assert_eq!(second.status(), 200);
+    assert_eq!(second.headers()["x-tl-cache"], "hit");
+    assert!(
+        second.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/event-stream")
+    );
+    assert_eq!(sse_content(&second.text().await.unwrap()), "Hi there");
+
+    // The request body is forwarded unchanged, and only once
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0], chat_request(true));
+}
+
+#[tokio::test]
+async fn test_replayed_stream_reports_usage_when_asked() {
+    let upstream = MockUpstream::start(&["Hi"]);
+    let cache_dir = tempfile::tempdir().unwrap();
+    let base = start_proxy(config(&upstream.endpoint, "http://127.0.0.1:9"), &cache_dir).await;
+
+    let mut request = chat_request(true);
+    request["stream_options"] = json!({ "include_usage": true });
+    post(&base, &request).await.text().await.unwrap();
+
+    let replayed = post(&base, &request).await;
+    assert_eq!(replayed.headers()["x-tl-cache"], "hit");
+    let body = replayed.text().await.unwrap();
+    assert_eq!(sse_content(&body), "Hi");
+    let usage: Vec<Value> = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .filter(|data| *data != "[DONE]")
+        .map(|data| serde_json::from_str::<Value>(data).unwrap())
+        .filter(|chunk| !chunk["usage"].is_null())
+        .collect();
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0]["usage"]["total_tokens"], 0);
+    assert_eq!(upstream.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_non_streamed_response_is_cached_for_both_modes() {
+    let upstream = MockUpstream::start_with(|_| {
+        MockResponse::Json(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "model": "test-model",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": "Hello!" },
+                "finish_reason": "stop",
+            }],
+        }))
+    });
+    let cache_dir = tempfile::tempdir().unwrap();
+    let base = start_proxy(config(&upstream.endpoint, "http://127.0.0.1:9"), &cache_dir).await;
+
+    let first: Value = post(&base, &chat_request(false))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(first["id"], "chatcmpl-1");
+
+    let replayed = post(&base, &chat_request(false)).await;
+    assert_eq!(replayed.headers()["x-tl-cache"], "hit");
+    let replayed: Value = replayed.json().await.unwrap();
+    assert_eq!(replayed["object"], "chat.completion");
+    assert_eq!(replayed["choices"][0]["message"]["content"], "Hello!");
+
+    // A streaming request for the same conversation replays as SSE
+    let streamed = post(&base, &chat_request(true)).await;
+    assert_eq!(streamed.headers()["x-tl-cache"], "hit");
+    assert_eq!(sse_content(&streamed.text().await.unwrap()), "Hello!");
+
+    assert_eq!(upstream.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_upstream_errors_pass_through_and_are_not_cached() {
+    let upstream = MockUpstream::start_with_status(429, r#"{"error":"slow down"}"#);
+    let cache_dir = tempfile::tempdir().unwrap();
+    let base = start_proxy(config(&upstream.endpoint, "http://127.0.0.1:9"), &cache_dir).await;
+
+    for _ in 0..2 {
+        let response = post(&base, &chat_request(true)).await;
+        assert_eq!(response.status(), 429);
+        assert_eq!(response.text().await.unwrap(), r#"{"error":"slow down"}"#);
+    }
+    assert_eq!(upstream.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_provider_is_selected_by_model() {
+    let default = MockUpstream::start(&["default"]);
+    let other = MockUpstream::start(&["other"]);
+    let cache_dir = tempfile::tempdir().unwrap();
+    let base = start_proxy(config(&default.endpoint, &other.endpoint), &cache_dir).await;
+
+    let mut request = chat_request(true);
+    request["model"] = json!("other-model");
+    let response = post(&base, &request).await;
+    assert_eq!(sse_content(&response.text().await.unwrap()), "other");
+
+    request["model"] = json!("unlisted-model");
+    let response = post(&base, &request).await;
+    assert_eq!(sse_content(&response.text().await.unwrap()), "default");
+
+    assert_eq!(default.requests().len(), 1);
+    assert_eq!(other.requests().len(), 1);
+}
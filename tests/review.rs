@@ -0,0 +1,132 @@
+#![allow(clippy::unwrap_used)]
+//! `tl review` tests.
+
+mod common;
+
+use assert_cmd::Command;
+use common::{MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+const JUDGMENT: &str = r#"```json
+{"scores": {"fluency": 5, "accuracy": 3, "terminology": 4},
+ "summary": "Mostly fine, one wrong count.",
+ "issues": [{"segment": 2, "category": "accuracy", "severity": "major",
+             "source": "twice", "translation": "一度", "comment": "Wrong count",
+             "suggestion": "二度"}]}
+```"#;
+
+/// Returns a `tl review` command for a source and its translation.
+fn review(endpoint: &str) -> (Command, TempDir) {
+    let (mut cmd, temp_dir) = tl_with_endpoint(endpoint);
+    let source = temp_dir.path().join("source.md");
+    let translated = temp_dir.path().join("translated.md");
+    std::fs::write(&source, "# Title\n\nRun it twice.\n").unwrap();
+    std::fs::write(&translated, "# タイトル\n\n一度実行する。\n").unwrap();
+    cmd.args(["--no-color", "review", "--from", "en"])
+        .arg(&source)
+        .arg(&translated);
+    (cmd, temp_dir)
+}
+
+#[test]
+fn test_review_json_output() {
+    let upstream = MockUpstream::start(&[JUDGMENT]);
+    let (mut cmd, _temp_dir) = review(&upstream.endpoint);
+
+    let output = cmd.args(["--output", "json"]).output().unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["provider"], "test");
+    assert_eq!(report["model"], "test-model");
+    assert_eq!(report["target_language"], "ja");
+    assert_eq!(report["scores"]["accuracy"], 3.0);
+    assert_eq!(report["issues"][0]["segment"], 2);
+    assert_eq!(report["issues"][0]["suggestion"], "二度");
+    assert_eq!(report["passed"], true);
+
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 1);
+    let prompt = requests[0]["messages"][0]["content"].as_str().unwrap();
+    assert!(prompt.contains("reviewer of translations from English to Japanese"));
+    assert_eq!(
+        requests[0]["messages"][1]["content"],
+        "SOURCE\n[1] # Title\n[2] Run it twice.\n\nTRANSLATION\n[1] # タイトル\n[2] 一度実行する。\n"
+    );
+}
+
+#[test]
+fn test_review_follows_configured_pair() {
+    let upstream = MockUpstream::start(&[JUDGMENT]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let path = temp_dir.path().join("config/tl/config.toml");
+    let config = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(
+        &path,
+        config.replace("to = \"ja\"", "pair = [\"ja\", \"en\"]"),
+    )
+    .unwrap();
+
+    // Japanese source, so the translation is reviewed as English
+    let source = temp_dir.path().join("source.md");
+    let translated = temp_dir.path().join("translated.md");
+    std::fs::write(&source, "これは日本語の文章です。\n").unwrap();
+    std::fs::write(&translated, "This is a Japanese sentence.\n").unwrap();
+    let output = cmd
+        .args(["review", "--output", "json"])
+        .arg(&source)
+        .arg(&translated)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["source_language"], "ja");
+    assert_eq!(report["target_language"], "en");
+    let prompt = upstream.requests()[0]["messages"][0]["content"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(prompt.contains("from Japanese to English"), "{prompt}");
+}
+
+#[test]
+fn test_review_prints_scores_and_issues() {
+    let upstream = MockUpstream::start(&[JUDGMENT]);
+    let (mut cmd, _temp_dir) = review(&upstream.endpoint);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Review by test/test-model"))
+        .stdout(predicate::str::contains("accuracy     3/5"))
+        .stdout(predicate::str::contains("Mostly fine, one wrong count."))
+        .stdout(predicate::str::contains(
+            "[2] major · accuracy: Wrong count",
+        ))
+        .stdout(predicate::str::contains("suggestion: 二度"));
+}
+
+#[test]
+fn test_review_min_score_fails() {
+    let upstream = MockUpstream::start(&[JUDGMENT]);
+    let (mut cmd, _temp_dir) = review(&upstream.endpoint);
+
+    cmd.args(["--min-score", "4"])
+        .assert()
+        .code(exitcode::DATAERR)
+        .stdout(predicate::str::contains("accuracy     3/5"))
+        .stderr(predicate::str::contains(
+            "Review scores below 4: accuracy 3",
+        ));
+}
+
+#[test]
+fn test_review_rejects_invalid_judgment() {
+    let upstream = MockUpstream::start(&["The translation looks good to me."]);
+    let (mut cmd, _temp_dir) = review(&upstream.endpoint);
+
+    cmd.assert()
+        .code(exitcode::PROTOCOL)
+        .stderr(predicate::str::contains("did not return a valid review"));
+}
@@ -0,0 +1,304 @@
+#![allow(clippy::unwrap_used)]
+//! HTTP API tests for `tl serve`.
+//!
+//! The server runs in-process on a random port and translates through a
+//! mock OpenAI-compatible upstream.
+
+mod common;
+
+use common::{MockResponse, MockUpstream};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tl_cli::cache::CacheManager;
+use tl_cli::config::ConfigFile;
+use tl_cli::server::{self, ServerState};
+
+fn config(endpoint: &str) -> ConfigFile {
+    toml::from_str(&format!(
+        r#"
+[tl]
+provider = "test"
+model = "test-model"
+to = "ja"
+
+[providers.test]
+endpoint = "{endpoint}"
+models = ["test-model"]
+
+[styles.pirate]
+description = "Pirate speak"
+prompt = "Translate like a pirate."
+"#
+    ))
+    .unwrap()
+}
+
+/// Starts the server and returns its base URL.
+async fn start_server(
+    endpoint: &str,
+    cache: Option<CacheManager>,
+    max_concurrency: usize,
+) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let state = ServerState::new(config(endpoint), cache, max_concurrency);
+    tokio::spawn(server::serve(listener, server::router(Arc::new(state))));
+    base
+}
+
+/// Parses an SSE body into `(event, data)` pairs.
+fn sse_events(body: &str) -> Vec<(String, Value)> {
+    body.split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let mut event = String::new();
+            let mut data = Value::Null;
+            for line in block.lines() {
+                if let Some(name) = line.strip_prefix("event: ") {
+                    event = name.to_string();
+                } else if let Some(json) = line.strip_prefix("data: ") {
+                    data = serde_json::from_str(json).unwrap();
+                }
+            }
+            (event, data)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_translate() {
+    let upstream = MockUpstream::start(&["こんにちは", "、世界"]);
+    let base = start_server(&upstream.endpoint, None, 4).await;
+
+    let response = reqwest::Client::new()
+        .post(format!("{base}/v1/translate"))
+        .json(&json!({ "text": "Hello, world", "style": "pirate" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["translation"], "こんにちは、世界");
+    assert_eq!(body["target_language"], "ja");
+    assert_eq!(body["provider"], "test");
+    assert_eq!(body["style"], "pirate");
+    assert_eq!(body["cached"], false);
+    assert_eq!(body["usage"]["completion_tokens"], 2);
+
+    // The custom style prompt reaches the upstream
+    let requests = upstream.requests();
+    let system = requests[0]["messages"][0]["content"].as_str().unwrap();
+    assert!(system.contains("Translate like a pirate."));
+}
+
+#[tokio::test]
+async fn test_translate_uses_cache() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let temp_dir = tempfile::tempdir().unwrap();
+    let cache = CacheManager::with_path(&temp_dir.path().join("translations.db")).unwrap();
+    let base = start_server(&upstream.endpoint, Some(cache), 4).await;
+
+    let client = reqwest::Client::new();
+    for expected_cached in [false, true] {
+        let body: Value = client
+            .post(format!("{base}/v1/translate"))
+            .json(&json!({ "text": "Hello" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["translation"], "こんにちは");
+        assert_eq!(body["cached"], expected_cached);
+    }
+    assert_eq!(upstream.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_translate_stream() {
+    let upstream = MockUpstream::start(&["こんにちは", "、世界"]);
+    let base = start_server(&upstream.endpoint, None, 4).await;
+
+    let response = reqwest::Client::new()
+        .post(format!("{base}/v1/translate/stream"))
+        .json(&json!({ "text": "Hello, world", "to": "ja" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/event-stream")
+    );
+
+    let events = sse_events(&response.text().await.unwrap());
+    let names: Vec<_> = events.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["start", "delta", "delta", "done"]);
+    assert_eq!(events[0].1["model"], "test-model");
+    assert_eq!(events[1].1["text"], "こんにちは");
+    assert_eq!(events[2].1["text"], "、世界");
+    assert_eq!(events[3].1["cached"], false);
+    assert_eq!(events[3].1["usage"]["total_tokens"], 12);
+}
+
+#[tokio::test]
+async fn test_translate_stream_upstream_error() {
+    let upstream = MockUpstream::start_with_status(500, "boom");
+    let base = start_server(&upstream.endpoint, None, 4).await;
+
+    let response = reqwest::Client::new()
+        .post(format!("{base}/v1/translate/stream"))
+        .json(&json!({ "text": "Hello" }))
+        .send()
+        .await
+        .unwrap();
+
+    let events = sse_events(&response.text().await.unwrap());
+    let names: Vec<_> = events.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["start", "error"]);
+    assert_eq!(events[1].1["error"]["code"], "network");
+    assert_eq!(events[1].1["error"]["status"], 500);
+}
+
+#[tokio::test]
+async fn test_translate_errors_map_to_http_status() {
+    let upstream = MockUpstream::start_with_status(429, "slow down");
+    let base = start_server(&upstream.endpoint, None, 4).await;
+    let client = reqwest::Client::new();
+
+    let cases = [
+        (json!({ "text": "Hello", "to": "xx" }), 400, "usage"),
+        (json!({ "text": "" }), 400, "input"),
+        (
+            json!({ "text": "Hello", "provider": "missing" }),
+            400,
+            "config",
+        ),
+        (json!({ "text": "Hello" }), 429, "rate_limit"),
+    ];
+    for (request, status, code) in cases {
+        let response = client
+            .post(format!("{base}/v1/translate"))
+            .json(&request)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), status, "{request}");
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], code, "{request}");
+    }
+}
+
+#[tokio::test]
+async fn test_malformed_body_returns_json_error() {
+    let upstream = MockUpstream::start(&["unused"]);
+    let base = start_server(&upstream.endpoint, None, 4).await;
+    let client = reqwest::Client::new();
+
+    let cases = [
+        ("/v1/translate", "{not json", 400),
+        ("/v1/translate", r#"{"to": "ja"}"#, 422),
+        ("/v1/translate/stream", r#"{"text": 42}"#, 422),
+    ];
+    for (path, request, status) in cases {
+        let response = client
+            .post(format!("{base}{path}"))
+            .header("content-type", "application/json")
+            .body(request)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), status, "{request}");
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], "usage", "{request}");
+    }
+    assert!(upstream.requests().is_empty());
+}
+
+#[tokio::test]
+async fn test_concurrency_limit() {
+    let upstream = MockUpstream::start_with(|_| {
+        std::thread::sleep(Duration::from_millis(500));
+        MockResponse::Stream(vec!["遅い".to_string()])
+    });
+    let base = start_server(&upstream.endpoint, None, 1).await;
+    let client = reqwest::Client::new();
+
+    let slow = tokio::spawn({
+        let client = client.clone();
+        let url = format!("{base}/v1/translate");
+        async move {
+            client
+                .post(url)
+                .json(&json!({ "text": "slow" }))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        }
+    });
+
+    // Wait until the first request has reached the upstream
+    while upstream.requests().is_empty() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let busy = client
+        .post(format!("{base}/v1/translate"))
+        .json(&json!({ "text": "rejected" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(busy.status(), 503);
+    let body: Value = busy.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "busy");
+
+    assert_eq!(slow.await.unwrap(), 200);
+    assert_eq!(upstream.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_list_languages_and_styles() {
+    let base = start_server("http://127.0.0.1:9", None, 4).await;
+    let client = reqwest::Client::new();
+
+    let languages: Value = client
+        .get(format!("{base}/v1/languages"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let languages = languages["languages"].as_array().unwrap();
+    assert!(
+        languages
+            .iter()
+            .any(|lang| lang["code"] == "ja" && lang["name"] == "Japanese")
+    );
+
+    let styles: Value = client
+        .get(format!("{base}/v1/styles"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let styles = styles["styles"].as_array().unwrap();
+    assert!(
+        styles
+            .iter()
+            .any(|style| style["name"] == "formal" && style["kind"] == "preset")
+    );
+    assert!(
+        styles
+            .iter()
+            .any(|style| style["name"] == "pirate" && style["kind"] == "custom")
+    );
+}
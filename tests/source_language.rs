@@ -0,0 +1,128 @@
+#![allow(clippy::unwrap_used)]
+//! Source language (`--from` and auto-detection) tests.
+
+mod common;
+
+use common::{MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+
+fn system_prompt(upstream: &MockUpstream) -> String {
+    let requests = upstream.requests();
+    requests[0]["messages"][0]["content"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_from_is_passed_to_prompt_and_report() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let output = cmd
+        .args(["--output", "json", "--from", "en"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["source_language"], "en");
+    assert!(system_prompt(&upstream).contains("The source text is in English."));
+}
+
+#[test]
+fn test_detected_source_language() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let output = cmd
+        .args(["--output", "json"])
+        .write_stdin("This is the text that you wanted to translate.")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["source_language"], "en");
+    assert!(system_prompt(&upstream).contains("The source text is in English."));
+}
+
+#[test]
+fn test_input_in_target_language_is_echoed() {
+    let upstream = MockUpstream::start(&["unused"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["--from", "ja"])
+        .write_stdin("これはすでに日本語です。")
+        .assert()
+        .success()
+        .stdout("これはすでに日本語です。")
+        .stderr(predicate::str::contains("skipping translation"));
+
+    assert!(upstream.requests().is_empty());
+}
+
+#[test]
+fn test_detected_target_language_is_still_translated() {
+    let upstream = MockUpstream::start(&["日本語では「ありがとう」は感謝を表します。"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    // Mostly English with a Japanese word; detection must not skip it
+    cmd.write_stdin("In Japanese, ありがとうございます means thank you.")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("日本語では"))
+        .stderr(predicate::str::contains("skipping translation").not());
+    assert_eq!(upstream.requests().len(), 1);
+
+    // Even text detected as Japanese is sent when --from is not given
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.write_stdin("これはすでに日本語です。")
+        .assert()
+        .success();
+    assert_eq!(upstream.requests().len(), 2);
+}
+
+#[test]
+fn test_explicit_from_overrides_detection() {
+    let upstream = MockUpstream::start(&["translated"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    // Written in kana but declared as Chinese, so it is translated
+    cmd.args(["--from", "zh"])
+        .write_stdin("これはすでに日本語です。")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("translated"));
+
+    assert_eq!(upstream.requests().len(), 1);
+}
+
+#[test]
+fn test_simplified_to_traditional_chinese_is_translated() {
+    let upstream = MockUpstream::start(&["這是中文。"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    // zh-TW implies Traditional script, so it differs from zh-Hans
+    cmd.args(["--from", "zh-Hans", "-t", "zh-TW"])
+        .write_stdin("这是中文。")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("這是中文。"))
+        .stderr(predicate::str::contains("skipping translation").not());
+
+    assert_eq!(upstream.requests().len(), 1);
+}
+
+#[test]
+fn test_invalid_from_is_usage_error() {
+    let upstream = MockUpstream::start(&["unused"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["--from", "xx"])
+        .write_stdin("Hello")
+        .assert()
+        .code(exitcode::USAGE)
+        .stderr(predicate::str::contains("Invalid language code"));
+}
@@ -0,0 +1,172 @@
+#![allow(clippy::unwrap_used)]
+//! Combined styles (`-s formal,technical`), `extends`, and style examples tests.
+
+mod common;
+
+use common::{MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+
+fn add_styles(temp_dir: &tempfile::TempDir) {
+    let path = temp_dir
+        .path()
+        .join("config")
+        .join("tl")
+        .join("config.toml");
+    let mut config = std::fs::read_to_string(&path).unwrap();
+    config.push_str(
+        r#"
+[styles.technical]
+description = "Technical writing"
+prompt = "Keep technical terms in English."
+
+[styles.docs]
+description = "Documentation"
+prompt = "Keep Markdown headings short."
+extends = "technical"
+"#,
+    );
+    std::fs::write(&path, config).unwrap();
+}
+
+#[test]
+fn test_combined_style_prompts_in_order() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    add_styles(&temp_dir);
+
+    let output = cmd
+        .args(["--output", "json", "-s", "formal,docs"])
+        .write_stdin("Hello")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["style"], "formal,docs");
+    let prompt = upstream.requests()[0]["messages"][0]["content"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(
+        prompt.ends_with(
+            "Use a formal, polite, business-appropriate tone. \
+             Keep technical terms in English. Keep Markdown headings short."
+        ),
+        "{prompt}"
+    );
+}
+
+#[test]
+fn test_conflicting_styles_are_rejected() {
+    let upstream = MockUpstream::start(&["unused"]);
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["-s", "casual,formal"])
+        .write_stdin("Hello")
+        .assert()
+        .failure()
+        .code(exitcode::USAGE)
+        .stderr(predicate::str::contains(
+            "Styles 'casual' and 'formal' conflict",
+        ));
+
+    assert!(upstream.requests().is_empty());
+}
+
+#[test]
+fn test_styles_show_expands_prompt() {
+    let (mut cmd, temp_dir) = tl_with_endpoint("http://127.0.0.1:9");
+    add_styles(&temp_dir);
+
+    cmd.args(["styles", "show", "docs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("technical + docs"))
+        .stdout(predicate::str::contains(
+            "Keep technical terms in English. Keep Markdown headings short.",
+        ));
+}
+
+#[test]
+fn test_style_examples_are_sent_as_prior_turns() {
+    let upstream = MockUpstream::start(&["こんにちは"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    add_styles(&temp_dir);
+    let path = temp_dir
+        .path()
+        .join("config")
+        .join("tl")
+        .join("config.toml");
+    let mut config = std::fs::read_to_string(&path).unwrap();
+    config.push_str(
+        r#"
+[[styles.technical.examples]]
+source_language = "en"
+target_language = "ja"
+source = "Click Save."
+target = "「保存」をクリックします。"
+
+[[styles.technical.examples]]
+source_language = "en"
+target_language = "de"
+source = "Click Save."
+target = "Klicken Sie auf „Speichern“."
+"#,
+    );
+    std::fs::write(&path, config).unwrap();
+
+    // Only the example into the target language is sent
+    cmd.args(["-s", "docs", "--from", "en"])
+        .write_stdin("Hello")
+        .assert()
+        .success();
+
+    let messages = upstream.requests()[0]["messages"].clone();
+    let turns: Vec<(&str, &str)> = messages
+        .as_array()
+        .unwrap()
+        .iter()
+        .skip(1)
+        .map(|m| (m["role"].as_str().unwrap(), m["content"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        turns,
+        [
+            ("user", "Click Save."),
+            ("assistant", "「保存」をクリックします。"),
+            ("user", "Hello"),
+        ]
+    );
+}
+
+#[test]
+fn test_style_example_with_invalid_language_is_rejected() {
+    let upstream = MockUpstream::start(&["unused"]);
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    add_styles(&temp_dir);
+    let path = temp_dir
+        .path()
+        .join("config")
+        .join("tl")
+        .join("config.toml");
+    let mut config = std::fs::read_to_string(&path).unwrap();
+    config.push_str(
+        r#"
+[[styles.technical.examples]]
+source_language = "en"
+target_language = "xx"
+source = "Click Save."
+target = "?"
+"#,
+    );
+    std::fs::write(&path, config).unwrap();
+
+    cmd.args(["-s", "technical"])
+        .write_stdin("Hello")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Style 'technical' has an example with an invalid language 'xx'",
+        ));
+    assert!(upstream.requests().is_empty());
+}
@@ -0,0 +1,132 @@
+#![allow(clippy::unwrap_used)]
+//! `tl styles export` / `tl styles import` tests.
+
+mod common;
+
+use common::tl_with_endpoint;
+use predicates::prelude::*;
+
+const ENDPOINT: &str = "http://127.0.0.1:9";
+
+fn config_path(temp_dir: &tempfile::TempDir) -> std::path::PathBuf {
+    temp_dir
+        .path()
+        .join("config")
+        .join("tl")
+        .join("config.toml")
+}
+
+fn add_styles(temp_dir: &tempfile::TempDir, styles: &str) {
+    let path = config_path(temp_dir);
+    let mut config = std::fs::read_to_string(&path).unwrap();
+    config.push_str(styles);
+    std::fs::write(&path, config).unwrap();
+}
+
+#[test]
+fn test_export_then_import_into_another_config() {
+    let (mut export, source_dir) = tl_with_endpoint(ENDPOINT);
+    add_styles(
+        &source_dir,
+        r#"
+[styles.technical]
+description = "Technical"
+prompt = "Keep technical terms."
+
+[styles.docs]
+description = "Docs"
+prompt = "Keep headings short."
+extends = "formal,technical"
+
+[styles.unrelated]
+description = "Unrelated"
+prompt = "Not exported."
+"#,
+    );
+
+    let output = export.args(["styles", "export", "docs"]).output().unwrap();
+    assert!(output.status.success());
+    let pack = String::from_utf8(output.stdout).unwrap();
+    assert!(pack.contains("[styles.docs]"));
+    assert!(pack.contains("[styles.technical]"));
+    assert!(!pack.contains("unrelated"));
+
+    let (mut import, target_dir) = tl_with_endpoint(ENDPOINT);
+    let pack_path = target_dir.path().join("pack.toml");
+    std::fs::write(&pack_path, &pack).unwrap();
+    import
+        .args(["styles", "import", pack_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added"))
+        .stdout(predicate::str::contains("docs, technical"));
+
+    let config = std::fs::read_to_string(config_path(&target_dir)).unwrap();
+    assert!(config.contains("[styles.docs]"));
+    assert!(config.contains("extends = \"formal,technical\""));
+}
+
+#[test]
+fn test_import_reports_conflicts_unless_overwrite() {
+    let (mut cmd, temp_dir) = tl_with_endpoint(ENDPOINT);
+    add_styles(
+        &temp_dir,
+        "\n[styles.brand]\ndescription = \"Brand\"\nprompt = \"Old voice.\"\n",
+    );
+    let pack_path = temp_dir.path().join("pack.toml");
+    std::fs::write(
+        &pack_path,
+        "[styles.brand]\ndescription = \"Brand\"\nprompt = \"New voice.\"\n\n\
+         [styles.extra]\ndescription = \"Extra\"\nprompt = \"Extra.\"\n",
+    )
+    .unwrap();
+    let pack_arg = pack_path.to_str().unwrap();
+
+    cmd.args(["styles", "import", pack_arg])
+        .assert()
+        .failure()
+        .code(exitcode::USAGE)
+        .stderr(predicate::str::contains("already exist"))
+        .stderr(predicate::str::contains("brand"));
+    let config = std::fs::read_to_string(config_path(&temp_dir)).unwrap();
+    assert!(config.contains("Old voice."));
+    assert!(!config.contains("extra"));
+
+    let (mut cmd, _) = tl_with_endpoint(ENDPOINT);
+    cmd.env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .args(["styles", "import", pack_arg, "--overwrite"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("Added .*extra").unwrap())
+        .stdout(predicate::str::is_match("Replaced .*brand").unwrap());
+    let config = std::fs::read_to_string(config_path(&temp_dir)).unwrap();
+    assert!(config.contains("New voice."));
+}
+
+#[test]
+fn test_import_rejects_invalid_keys() {
+    let (mut cmd, temp_dir) = tl_with_endpoint(ENDPOINT);
+    let pack_path = temp_dir.path().join("pack.toml");
+    std::fs::write(
+        &pack_path,
+        "[styles.bad-key]\ndescription = \"Bad\"\nprompt = \"Bad.\"\n",
+    )
+    .unwrap();
+
+    cmd.args(["styles", "import", pack_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .code(exitcode::NOINPUT)
+        .stderr(predicate::str::contains("Invalid style key 'bad-key'"));
+}
+
+#[test]
+fn test_export_rejects_presets() {
+    let (mut cmd, _temp_dir) = tl_with_endpoint(ENDPOINT);
+
+    cmd.args(["styles", "export", "casual"])
+        .assert()
+        .failure()
+        .code(exitcode::USAGE)
+        .stderr(predicate::str::contains("Cannot export preset style"));
+}
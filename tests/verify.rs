@@ -0,0 +1,98 @@
+#![allow(clippy::unwrap_used)]
+//! Back-translation (`--verify`) tests.
+
+mod common;
+
+use common::{MockResponse, MockUpstream, tl_with_endpoint};
+use predicates::prelude::*;
+
+/// Starts an upstream that translates into Japanese as `こんにちは世界`
+/// and back into English as `back`.
+fn round_trip(back: &'static str) -> MockUpstream {
+    MockUpstream::start_with(move |request| {
+        let prompt = request["messages"][0]["content"].as_str().unwrap();
+        if prompt.contains("to English") {
+            MockResponse::Stream(vec![back.to_string()])
+        } else {
+            MockResponse::Stream(vec!["こんにちは世界".to_string()])
+        }
+    })
+}
+
+#[test]
+fn test_verify_reports_similarity() {
+    let upstream = round_trip("Hello, world");
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    cmd.args(["--no-color", "--from", "en", "--verify"])
+        .write_stdin("Hello world")
+        .assert()
+        .success()
+        .stdout("こんにちは世界\n")
+        .stderr(predicate::str::contains(
+            "Back-translation similarity: 100%",
+        ));
+
+    let requests = upstream.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1]["messages"][1]["content"], "こんにちは世界");
+}
+
+#[test]
+fn test_verify_threshold_fails_and_keeps_file() {
+    let upstream = round_trip("Goodbye world");
+    let (mut cmd, temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    let file = temp_dir.path().join("doc.md");
+    std::fs::write(&file, "Hello world").unwrap();
+
+    cmd.args([
+        "--no-color",
+        "--from",
+        "en",
+        "--verify",
+        "--verify-threshold",
+        "0.8",
+        "-w",
+    ])
+    .arg(&file)
+    .assert()
+    .code(exitcode::DATAERR)
+    .stderr(predicate::str::contains("[-Hello-]{+Goodbye+} world"))
+    .stderr(predicate::str::contains(
+        "Back-translation similarity 50% is below the threshold of 80%",
+    ));
+
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "Hello world");
+}
+
+#[test]
+fn test_verify_json_output() {
+    let upstream = round_trip("Goodbye world");
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+
+    let output = cmd
+        .args(["--from", "en", "--verify", "--verify-threshold", "0.4"])
+        .args(["--output", "json"])
+        .write_stdin("Hello world")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let verification = &report["verification"];
+    assert_eq!(verification["back_translation"], "Goodbye world");
+    assert_eq!(verification["similarity"], 0.5);
+    assert_eq!(verification["threshold"], 0.4);
+    assert_eq!(verification["passed"], true);
+}
+
+#[test]
+fn test_verify_threshold_requires_verify() {
+    let upstream = round_trip("Hello world");
+    let (mut cmd, _temp_dir) = tl_with_endpoint(&upstream.endpoint);
+    cmd.args(["--verify-threshold", "0.8"])
+        .write_stdin("Hello world")
+        .assert()
+        .failure();
+    assert!(upstream.requests().is_empty());
+}